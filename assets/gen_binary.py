@@ -2,6 +2,7 @@
 Generates binary files for testing.
 """
 
+import shutil
 import struct
 
 
@@ -33,3 +34,153 @@ marker = 5 * 8
 fmt = f">i{'8s'*5}i"
 with open(filename, 'ab') as f:
     f.write(struct.pack(fmt, marker, *data, marker))
+
+
+# SPE10 summary with the PARAMS records written as doubles
+
+shutil.copyfile('SPE10.SMSPEC', 'SPE10_DOUB.SMSPEC')
+
+with open('SPE10.UNSMRY', 'rb') as f:
+    unsmry = f.read()
+
+with open('SPE10_DOUB.UNSMRY', 'wb') as f:
+    pos = 0
+    while pos < len(unsmry):
+        _, name, n_elements, type_id, _ = struct.unpack_from('>i8si4si', unsmry, pos)
+        pos += 24
+        (marker,) = struct.unpack_from('>i', unsmry, pos)
+        body = unsmry[pos + 4:pos + 4 + marker]
+        pos += marker + 8
+
+        if name == b'PARAMS  ':
+            type_id = b'DOUB'
+            values = struct.unpack(f'>{n_elements}f', body)
+            body = struct.pack(f'>{n_elements}d', *values)
+
+        f.write(struct.pack('>i8si4si', 16, name, n_elements, type_id, 16))
+        f.write(struct.pack('>i', len(body)) + body + struct.pack('>i', len(body)))
+
+
+# SPE10 summary in the formatted (ASCII) layout
+
+def read_binary_records(path):
+    with open(path, 'rb') as f:
+        data = f.read()
+
+    pos = 0
+    while pos < len(data):
+        _, name, n_elements, type_id, _ = struct.unpack_from('>i8si4si', data, pos)
+        pos += 24
+        body = b''
+        if type_id != b'MESS':
+            element_size = {b'INTE': 4, b'LOGI': 4, b'REAL': 4, b'DOUB': 8, b'CHAR': 8}[type_id]
+            while len(body) < n_elements * element_size:
+                (marker,) = struct.unpack_from('>i', data, pos)
+                body += data[pos + 4:pos + 4 + marker]
+                pos += marker + 8
+        yield name, n_elements, type_id, body
+
+
+def fortran_float(value, digits, exponent_char):
+    if value == 0.0:
+        return f'0.{"0" * digits}{exponent_char}+00'
+    mantissa, exponent = f'{abs(value):.{digits - 1}e}'.split('e')
+    exponent = int(exponent) + 1
+    sign = '-' if value < 0 else ''
+    return f'{sign}0.{mantissa.replace(".", "")}{exponent_char}{exponent:+03d}'
+
+
+def write_formatted(src, dst):
+    with open(dst, 'w') as f:
+        for name, n_elements, type_id, body in read_binary_records(src):
+            f.write(f" '{name.decode()}'{n_elements:12d} '{type_id.decode()}'\n")
+            if type_id == b'INTE':
+                tokens, per_line = [f'{v:12d}' for v in struct.unpack(f'>{n_elements}i', body)], 6
+            elif type_id == b'LOGI':
+                values = struct.unpack(f'>{n_elements}i', body)
+                tokens, per_line = ['  T' if v else '  F' for v in values], 25
+            elif type_id == b'REAL':
+                values = struct.unpack(f'>{n_elements}f', body)
+                tokens, per_line = [f'{fortran_float(v, 8, "E"):>17}' for v in values], 4
+            elif type_id == b'DOUB':
+                values = struct.unpack(f'>{n_elements}d', body)
+                tokens, per_line = [f'{fortran_float(v, 14, "D"):>23}' for v in values], 3
+            elif type_id == b'CHAR':
+                values = [body[i:i + 8].decode() for i in range(0, len(body), 8)]
+                tokens, per_line = [f" '{v}'" for v in values], 7
+            else:
+                tokens, per_line = [], 1
+            for i in range(0, len(tokens), per_line):
+                f.write(''.join(tokens[i:i + per_line]) + '\n')
+
+
+write_formatted('SPE10.SMSPEC', 'SPE10.FSMSPEC')
+write_formatted('SPE10.UNSMRY', 'SPE10.FUNSMRY')
+
+
+# SPE10 summary with 12-character well names stored in a C020 NAMES record
+
+with open('SPE10_LONG.SMSPEC', 'wb') as dst:
+    for name, n_elements, type_id, body in read_binary_records('SPE10.SMSPEC'):
+        if name == b'WGNAMES ':
+            names = [body[i:i + 8].decode().strip() for i in range(0, len(body), 8)]
+            names = [f'{n}_WELL_NAME' if n.startswith(('P', 'I')) else n for n in names]
+            name, type_id = b'NAMES   ', b'C020'
+            body = b''.join(n.ljust(20).encode() for n in names)
+
+        element_size = {b'CHAR': 8, b'C020': 20, b'DOUB': 8}.get(type_id, 4)
+        block_length = 105 if type_id in (b'CHAR', b'C020') else 1000
+        dst.write(struct.pack('>i8si4si', 16, name, n_elements, type_id, 16))
+        for i in range(0, n_elements, block_length):
+            block = body[i * element_size:(i + block_length) * element_size]
+            dst.write(struct.pack('>i', len(block)) + block + struct.pack('>i', len(block)))
+
+shutil.copyfile('SPE10.UNSMRY', 'SPE10_LONG.UNSMRY')
+
+
+# SPE10 summary with some of the block and well items in a local grid refinement LGR1
+
+def write_binary_record(dst, name, n_elements, type_id, body):
+    element_size = {b'CHAR': 8, b'C020': 20, b'DOUB': 8}.get(type_id, 4)
+    block_length = 105 if type_id in (b'CHAR', b'C020') else 1000
+    dst.write(struct.pack('>i8si4si', 16, name, n_elements, type_id, 16))
+    for i in range(0, n_elements, block_length):
+        block = body[i * element_size:(i + block_length) * element_size]
+        dst.write(struct.pack('>i', len(block)) + block + struct.pack('>i', len(block)))
+
+
+local_items = {
+    (b'BPR     ', b':+:+:+:+', 120910): (b'LBPR    ', (1, 1, 1)),
+    (b'BPR     ', b':+:+:+:+', 197450): (b'LBPR    ', (2, 2, 1)),
+    (b'WBHP    ', b'P1      ', 1): (b'LWBHP   ', (0, 0, 0)),
+    (b'WOPR    ', b'P1      ', 1): (b'LCOPR   ', (1, 1, 1)),
+}
+
+records = list(read_binary_records('SPE10.SMSPEC'))
+bodies = {name: body for name, _, _, body in records}
+n_items = len(bodies[b'KEYWORDS']) // 8
+items = [
+    (bodies[b'KEYWORDS'][8 * i:8 * i + 8], bodies[b'WGNAMES '][8 * i:8 * i + 8],
+     struct.unpack_from('>i', bodies[b'NUMS    '], 4 * i)[0])
+    for i in range(n_items)
+]
+
+keywords, lgrs, cells = b'', b'', []
+for item in items:
+    keyword, cell = local_items.get(item, (item[0], (0, 0, 0)))
+    keywords += keyword
+    lgrs += b'LGR1    ' if item in local_items else b' ' * 8
+    cells.append(cell)
+
+with open('SPE10_LGR.SMSPEC', 'wb') as dst:
+    for name, n_elements, type_id, body in records:
+        if name == b'KEYWORDS':
+            body = keywords
+        write_binary_record(dst, name, n_elements, type_id, body)
+        if name == b'NUMS    ':
+            write_binary_record(dst, b'LGRS    ', n_items, b'CHAR', lgrs)
+            for axis, axis_name in enumerate([b'NUMLX   ', b'NUMLY   ', b'NUMLZ   ']):
+                coords = [cell[axis] for cell in cells]
+                write_binary_record(dst, axis_name, n_items, b'INTE', struct.pack(f'>{n_items}i', *coords))
+
+shutil.copyfile('SPE10.UNSMRY', 'SPE10_LGR.UNSMRY')
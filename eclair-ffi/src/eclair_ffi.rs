@@ -1,7 +1,8 @@
 use eclair::{
     error::EclairError,
-    summary::{ItemId as EclItemId, ItemQualifier as EclQualifier},
-    summary_manager::SummaryManager as EclSM,
+    summary::{ItemId as EclItemId, ItemQualifier as EclQualifier, UnitSystem as EclUnitSystem},
+    summary_manager::{SummaryManager as EclSM, SummarySourceStatus as EclSourceStatus},
+    zmq::{ZmqAuth as EclZmqAuth, ZmqSocketKind},
 };
 
 #[cxx::bridge(namespace = "eclair")]
@@ -16,17 +17,64 @@ mod ffi {
         CrossRegionFlow,
         Well,
         Completion,
+        Segment,
         Group,
+        Node,
         Block,
+        LocalWell,
+        LocalCompletion,
+        LocalBlock,
         Unrecognized,
     }
 
+    pub(crate) enum UnitSystem {
+        Unknown,
+        Metric,
+        Field,
+        Lab,
+        PvtM,
+    }
+
     #[derive(PartialEq, Eq, PartialOrd, Ord)]
     pub(crate) struct ItemId {
         name: String,
         qualifier: ItemQualifier,
         index: i32,
         wg_name: String,
+        /// Local grid name of local items, empty for others
+        lgr: String,
+        /// Grid coordinates of block and completion cells, -1 if unknown. Local items have the
+        /// coordinates in their local grid
+        i: i32,
+        j: i32,
+        k: i32,
+    }
+
+    /// Grid coordinates of a cell
+    pub(crate) struct CellIjk {
+        i: i32,
+        j: i32,
+        k: i32,
+    }
+
+    pub(crate) struct SummarySourceStatus {
+        failed: bool,
+        disconnected: bool,
+        stale: bool,
+        finished: bool,
+        error: String,
+    }
+
+    /// Z85-encoded CURVE keys. An empty server key means no authentication.
+    pub(crate) struct ZmqAuth {
+        server_public_key: String,
+        client_public_key: String,
+        client_secret_key: String,
+    }
+
+    pub(crate) struct RefreshDelta {
+        summary_index: usize,
+        new_steps: usize,
     }
 
     extern "Rust" {
@@ -42,20 +90,41 @@ mod ffi {
             server: &str,
             port: i32,
             identity: &str,
+            topic: &str,
+            auth: &ZmqAuth,
             name: &str,
         ) -> Result<()>;
 
         fn remove(&mut self, index: usize) -> Result<()>;
+        fn remove_by_name(&mut self, name: &str) -> Result<()>;
+
+        fn refresh(&mut self) -> Result<usize>;
+
+        fn refresh_deltas(&mut self) -> Result<Vec<RefreshDelta>>;
 
-        fn refresh(&mut self) -> Result<bool>;
+        fn health(&self, summary_idx: usize) -> SummarySourceStatus;
 
         fn length(&self) -> usize;
 
         fn summary_name(&self, index: usize) -> &str;
 
+        fn index_of(&self, name: &str) -> isize;
+
         fn all_item_ids(&self) -> Vec<ItemId>;
 
-        // TODO: Units.
+        fn query(
+            &self,
+            summary_idx: usize,
+            keyword_glob: &str,
+            qualifier_glob: &str,
+        ) -> Vec<ItemId>;
+
+        fn units_system(&self, summary_idx: usize) -> UnitSystem;
+
+        fn simulator_id(&self, summary_idx: usize) -> i32;
+
+        unsafe fn item_unit<'a>(&'a self, summary_idx: usize, id: &'_ ItemId) -> &'a str;
+
         unsafe fn timestamps<'a>(&'a self, summary_idx: usize) -> &'a [i64];
 
         unsafe fn time_item<'a>(&'a self, summary_idx: usize, name: &'_ str) -> &'a [f32];
@@ -92,6 +161,13 @@ mod ffi {
             group_name: &'_ str,
         ) -> &'a [f32];
 
+        unsafe fn node_item<'a>(
+            &'a self,
+            summary_idx: usize,
+            name: &'_ str,
+            node_name: &'_ str,
+        ) -> &'a [f32];
+
         unsafe fn region_item<'a>(
             &'a self,
             summary_idx: usize,
@@ -113,11 +189,45 @@ mod ffi {
             well_name: &'_ str,
             index: i32,
         ) -> &'a [f32];
+
+        unsafe fn segment_item<'a>(
+            &'a self,
+            summary_idx: usize,
+            name: &'_ str,
+            well_name: &'_ str,
+            segment: i32,
+        ) -> &'a [f32];
+
+        unsafe fn local_well_item<'a>(
+            &'a self,
+            summary_idx: usize,
+            name: &'_ str,
+            lgr_name: &'_ str,
+            well_name: &'_ str,
+        ) -> &'a [f32];
+
+        unsafe fn local_completion_item<'a>(
+            &'a self,
+            summary_idx: usize,
+            name: &'_ str,
+            lgr_name: &'_ str,
+            well_name: &'_ str,
+            cell: CellIjk,
+        ) -> &'a [f32];
+
+        unsafe fn local_block_item<'a>(
+            &'a self,
+            summary_idx: usize,
+            name: &'_ str,
+            lgr_name: &'_ str,
+            cell: CellIjk,
+        ) -> &'a [f32];
     }
 }
 
-impl From<&EclItemId> for ffi::ItemId {
-    fn from(value: &EclItemId) -> Self {
+impl ffi::ItemId {
+    /// Convert the item id, with the cell coordinates in a grid of the given dimensions.
+    fn new(value: &EclItemId, dims: [i32; 3]) -> Self {
         let name = value.name.to_string();
         let (qualifier, index, wg_name) = match &value.qualifier {
             EclQualifier::Time => (ffi::ItemQualifier::Time, -1, String::new()),
@@ -142,8 +252,19 @@ impl From<&EclItemId> for ffi::ItemId {
             EclQualifier::Completion { wg_name, index } => {
                 (ffi::ItemQualifier::Completion, *index, wg_name.to_string())
             }
+            EclQualifier::Segment { wg_name, index } => {
+                (ffi::ItemQualifier::Segment, *index, wg_name.to_string())
+            }
             EclQualifier::Group { wg_name } => (ffi::ItemQualifier::Group, -1, wg_name.to_string()),
+            EclQualifier::Node { wg_name } => (ffi::ItemQualifier::Node, -1, wg_name.to_string()),
             EclQualifier::Block { index } => (ffi::ItemQualifier::Block, *index, String::new()),
+            EclQualifier::LocalWell { wg_name, .. } => {
+                (ffi::ItemQualifier::LocalWell, -1, wg_name.to_string())
+            }
+            EclQualifier::LocalCompletion { wg_name, .. } => {
+                (ffi::ItemQualifier::LocalCompletion, -1, wg_name.to_string())
+            }
+            EclQualifier::LocalBlock { .. } => (ffi::ItemQualifier::LocalBlock, -1, String::new()),
             EclQualifier::Unrecognized { wg_name, index } => (
                 ffi::ItemQualifier::Unrecognized,
                 *index,
@@ -151,11 +272,84 @@ impl From<&EclItemId> for ffi::ItemId {
             ),
         };
 
+        let (lgr, (i, j, k)) = match &value.qualifier {
+            EclQualifier::LocalWell { lgr, .. } => (lgr.to_string(), (-1, -1, -1)),
+            EclQualifier::LocalCompletion { lgr, i, j, k, .. }
+            | EclQualifier::LocalBlock { lgr, i, j, k } => (lgr.to_string(), (*i, *j, *k)),
+            qualifier => (
+                String::new(),
+                qualifier.block_ijk(dims).unwrap_or((-1, -1, -1)),
+            ),
+        };
+
         ffi::ItemId {
             name,
             qualifier,
             index,
             wg_name,
+            lgr,
+            i,
+            j,
+            k,
+        }
+    }
+}
+
+impl From<&ffi::ItemId> for EclItemId {
+    fn from(value: &ffi::ItemId) -> Self {
+        let name = value.name.as_str().into();
+        let wg_name = value.wg_name.as_str().into();
+        let index = value.index;
+        let lgr = value.lgr.as_str().into();
+        let (i, j, k) = (value.i, value.j, value.k);
+        let qualifier = match value.qualifier {
+            ffi::ItemQualifier::Time => EclQualifier::Time,
+            ffi::ItemQualifier::Performance => EclQualifier::Performance,
+            ffi::ItemQualifier::Field => EclQualifier::Field,
+            ffi::ItemQualifier::Aquifer => EclQualifier::Aquifer { index },
+            ffi::ItemQualifier::Region => EclQualifier::Region {
+                wg_name: if value.wg_name.is_empty() {
+                    None
+                } else {
+                    Some(wg_name)
+                },
+                index,
+            },
+            ffi::ItemQualifier::CrossRegionFlow => {
+                let to = index / 32768 - 10;
+                let from = index - 32768 * (to + 10);
+                EclQualifier::CrossRegionFlow { from, to }
+            }
+            ffi::ItemQualifier::Well => EclQualifier::Well { wg_name },
+            ffi::ItemQualifier::Completion => EclQualifier::Completion { wg_name, index },
+            ffi::ItemQualifier::Segment => EclQualifier::Segment { wg_name, index },
+            ffi::ItemQualifier::Group => EclQualifier::Group { wg_name },
+            ffi::ItemQualifier::Node => EclQualifier::Node { wg_name },
+            ffi::ItemQualifier::Block => EclQualifier::Block { index },
+            ffi::ItemQualifier::LocalWell => EclQualifier::LocalWell { lgr, wg_name },
+            ffi::ItemQualifier::LocalCompletion => EclQualifier::LocalCompletion {
+                lgr,
+                wg_name,
+                i,
+                j,
+                k,
+            },
+            ffi::ItemQualifier::LocalBlock => EclQualifier::LocalBlock { lgr, i, j, k },
+            _ => EclQualifier::Unrecognized { wg_name, index },
+        };
+
+        EclItemId { name, qualifier }
+    }
+}
+
+impl From<Option<EclUnitSystem>> for ffi::UnitSystem {
+    fn from(value: Option<EclUnitSystem>) -> Self {
+        match value {
+            None => ffi::UnitSystem::Unknown,
+            Some(EclUnitSystem::Metric) => ffi::UnitSystem::Metric,
+            Some(EclUnitSystem::Field) => ffi::UnitSystem::Field,
+            Some(EclUnitSystem::Lab) => ffi::UnitSystem::Lab,
+            Some(EclUnitSystem::PvtM) => ffi::UnitSystem::PvtM,
         }
     }
 }
@@ -184,12 +378,34 @@ impl SummaryManager {
         server: &str,
         port: i32,
         identity: &str,
+        topic: &str,
+        auth: &ffi::ZmqAuth,
         name: &str,
     ) -> Result<(), EclairError> {
+        // Subscribe to a publisher if a topic is given.
+        let kind = if topic.is_empty() {
+            ZmqSocketKind::Dealer
+        } else {
+            ZmqSocketKind::Sub {
+                topic: topic.to_string(),
+            }
+        };
+        // Authenticate with CURVE if the server key is given.
+        let auth = if auth.server_public_key.is_empty() {
+            None
+        } else {
+            Some(EclZmqAuth {
+                server_public_key: auth.server_public_key.clone(),
+                client_public_key: auth.client_public_key.clone(),
+                client_secret_key: auth.client_secret_key.clone(),
+            })
+        };
         self.0.add_from_network(
             server,
             port,
             identity,
+            kind,
+            auth.as_ref(),
             if name.is_empty() { None } else { Some(name) },
         )
     }
@@ -198,8 +414,73 @@ impl SummaryManager {
         self.0.remove(index)
     }
 
-    pub fn refresh(&mut self) -> Result<bool, EclairError> {
-        self.0.refresh()
+    pub fn remove_by_name(&mut self, name: &str) -> Result<(), EclairError> {
+        self.0.remove_by_name(name)
+    }
+
+    /// Total number of new time iterations across all summaries.
+    pub fn refresh(&mut self) -> Result<usize, EclairError> {
+        Ok(self
+            .0
+            .refresh_deltas()?
+            .iter()
+            .map(|delta| delta.new_steps)
+            .sum())
+    }
+
+    pub fn refresh_deltas(&mut self) -> Result<Vec<ffi::RefreshDelta>, EclairError> {
+        Ok(self
+            .0
+            .refresh_deltas()?
+            .into_iter()
+            .map(|delta| ffi::RefreshDelta {
+                summary_index: delta.summary_index,
+                new_steps: delta.new_steps,
+            })
+            .collect())
+    }
+
+    pub fn health(&self, summary_idx: usize) -> ffi::SummarySourceStatus {
+        let status = self.0.health(summary_idx).unwrap_or_else(|| {
+            EclSourceStatus::Failed(format!("No summary at index {}", summary_idx))
+        });
+        match status {
+            EclSourceStatus::Active => ffi::SummarySourceStatus {
+                failed: false,
+                disconnected: false,
+                stale: false,
+                finished: false,
+                error: String::new(),
+            },
+            EclSourceStatus::Disconnected => ffi::SummarySourceStatus {
+                failed: false,
+                disconnected: true,
+                stale: false,
+                finished: false,
+                error: String::new(),
+            },
+            EclSourceStatus::Stale => ffi::SummarySourceStatus {
+                failed: false,
+                disconnected: false,
+                stale: true,
+                finished: false,
+                error: String::new(),
+            },
+            EclSourceStatus::Failed(error) => ffi::SummarySourceStatus {
+                failed: true,
+                disconnected: false,
+                stale: false,
+                finished: false,
+                error,
+            },
+            EclSourceStatus::Finished => ffi::SummarySourceStatus {
+                failed: false,
+                disconnected: false,
+                stale: false,
+                finished: true,
+                error: String::new(),
+            },
+        }
     }
 
     pub fn length(&self) -> usize {
@@ -210,20 +491,59 @@ impl SummaryManager {
         self.0.name(index)
     }
 
+    pub fn index_of(&self, name: &str) -> isize {
+        self.0.index_of(name).map_or(-1, |index| index as isize)
+    }
+
     pub fn all_item_ids(&self) -> Vec<ffi::ItemId> {
         let mut ids: Vec<ffi::ItemId> = self
             .0
             .all_item_ids()
             .iter()
             .filter(|el| el.qualifier.is_recognized())
-            .map(|&el| el.into())
+            .map(|&el| {
+                // Summaries usually share the grid, so the first one with the item will do.
+                let dims = (0..self.0.length())
+                    .find(|&summary_idx| self.0.get(summary_idx, el).is_some())
+                    .and_then(|summary_idx| self.0.dims(summary_idx))
+                    .unwrap_or([0; 3]);
+                ffi::ItemId::new(el, dims)
+            })
             .collect();
         ids.sort();
         ids
     }
 
+    pub fn query(
+        &self,
+        summary_idx: usize,
+        keyword_glob: &str,
+        qualifier_glob: &str,
+    ) -> Vec<ffi::ItemId> {
+        self.0
+            .query(summary_idx, keyword_glob, qualifier_glob)
+            .into_iter()
+            .filter(|(id, _)| id.qualifier.is_recognized())
+            .map(|(id, _)| ffi::ItemId::new(id, self.0.dims(summary_idx).unwrap_or([0; 3])))
+            .collect()
+    }
+
+    pub fn units_system(&self, summary_idx: usize) -> ffi::UnitSystem {
+        self.0.units_system(summary_idx).into()
+    }
+
+    pub fn simulator_id(&self, summary_idx: usize) -> i32 {
+        self.0.simulator_id(summary_idx).unwrap_or(-1)
+    }
+
+    pub fn item_unit<'a>(&'a self, summary_idx: usize, id: &'_ ffi::ItemId) -> &'a str {
+        self.0
+            .item_unit(summary_idx, &id.into())
+            .unwrap_or_default()
+    }
+
     pub fn timestamps(&self, summary_idx: usize) -> &[i64] {
-        self.0.timestamps(summary_idx)
+        self.0.timestamps(summary_idx).unwrap_or_default()
     }
 
     pub fn time_item<'a>(&'a self, summary_idx: usize, name: &'_ str) -> &'a [f32] {
@@ -274,6 +594,17 @@ impl SummaryManager {
             .unwrap_or_default()
     }
 
+    pub fn node_item<'a>(
+        &'a self,
+        summary_idx: usize,
+        name: &'_ str,
+        node_name: &'_ str,
+    ) -> &'a [f32] {
+        self.0
+            .node_item(summary_idx, name, node_name)
+            .unwrap_or_default()
+    }
+
     pub fn region_item<'a>(&'a self, summary_idx: usize, name: &'_ str, index: i32) -> &'a [f32] {
         self.0
             .region_item(summary_idx, name, index)
@@ -304,4 +635,59 @@ impl SummaryManager {
             .completion_item(summary_idx, name, well_name, index)
             .unwrap_or_default()
     }
+
+    pub fn segment_item<'a>(
+        &'a self,
+        summary_idx: usize,
+        name: &'_ str,
+        well_name: &'_ str,
+        segment: i32,
+    ) -> &'a [f32] {
+        self.0
+            .segment_item(summary_idx, name, well_name, segment)
+            .unwrap_or_default()
+    }
+
+    pub fn local_well_item<'a>(
+        &'a self,
+        summary_idx: usize,
+        name: &'_ str,
+        lgr_name: &'_ str,
+        well_name: &'_ str,
+    ) -> &'a [f32] {
+        self.0
+            .local_well_item(summary_idx, name, lgr_name, well_name)
+            .unwrap_or_default()
+    }
+
+    pub fn local_completion_item<'a>(
+        &'a self,
+        summary_idx: usize,
+        name: &'_ str,
+        lgr_name: &'_ str,
+        well_name: &'_ str,
+        cell: ffi::CellIjk,
+    ) -> &'a [f32] {
+        self.0
+            .local_completion_item(
+                summary_idx,
+                name,
+                lgr_name,
+                well_name,
+                (cell.i, cell.j, cell.k),
+            )
+            .unwrap_or_default()
+    }
+
+    pub fn local_block_item<'a>(
+        &'a self,
+        summary_idx: usize,
+        name: &'_ str,
+        lgr_name: &'_ str,
+        cell: ffi::CellIjk,
+    ) -> &'a [f32] {
+        self.0
+            .local_block_item(summary_idx, name, lgr_name, (cell.i, cell.j, cell.k))
+            .unwrap_or_default()
+    }
 }
@@ -36,12 +36,29 @@ DataManager::PlotData DataManager::plot_data(size_t summary_index,
   case ItemQualifier::Completion:
     return {time, manager->completion_item(summary_index, item_id.name,
                                            item_id.wg_name, item_id.index)};
+  case ItemQualifier::Segment:
+    return {time, manager->segment_item(summary_index, item_id.name,
+                                        item_id.wg_name, item_id.index)};
   case ItemQualifier::Group:
     return {time,
             manager->group_item(summary_index, item_id.name, item_id.wg_name)};
+  case ItemQualifier::Node:
+    return {time,
+            manager->node_item(summary_index, item_id.name, item_id.wg_name)};
   case ItemQualifier::Block:
     return {time,
             manager->block_item(summary_index, item_id.name, item_id.index)};
+  case ItemQualifier::LocalWell:
+    return {time, manager->local_well_item(summary_index, item_id.name,
+                                           item_id.lgr, item_id.wg_name)};
+  case ItemQualifier::LocalCompletion:
+    return {time, manager->local_completion_item(
+                      summary_index, item_id.name, item_id.lgr,
+                      item_id.wg_name, {item_id.i, item_id.j, item_id.k})};
+  case ItemQualifier::LocalBlock:
+    return {time, manager->local_block_item(
+                      summary_index, item_id.name, item_id.lgr,
+                      {item_id.i, item_id.j, item_id.k})};
   case ItemQualifier::Unrecognized:
     throw std::runtime_error("Why would you wanna do this?");
   }
@@ -57,6 +74,7 @@ std::string DataManager::item_name_and_location(int index) const {
 
   std::string_view name = item_name(index);
   std::string_view wg_name(item_id.wg_name.data(), item_id.wg_name.size());
+  std::string_view lgr(item_id.lgr.data(), item_id.lgr.size());
 
   switch (item_id.qualifier) {
   case ItemQualifier::Time:
@@ -82,13 +100,38 @@ std::string DataManager::item_name_and_location(int index) const {
     oss << name << " @ " << wg_name;
     break;
   case ItemQualifier::Completion:
-    oss << name << " @ " << wg_name << "[" << item_id.index << "]";
+    oss << name << " @ " << wg_name;
+    if (item_id.i > 0) {
+      oss << " (" << item_id.i << ", " << item_id.j << ", " << item_id.k << ")";
+    } else {
+      oss << "[" << item_id.index << "]";
+    }
+    break;
+  case ItemQualifier::Segment:
+    oss << name << " @ " << wg_name << " #" << item_id.index;
     break;
   case ItemQualifier::Group:
+  case ItemQualifier::Node:
     oss << name << " @ " << wg_name;
     break;
   case ItemQualifier::Block:
-    oss << name << " @ " << item_id.index;
+    if (item_id.i > 0) {
+      oss << name << " @ (" << item_id.i << ", " << item_id.j << ", "
+          << item_id.k << ")";
+    } else {
+      oss << name << " @ " << item_id.index;
+    }
+    break;
+  case ItemQualifier::LocalWell:
+    oss << name << " @ " << wg_name << " in " << lgr;
+    break;
+  case ItemQualifier::LocalCompletion:
+    oss << name << " @ " << wg_name << " (" << item_id.i << ", " << item_id.j
+        << ", " << item_id.k << ") in " << lgr;
+    break;
+  case ItemQualifier::LocalBlock:
+    oss << name << " @ (" << item_id.i << ", " << item_id.j << ", "
+        << item_id.k << ") in " << lgr;
     break;
   case ItemQualifier::Unrecognized:
     oss << "Unrecognized @ " << wg_name << "[" << item_id.index << "]";
@@ -113,7 +156,7 @@ bool DataManager::names_equal(int index1, int index2) const {
 }
 
 // Refresh the time data.
-bool DataManager::refresh() { return manager->refresh(); }
+bool DataManager::refresh() { return manager->refresh() > 0; }
 
 void DataManager::draw() {
   // Draw the "Sources" first. Sources can be removed, that's why we don't draw
@@ -42,7 +42,7 @@ public:
   }
 
   void add_from_network(const std::string &server, int port) {
-    manager->add_from_network(server, port, "eclair", "");
+    manager->add_from_network(server, port, "eclair", "", eclair::ZmqAuth{}, "");
     item_ids = manager->all_item_ids();
   }
 
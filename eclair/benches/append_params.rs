@@ -0,0 +1,111 @@
+//! Appending time iterations to a summary one by one and in batches with `append_params_batch`.
+//! Run it with and without the `row_major` feature to compare the storage layouts. The summary has
+//! 100,000 time iterations of 10,000 items by default, set `ECLAIR_BENCH_STEPS` and
+//! `ECLAIR_BENCH_ITEMS` to change that.
+
+use std::ops::Range;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use eclair::{
+    records::{Record, RecordData, WriteRecord},
+    summary::{ChunkedSummaryReader, Params, Summary},
+};
+
+/// Number of time iterations appended in a batch.
+const BATCH_STEPS: usize = 100;
+
+/// A summary with the TIME item followed by block pressures, and no time iterations yet.
+fn empty_summary(n_items: usize) -> Summary {
+    let chars = |values: Vec<&str>| RecordData::Chars(values.into_iter().map(Into::into).collect());
+
+    let mut keywords = vec!["TIME"];
+    keywords.resize(n_items, "BPR");
+    let records = [
+        Record::new(
+            "DIMENS",
+            RecordData::Int(vec![n_items as i32, 10, 10, 3, 0, -1]),
+        ),
+        Record::new("STARTDAT", RecordData::Int(vec![1, 1, 2020])),
+        Record::new("KEYWORDS", chars(keywords)),
+        Record::new("WGNAMES", chars(vec![""; n_items])),
+        Record::new("NUMS", RecordData::Int((0..n_items as i32).collect())),
+        Record::new("UNITS", chars(vec![""; n_items])),
+    ];
+
+    let mut smspec = Vec::new();
+    for record in &records {
+        smspec.write_record(record).unwrap();
+    }
+    ChunkedSummaryReader::from_smspec_bytes(&smspec)
+        .unwrap()
+        .into_summary()
+}
+
+/// The values of the time iterations in the range. TIME comes first and has to increase.
+fn batch(steps: Range<usize>, row: &[f32]) -> Vec<Params> {
+    steps
+        .map(|step| {
+            let mut values = row.to_vec();
+            values[0] = step as f32;
+            Params::F32(values)
+        })
+        .collect()
+}
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn append_params(c: &mut Criterion) {
+    let n_steps = env_or("ECLAIR_BENCH_STEPS", 100_000);
+    let n_items = env_or("ECLAIR_BENCH_ITEMS", 10_000);
+    // The time iterations are generated while appending, so that they don't take as much memory
+    // as the summary. Copying a row costs far less than appending it.
+    let row: Vec<f32> = (0..n_items).map(|i| i as f32).collect();
+    let layout = if cfg!(feature = "row_major") {
+        "row-major"
+    } else {
+        "column-major"
+    };
+
+    let mut group = c.benchmark_group(format!(
+        "{} steps of {} items, {}",
+        n_steps, n_items, layout
+    ));
+    group.sample_size(10);
+    group.bench_function("one by one", |b| {
+        b.iter_batched(
+            || empty_summary(n_items),
+            |mut summary| {
+                for first in (0..n_steps).step_by(BATCH_STEPS) {
+                    for params in batch(first..n_steps.min(first + BATCH_STEPS), &row) {
+                        summary.append_params(params).unwrap();
+                    }
+                }
+                summary
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("in batches", |b| {
+        b.iter_batched(
+            || empty_summary(n_items),
+            |mut summary| {
+                for first in (0..n_steps).step_by(BATCH_STEPS) {
+                    summary
+                        .append_params_batch(batch(first..n_steps.min(first + BATCH_STEPS), &row))
+                        .unwrap();
+                }
+                summary
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, append_params);
+criterion_main!(benches);
@@ -0,0 +1,97 @@
+//! Loading a large unified summary from scratch through the buffered reader of `from_path` and
+//! through the memory map of `from_path_mmap`. The synthetic UNSMRY file is 1 GB by default, set
+//! `ECLAIR_BENCH_UNSMRY_MB` to change its size. The file is read from the page cache after the
+//! first iteration, so the numbers leave out the disk.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use eclair::{
+    records::{Record, RecordData, WriteRecord},
+    summary::{InitializeSummary, Summary, SummaryFileReader},
+};
+
+const N_ITEMS: usize = 1000;
+
+/// Write the SMSPEC file of the TIME item followed by block pressures.
+fn write_smspec(path: &Path) {
+    let chars = |values: Vec<&str>| RecordData::Chars(values.into_iter().map(Into::into).collect());
+
+    let mut keywords = vec!["TIME"];
+    keywords.resize(N_ITEMS, "BPR");
+    let mut units = vec!["DAYS"];
+    units.resize(N_ITEMS, "BARSA");
+    let records = [
+        Record::new(
+            "DIMENS",
+            RecordData::Int(vec![N_ITEMS as i32, 10, 10, 10, 0, -1]),
+        ),
+        Record::new("STARTDAT", RecordData::Int(vec![1, 1, 2020])),
+        Record::new("KEYWORDS", chars(keywords)),
+        Record::new("WGNAMES", chars(vec![""; N_ITEMS])),
+        Record::new("NUMS", RecordData::Int((0..N_ITEMS as i32).collect())),
+        Record::new("UNITS", chars(units)),
+    ];
+
+    let mut smspec = BufWriter::new(File::create(path).unwrap());
+    for record in &records {
+        smspec.write_record(record).unwrap();
+    }
+    smspec.flush().unwrap();
+}
+
+/// Write time iterations to the UNSMRY file until it holds at least `size` bytes.
+fn write_unsmry(path: &Path, size: usize) {
+    let mut unsmry = BufWriter::new(File::create(path).unwrap());
+    let mut written = 0;
+    let mut step = 0;
+    while written < size {
+        let mut values: Vec<f32> = (0..N_ITEMS).map(|i| (step + i) as f32).collect();
+        values[0] = step as f32;
+        written += unsmry
+            .write_record(&Record::new("SEQHDR", RecordData::Int(vec![0])))
+            .unwrap();
+        written += unsmry
+            .write_record(&Record::new("MINISTEP", RecordData::Int(vec![step as i32])))
+            .unwrap();
+        written += unsmry
+            .write_record(&Record::new("PARAMS", RecordData::F32(values)))
+            .unwrap();
+        step += 1;
+    }
+    unsmry.flush().unwrap();
+}
+
+fn load(reader: SummaryFileReader) -> Summary {
+    reader.init().unwrap().0
+}
+
+fn cold_load(c: &mut Criterion) {
+    let size_mb: usize = std::env::var("ECLAIR_BENCH_UNSMRY_MB")
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(1024);
+
+    let dir = tempfile::tempdir().unwrap();
+    let case = dir.path().join("LARGE");
+    write_smspec(&case.with_extension("SMSPEC"));
+    write_unsmry(&case.with_extension("UNSMRY"), size_mb << 20);
+
+    let mut group = c.benchmark_group(format!("{} MB UNSMRY", size_mb));
+    group.sample_size(10);
+    group.bench_function("from_path", |b| {
+        b.iter(|| load(SummaryFileReader::from_path(&case).unwrap()))
+    });
+    // The file isn't touched while the benchmark runs.
+    group.bench_function("from_path_mmap", |b| {
+        b.iter(|| load(unsafe { SummaryFileReader::from_path_mmap(&case).unwrap() }))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, cold_load);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+//! Reading PARAMS records through the `std::io::Read` path, which allocates a body buffer for
+//! every record, through `RecordReader`, which reuses a single one, and through `SliceReader`,
+//! which parses the records in place.
+
+use std::io::Cursor;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use eclair::records::{ReadRecord, Record, RecordData, RecordReader, SliceReader, WriteRecord};
+
+/// MINISTEP and PARAMS records of the given number of time iterations and items.
+fn unsmry_bytes(n_steps: usize, n_items: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for step in 0..n_steps {
+        let ministep = Record::new("MINISTEP", RecordData::Int(vec![step as i32]));
+        let params = Record::new("PARAMS", RecordData::F32(vec![step as f32; n_items]));
+        bytes.write_record(&ministep).unwrap();
+        bytes.write_record(&params).unwrap();
+    }
+    bytes
+}
+
+fn read_all<R: ReadRecord>(mut reader: R) -> usize {
+    let mut n_records = 0;
+    while let (_, Some(_)) = reader.read_record().unwrap() {
+        n_records += 1;
+    }
+    n_records
+}
+
+fn read_records(c: &mut Criterion) {
+    let bytes = unsmry_bytes(2000, 1000);
+
+    let mut group = c.benchmark_group("2000 steps of 1000 items");
+    group.bench_function("io::Read", |b| b.iter(|| read_all(Cursor::new(&bytes))));
+    group.bench_function("RecordReader", |b| {
+        b.iter(|| read_all(RecordReader::new(Cursor::new(&bytes))))
+    });
+    group.bench_function("SliceReader", |b| {
+        b.iter(|| read_all(SliceReader::new(&bytes)))
+    });
+    group.finish();
+
+    let spe_10 = concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/SPE10.UNSMRY");
+    let bytes = std::fs::read(spe_10).unwrap();
+
+    let mut group = c.benchmark_group("SPE10.UNSMRY");
+    group.bench_function("io::Read", |b| b.iter(|| read_all(Cursor::new(&bytes))));
+    group.bench_function("RecordReader", |b| {
+        b.iter(|| read_all(RecordReader::new(Cursor::new(&bytes))))
+    });
+    group.bench_function("SliceReader", |b| {
+        b.iter(|| read_all(SliceReader::new(&bytes)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, read_records);
+criterion_main!(benches);
@@ -0,0 +1,166 @@
+//! Export of summary data to Apache Arrow record batches.
+//!
+//! A batch holds a `TIMESTAMP` column with the datetimes of all time iterations, followed by a
+//! column per item, sorted by `ItemId`. Items listed more than once in the SMSPEC get a column per
+//! occurrence. Item columns are named by the full item name, e.g. `WBHP:P1`, and carry the item
+//! unit in the `unit` field metadata. Names that occur more than once get a `_2`, `_3`, ... suffix
+//! in the order of the columns, skipping the suffixed names that are already taken by other items.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{
+    summary::{ItemId, Summary},
+    Result,
+};
+use arrow::{
+    array::{
+        ArrayRef, Float32Array, Float64Array, TimestampMillisecondArray, TimestampSecondArray,
+    },
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    record_batch::RecordBatch,
+};
+
+pub(crate) const TIMESTAMP_COLUMN: &str = "TIMESTAMP";
+
+pub(crate) const UNIT_METADATA_KEY: &str = "unit";
+
+/// Unique column names for the items, in the given order.
+fn column_names(ids: &[&ItemId]) -> Vec<String> {
+    let names: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+
+    // Suffixed names must not collide with the name of any item, including the later ones.
+    let mut taken: HashSet<String> = names.iter().cloned().collect();
+    taken.insert(TIMESTAMP_COLUMN.to_string());
+    let mut used: HashSet<String> = HashSet::new();
+    used.insert(TIMESTAMP_COLUMN.to_string());
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    names
+        .into_iter()
+        .map(|name| {
+            if used.insert(name.clone()) {
+                return name;
+            }
+            let count = counts.entry(name.clone()).or_insert(1);
+            loop {
+                *count += 1;
+                let suffixed = format!("{}_{}", name, count);
+                if taken.insert(suffixed.clone()) {
+                    used.insert(suffixed.clone());
+                    return suffixed;
+                }
+            }
+        })
+        .collect()
+}
+
+impl Summary {
+    /// All summary data as a single Arrow record batch. Timestamps are in seconds. Item columns
+    /// hold `Float64` values for double precision data and `Float32` values otherwise.
+    pub fn to_arrow_batch(&self) -> Result<RecordBatch> {
+        self.build_record_batch(TimeUnit::Second, true)
+    }
+
+    /// All summary data as a single Arrow record batch with timestamps in milliseconds and
+    /// `Float32` values in every item column.
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        self.build_record_batch(TimeUnit::Millisecond, false)
+    }
+
+    fn build_record_batch(&self, time_unit: TimeUnit, keep_precision: bool) -> Result<RecordBatch> {
+        let mut ids: Vec<(&ItemId, usize)> = self.columns().collect();
+        ids.sort_unstable();
+
+        let names = column_names(&ids.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+
+        let timestamps: ArrayRef = match time_unit {
+            TimeUnit::Millisecond => Arc::new(TimestampMillisecondArray::from(
+                self.timestamps
+                    .iter()
+                    .map(|&ts| ts * 1000)
+                    .collect::<Vec<_>>(),
+            )),
+            _ => Arc::new(TimestampSecondArray::from(self.timestamps.clone())),
+        };
+        let mut fields = vec![Field::new(
+            TIMESTAMP_COLUMN,
+            DataType::Timestamp(time_unit, None),
+            false,
+        )];
+        let mut columns = vec![timestamps];
+
+        for ((_, index), name) in ids.iter().zip(names) {
+            let unit = self.items[*index].unit.to_string();
+            let metadata = HashMap::from([(UNIT_METADATA_KEY.to_string(), unit)]);
+            let values = self.item_column(*index);
+            if keep_precision && values.is_double_precision() {
+                fields.push(Field::new(name, DataType::Float64, false).with_metadata(metadata));
+                columns.push(Arc::new(Float64Array::from(values.as_f64_vec())));
+            } else {
+                fields.push(Field::new(name, DataType::Float32, false).with_metadata(metadata));
+                columns.push(Arc::new(Float32Array::from(values.as_f32_slice().to_vec())));
+            }
+        }
+
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{summary::ItemQualifier, FlexString};
+    use arrow::array::Array;
+
+    #[test]
+    fn duplicate_names() {
+        let rpr = |wg_name: Option<&str>| ItemId {
+            name: FlexString::from("RPR"),
+            qualifier: ItemQualifier::Region {
+                wg_name: wg_name.map(FlexString::from),
+                index: 1,
+            },
+        };
+        let (a, b) = (rpr(None), rpr(Some("NORTH")));
+        assert_eq!(column_names(&[&a, &b, &a]), ["RPR:1", "RPR:1_2", "RPR:1_3"]);
+
+        // A well named P1_2 takes the name that would otherwise go to the second WBHP:P1.
+        let wbhp = |wg_name: &str| ItemId {
+            name: FlexString::from("WBHP"),
+            qualifier: ItemQualifier::Well {
+                wg_name: FlexString::from(wg_name),
+            },
+        };
+        let (p1, p1_2) = (wbhp("P1"), wbhp("P1_2"));
+        assert_eq!(
+            column_names(&[&p1, &p1, &p1_2]),
+            ["WBHP:P1", "WBHP:P1_3", "WBHP:P1_2"]
+        );
+    }
+
+    #[test]
+    fn record_batch_spe_10_doub() {
+        let summary = Summary::open("../assets/SPE10_DOUB").unwrap();
+        let batch = summary.to_record_batch().unwrap();
+        assert_eq!(batch.num_columns(), summary.n_items() + 1);
+        assert_eq!(batch.num_rows(), summary.n_steps());
+
+        let timestamps = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(timestamps.value(1), summary.timestamps[1] * 1000);
+
+        let schema = batch.schema();
+        let field = schema.field_with_name("WBHP:P1").unwrap();
+        assert_eq!(field.data_type(), &DataType::Float32);
+        assert_eq!(field.metadata()[UNIT_METADATA_KEY], "PSIA");
+    }
+}
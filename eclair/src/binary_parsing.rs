@@ -1,19 +1,80 @@
 use crate::{error::EclairError, FlexString, Result};
 use std::{convert::TryInto, str};
 
+/// Byte order of the numbers in an Eclipse binary file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ByteOrder {
+    BigEndian,
+    LittleEndian,
+}
+
+impl ByteOrder {
+    /// Detect the byte order from the head marker of a record header, which is always equal to 16.
+    pub(crate) fn detect(input: &[u8]) -> Option<Self> {
+        let marker: [u8; 4] = input.get(..4)?.try_into().ok()?;
+        if i32::from_be_bytes(marker) == 16 {
+            Some(ByteOrder::BigEndian)
+        } else if i32::from_le_bytes(marker) == 16 {
+            Some(ByteOrder::LittleEndian)
+        } else {
+            None
+        }
+    }
+}
+
 /// Interpret a byte slice as an `i32` integer number.
-pub(crate) fn read_i32(input: &[u8]) -> i32 {
-    i32::from_be_bytes(input.try_into().unwrap())
+pub(crate) fn read_i32(input: &[u8], order: ByteOrder) -> i32 {
+    let bytes = input.try_into().unwrap();
+    match order {
+        ByteOrder::BigEndian => i32::from_be_bytes(bytes),
+        ByteOrder::LittleEndian => i32::from_le_bytes(bytes),
+    }
 }
 
 /// Interpret a byte slice as an `f32` floating point number.
-pub(crate) fn read_f32(input: &[u8]) -> f32 {
-    f32::from_be_bytes(input.try_into().unwrap())
+pub(crate) fn read_f32(input: &[u8], order: ByteOrder) -> f32 {
+    let bytes = input.try_into().unwrap();
+    match order {
+        ByteOrder::BigEndian => f32::from_be_bytes(bytes),
+        ByteOrder::LittleEndian => f32::from_le_bytes(bytes),
+    }
 }
 
 /// Interpret a byte slice as an `f64` floating point number.
-pub(crate) fn read_f64(input: &[u8]) -> f64 {
-    f64::from_be_bytes(input.try_into().unwrap())
+pub(crate) fn read_f64(input: &[u8], order: ByteOrder) -> f64 {
+    let bytes = input.try_into().unwrap();
+    match order {
+        ByteOrder::BigEndian => f64::from_be_bytes(bytes),
+        ByteOrder::LittleEndian => f64::from_le_bytes(bytes),
+    }
+}
+
+/// Append an `i32` integer number to the byte vector.
+pub(crate) fn write_i32(value: i32, output: &mut Vec<u8>) {
+    output.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Append an `f32` floating point number to the byte vector.
+pub(crate) fn write_f32(value: f32, output: &mut Vec<u8>) {
+    output.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Append an `f64` floating point number to the byte vector.
+pub(crate) fn write_f64(value: f64, output: &mut Vec<u8>) {
+    output.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Append a string to the byte vector, padding it with spaces to the requested width.
+pub(crate) fn write_str(value: &str, width: usize, output: &mut Vec<u8>) {
+    output.extend_from_slice(value.as_bytes());
+    output.resize(output.len() + width.saturating_sub(value.len()), b' ');
+}
+
+/// Append a single binary block to the byte vector, surrounding the data with the size markers.
+pub(crate) fn write_block(data: &[u8], output: &mut Vec<u8>) {
+    write_i32(data.len() as i32, output);
+    output.extend_from_slice(data);
+    write_i32(data.len() as i32, output);
 }
 
 /// A fallible wrapper around the byte slice's `split_at`.
@@ -35,22 +96,22 @@ pub(crate) fn take_str(size: usize, input: &[u8]) -> Result<(FlexString, &[u8])>
 }
 
 /// Take a single i32 integer from the slice front and return it along with the rest of the slice.
-pub(crate) fn take_i32(input: &[u8]) -> Result<(i32, &[u8])> {
+pub(crate) fn take_i32(input: &[u8], order: ByteOrder) -> Result<(i32, &[u8])> {
     let (left, right) = take(4, input)?;
-    Ok((read_i32(left), right))
+    Ok((read_i32(left, order), right))
 }
 
 /// Extract a single binary block from the byte slice and return it along with the rest of the slice.
 /// The surrounding size markers are excluded from the resulting slice.
-fn take_block(input: &[u8]) -> Result<(&[u8], &[u8])> {
+fn take_block(input: &[u8], order: ByteOrder) -> Result<(&[u8], &[u8])> {
     // head marker
-    let (head, input) = take_i32(input)?;
+    let (head, input) = take_i32(input, order)?;
 
     // actual data
     let (data, input) = take(head as usize, input)?;
 
     // tail marker
-    let (tail, input) = take_i32(input)?;
+    let (tail, input) = take_i32(input, order)?;
 
     if head == tail {
         Ok((data, input))
@@ -61,8 +122,12 @@ fn take_block(input: &[u8]) -> Result<(&[u8], &[u8])> {
 
 /// Extract a single binary block of the exact byte size from the byte slice and return it along
 /// with the rest of the slice. The surrounding size markers are excluded from the resulting slice.
-pub(crate) fn take_block_exact(size: usize, input: &[u8]) -> Result<(&[u8], &[u8])> {
-    take_block(input).and_then(|data| {
+pub(crate) fn take_block_exact(
+    size: usize,
+    input: &[u8],
+    order: ByteOrder,
+) -> Result<(&[u8], &[u8])> {
+    take_block(input, order).and_then(|data| {
         if data.0.len() != size {
             Err(EclairError::RecordByteLengthMismatch {
                 expected: size,
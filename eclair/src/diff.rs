@@ -0,0 +1,645 @@
+//! Numerical comparison of summaries, e.g. to check a simulator run against one or more reference
+//! runs.
+//!
+//! All summaries are resampled onto a merged time axis, which holds the time iterations of every
+//! run. Values are compared at the time iterations within the time range of both compared runs.
+//! An item passes if at every such time iteration
+//! `|candidate - reference| <= abs_tolerance + rel_tolerance * |reference|`. Items of runs with
+//! no time iterations in common fail, as nothing could be compared.
+//!
+//! Large models have thousands of items, so the comparison can be restricted to a subset of them.
+//! The report records the selection, so that its reader knows when it is partial.
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    iter,
+};
+
+use serde::Serialize;
+
+use crate::{
+    query,
+    summary::{ItemId, ItemQualifier, ResampleMethod, Summary},
+};
+
+/// Tolerances and item selection for `diff_report`.
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    /// Absolute tolerance
+    pub abs_tolerance: f64,
+
+    /// Tolerance relative to the reference value
+    pub rel_tolerance: f64,
+
+    /// Patterns of the items to compare in the `KEYWORD:QUALIFIER` notation of
+    /// `query::matches_pattern`, e.g. `FOPR`, `WBHP:*` or `RPR:3`. All items are compared if empty.
+    pub item_filter: Vec<String>,
+
+    /// Skip the simulator performance items, e.g. `TCPU` or `NEWTON`.
+    pub exclude_performance: bool,
+}
+
+impl DiffOptions {
+    fn selects(&self, id: &ItemId) -> bool {
+        if self.exclude_performance && id.qualifier == ItemQualifier::Performance {
+            return false;
+        }
+        self.item_filter.is_empty()
+            || self
+                .item_filter
+                .iter()
+                .any(|pattern| query::matches_pattern(id, pattern))
+    }
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            abs_tolerance: 0.0,
+            rel_tolerance: 1e-6,
+            item_filter: vec![],
+            exclude_performance: false,
+        }
+    }
+}
+
+/// Outcome of comparing a single item.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStatus {
+    Pass,
+    Fail,
+    /// The runs have no time iterations in common, so no values were compared
+    NoOverlap,
+    MissingInCandidate,
+    MissingInReference,
+}
+
+/// Errors of the candidate values relative to the reference ones.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DiffErrors {
+    pub max_abs_error: f64,
+    pub max_rel_error: f64,
+    pub rms_error: f64,
+
+    /// Number of time iterations at which the values were compared
+    pub n_compared: usize,
+}
+
+/// Comparison result for a single item.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemDiff {
+    pub keyword: String,
+    pub qualifier: String,
+    pub unit: String,
+    pub status: DiffStatus,
+
+    /// Absent for items missing from one of the summaries
+    pub errors: Option<DiffErrors>,
+}
+
+/// Comparison results for all the compared items, ordered by keyword and qualifier.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffReport {
+    /// Number of time iterations in the merged time axis
+    pub n_steps: usize,
+
+    /// Item patterns the comparison was restricted to, if any
+    pub item_filter: Vec<String>,
+
+    /// Whether the performance items were skipped
+    pub exclude_performance: bool,
+
+    pub items: Vec<ItemDiff>,
+}
+
+impl DiffReport {
+    /// Items that failed the comparison or are missing from one of the summaries.
+    pub fn failures(&self) -> impl Iterator<Item = &ItemDiff> {
+        self.items
+            .iter()
+            .filter(|item| item.status != DiffStatus::Pass)
+    }
+
+    pub fn n_failures(&self) -> usize {
+        self.failures().count()
+    }
+
+    /// Items present in the candidate summary only.
+    pub fn only_in_candidate(&self) -> impl Iterator<Item = &ItemDiff> {
+        self.with_status(DiffStatus::MissingInReference)
+    }
+
+    /// Items present in the reference summary only.
+    pub fn only_in_reference(&self) -> impl Iterator<Item = &ItemDiff> {
+        self.with_status(DiffStatus::MissingInCandidate)
+    }
+
+    fn with_status(&self, status: DiffStatus) -> impl Iterator<Item = &ItemDiff> {
+        self.items.iter().filter(move |item| item.status == status)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.n_failures() == 0
+    }
+
+    /// Whether only a subset of the items was compared.
+    pub fn is_partial(&self) -> bool {
+        !self.item_filter.is_empty() || self.exclude_performance
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("DiffReport is always serializable")
+    }
+}
+
+impl Display for DiffReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} of {} items failed",
+            self.n_failures(),
+            self.items.len()
+        )?;
+        if !self.item_filter.is_empty() {
+            writeln!(f, "Compared items: {}", self.item_filter.join(", "))?;
+        }
+        if self.exclude_performance {
+            writeln!(f, "Performance items are excluded")?;
+        }
+        for item in self.failures() {
+            write!(f, "{} ({}): ", item.keyword, item.qualifier)?;
+            match (&item.status, &item.errors) {
+                (DiffStatus::MissingInCandidate, _) => writeln!(f, "missing in candidate")?,
+                (DiffStatus::MissingInReference, _) => writeln!(f, "missing in reference")?,
+                (DiffStatus::NoOverlap, _) => writeln!(f, "no time iterations in common")?,
+                (_, Some(errors)) => writeln!(
+                    f,
+                    "max abs error {}, max rel error {}",
+                    errors.max_abs_error, errors.max_rel_error
+                )?,
+                (_, None) => writeln!(f, "failed")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Comparison of a candidate summary against a named reference.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReferenceDiff {
+    pub name: String,
+    pub report: DiffReport,
+}
+
+/// Comparison results of a candidate summary against several references, in the order of the
+/// references.
+#[derive(Debug, Clone, Serialize)]
+pub struct MultiDiffReport {
+    pub references: Vec<ReferenceDiff>,
+}
+
+impl MultiDiffReport {
+    pub fn n_failures(&self) -> usize {
+        self.references
+            .iter()
+            .map(|reference| reference.report.n_failures())
+            .sum()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.n_failures() == 0
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("MultiDiffReport is always serializable")
+    }
+}
+
+impl Display for MultiDiffReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for reference in &self.references {
+            writeln!(f, "Against {}:", reference.name)?;
+            write!(f, "{}", reference.report)?;
+        }
+        Ok(())
+    }
+}
+
+/// Time iterations of all the summaries.
+fn merged_time_axis(axes: &[&[i64]]) -> Vec<i64> {
+    let mut times: Vec<i64> = axes.iter().flat_map(|axis| axis.iter().copied()).collect();
+    times.sort_unstable();
+    times.dedup();
+    times
+}
+
+fn compute_errors(candidate: &[f32], reference: &[f32], opts: &DiffOptions) -> (bool, DiffErrors) {
+    let mut pass = true;
+    let mut errors = DiffErrors {
+        max_abs_error: 0.0,
+        max_rel_error: 0.0,
+        rms_error: 0.0,
+        n_compared: 0,
+    };
+
+    for (&c, &r) in candidate.iter().zip(reference) {
+        // Outside of the time range of either run.
+        if c.is_nan() || r.is_nan() {
+            continue;
+        }
+        errors.n_compared += 1;
+
+        let (c, r) = (c as f64, r as f64);
+        let abs_error = (c - r).abs();
+        let rel_error = if r != 0.0 {
+            abs_error / r.abs()
+        } else if abs_error == 0.0 {
+            0.0
+        } else {
+            f64::INFINITY
+        };
+
+        pass &= abs_error <= opts.abs_tolerance + opts.rel_tolerance * r.abs();
+        errors.max_abs_error = errors.max_abs_error.max(abs_error);
+        errors.max_rel_error = errors.max_rel_error.max(rel_error);
+        errors.rms_error += abs_error * abs_error;
+    }
+
+    if errors.n_compared == 0 {
+        return (false, errors);
+    }
+    errors.rms_error = (errors.rms_error / errors.n_compared as f64).sqrt();
+    (pass, errors)
+}
+
+/// Compare the candidate summary against the reference one.
+pub fn diff_report(candidate: &Summary, reference: &Summary, opts: DiffOptions) -> DiffReport {
+    diff_many(candidate, &[("reference", reference)], opts)
+        .references
+        .remove(0)
+        .report
+}
+
+/// Compare the candidate summary against the reference one and render the report as JSON, e.g. for
+/// CI regression gates.
+pub fn diff_json(candidate: &Summary, reference: &Summary, opts: DiffOptions) -> String {
+    diff_report(candidate, reference, opts).to_json()
+}
+
+/// Check whether every item of both summaries stays within the tolerances at all the time
+/// iterations within the time range of both runs. Items present in one summary only are ignored.
+pub fn compare(
+    candidate: &Summary,
+    reference: &Summary,
+    abs_tolerance: f64,
+    rel_tolerance: f64,
+) -> bool {
+    failing_items(candidate, reference, abs_tolerance, rel_tolerance).is_empty()
+}
+
+/// Items of both summaries that exceed the tolerances, as checked by `compare`, in sorted order.
+pub fn failing_items(
+    candidate: &Summary,
+    reference: &Summary,
+    abs_tolerance: f64,
+    rel_tolerance: f64,
+) -> Vec<ItemId> {
+    let opts = DiffOptions {
+        abs_tolerance,
+        rel_tolerance,
+        ..DiffOptions::default()
+    };
+    let (_, mut compared) = compare_many(candidate, &[reference], &opts);
+
+    let mut failures: Vec<ItemId> = compared
+        .remove(0)
+        .into_iter()
+        .filter(|(_, item)| matches!(item.status, DiffStatus::Fail | DiffStatus::NoOverlap))
+        .map(|(id, _)| id.clone())
+        .collect();
+    failures.sort();
+    failures
+}
+
+/// Compare the candidate summary against each of the named references on a common time axis.
+pub fn diff_many(
+    candidate: &Summary,
+    references: &[(&str, &Summary)],
+    opts: DiffOptions,
+) -> MultiDiffReport {
+    let summaries: Vec<&Summary> = references.iter().map(|(_, reference)| *reference).collect();
+    let (n_steps, compared) = compare_many(candidate, &summaries, &opts);
+
+    let references = references
+        .iter()
+        .zip(compared)
+        .map(|((name, _), items)| ReferenceDiff {
+            name: name.to_string(),
+            report: DiffReport {
+                n_steps,
+                item_filter: opts.item_filter.clone(),
+                exclude_performance: opts.exclude_performance,
+                items: items.into_iter().map(|(_, item)| item).collect(),
+            },
+        })
+        .collect();
+
+    MultiDiffReport { references }
+}
+
+/// Resample all the summaries onto their merged time axis and compare the candidate against each
+/// of the references. Returns the number of merged time iterations and the compared items of every
+/// reference.
+fn compare_many<'a>(
+    candidate: &'a Summary,
+    references: &[&'a Summary],
+    opts: &DiffOptions,
+) -> (usize, Vec<Vec<(&'a ItemId, ItemDiff)>>) {
+    let axes: Vec<&[i64]> = iter::once(candidate)
+        .chain(references.iter().copied())
+        .map(|summary| summary.timestamps.as_slice())
+        .collect();
+    let times = merged_time_axis(&axes);
+    let candidate_values = candidate.resample(&times, ResampleMethod::Auto);
+
+    let compared = references
+        .iter()
+        .map(|reference| {
+            let reference_values = reference.resample(&times, ResampleMethod::Auto);
+            compare_resampled(
+                (candidate, &candidate_values),
+                (reference, &reference_values),
+                opts,
+            )
+        })
+        .collect();
+
+    (times.len(), compared)
+}
+
+/// Compare the resampled values of two summaries. The items are ordered by keyword and qualifier.
+fn compare_resampled<'a>(
+    (candidate, candidate_values): (&'a Summary, &[Vec<f32>]),
+    (reference, reference_values): (&'a Summary, &[Vec<f32>]),
+    opts: &DiffOptions,
+) -> Vec<(&'a ItemId, ItemDiff)> {
+    let mut ids: HashMap<&ItemId, (Option<usize>, Option<usize>)> = HashMap::new();
+    for (id, &index) in candidate.item_ids.iter().filter(|(id, _)| opts.selects(id)) {
+        ids.entry(id).or_default().0 = Some(index);
+    }
+    for (id, &index) in reference.item_ids.iter().filter(|(id, _)| opts.selects(id)) {
+        ids.entry(id).or_default().1 = Some(index);
+    }
+
+    let mut items: Vec<(&ItemId, ItemDiff)> = ids
+        .into_iter()
+        .map(|(id, indices)| {
+            let (status, errors, unit) = match indices {
+                (Some(c), Some(r)) => {
+                    let (pass, errors) =
+                        compute_errors(&candidate_values[c], &reference_values[r], opts);
+                    let status = if pass {
+                        DiffStatus::Pass
+                    } else if errors.n_compared == 0 {
+                        DiffStatus::NoOverlap
+                    } else {
+                        DiffStatus::Fail
+                    };
+                    (status, Some(errors), &reference.items[r].unit)
+                }
+                (None, Some(r)) => (
+                    DiffStatus::MissingInCandidate,
+                    None,
+                    &reference.items[r].unit,
+                ),
+                (Some(c), None) => (
+                    DiffStatus::MissingInReference,
+                    None,
+                    &candidate.items[c].unit,
+                ),
+                (None, None) => unreachable!(),
+            };
+
+            let item = ItemDiff {
+                keyword: id.name.to_string(),
+                qualifier: id.qualifier.to_string(),
+                unit: unit.to_string(),
+                status,
+                errors,
+            };
+            (id, item)
+        })
+        .collect();
+    items.sort_by(|(_, a), (_, b)| (&a.keyword, &a.qualifier).cmp(&(&b.keyword, &b.qualifier)));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{summary::ItemQualifier, FlexString};
+
+    fn spe_10() -> Summary {
+        Summary::open("../assets/SPE10").unwrap()
+    }
+
+    #[test]
+    fn identical_summaries() {
+        let report = diff_report(&spe_10(), &spe_10(), DiffOptions::default());
+        assert_eq!(report.n_steps, 58);
+        assert_eq!(report.items.len(), 34);
+        assert!(report.passed());
+        assert!(!report.is_partial());
+        assert!(report.items.iter().all(|item| {
+            let errors = item.errors.unwrap();
+            errors.max_abs_error == 0.0 && errors.max_rel_error == 0.0
+        }));
+        assert_eq!(report.only_in_candidate().count(), 0);
+        assert_eq!(report.only_in_reference().count(), 0);
+    }
+
+    #[test]
+    fn identical_summaries_json() {
+        let json = diff_json(&spe_10(), &spe_10(), DiffOptions::default());
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let items = json["items"].as_array().unwrap();
+        assert_eq!(items.len(), 34);
+        assert!(items.iter().all(|item| {
+            item["status"] == "pass"
+                && item["errors"]["max_abs_error"] == 0.0
+                && item["errors"]["max_rel_error"] == 0.0
+        }));
+    }
+
+    #[test]
+    fn missing_items() {
+        let mut candidate = spe_10();
+        candidate.item_ids.remove(&ItemId {
+            name: FlexString::from("WBHP"),
+            qualifier: ItemQualifier::Well {
+                wg_name: FlexString::from("P1"),
+            },
+        });
+
+        let opts = DiffOptions {
+            item_filter: vec!["WBHP".to_string()],
+            ..DiffOptions::default()
+        };
+        let report = diff_report(&candidate, &spe_10(), opts);
+        assert_eq!(report.items.len(), 5);
+        assert_eq!(report.n_failures(), 1);
+
+        let failure = report.failures().next().unwrap();
+        assert_eq!(failure.qualifier, "Well P1");
+        assert_eq!(failure.status, DiffStatus::MissingInCandidate);
+        assert!(failure.errors.is_none());
+        assert_eq!(report.only_in_reference().count(), 1);
+        assert_eq!(report.only_in_candidate().count(), 0);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["items"][1]["status"], "missing_in_candidate");
+        assert_eq!(json["items"][0]["status"], "pass");
+    }
+
+    #[test]
+    fn many_references() {
+        // The shifted reference extends a day past the end of the candidate run.
+        let mut shifted = spe_10();
+        for ts in shifted.timestamps.iter_mut() {
+            *ts += 86400;
+        }
+
+        let candidate = spe_10();
+        let same = spe_10();
+        let opts = DiffOptions {
+            item_filter: vec!["FOIP".to_string()],
+            ..DiffOptions::default()
+        };
+        let report = diff_many(&candidate, &[("same", &same), ("shifted", &shifted)], opts);
+
+        let names: Vec<&str> = report.references.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["same", "shifted"]);
+        assert_eq!(report.n_failures(), 1);
+        assert!(!report.passed());
+
+        let same = &report.references[0].report;
+        assert!(same.n_steps > 58);
+        assert!(same.passed());
+        assert_eq!(same.items[0].errors.unwrap().max_abs_error, 0.0);
+
+        let shifted = &report.references[1].report;
+        assert_eq!(shifted.n_steps, same.n_steps);
+        assert!(!shifted.passed());
+        assert!(report.to_string().contains("Against shifted:"));
+    }
+
+    #[test]
+    fn tolerance_verdict() {
+        assert!(compare(&spe_10(), &spe_10(), 0.0, 0.0));
+
+        // Only FOIP differs, but WBHP:P1 is missing in the candidate.
+        let mut candidate = spe_10();
+        let foip = ItemId {
+            name: FlexString::from("FOIP"),
+            qualifier: ItemQualifier::Field,
+        };
+        let index = candidate.item_ids[&foip];
+        candidate.map_item_values(index, |value| value * 1.01);
+        candidate.item_ids.remove(&ItemId {
+            name: FlexString::from("WBHP"),
+            qualifier: ItemQualifier::Well {
+                wg_name: FlexString::from("P1"),
+            },
+        });
+
+        assert!(!compare(&candidate, &spe_10(), 0.0, 1e-3));
+        assert_eq!(failing_items(&candidate, &spe_10(), 0.0, 1e-3), [foip]);
+        assert!(compare(&candidate, &spe_10(), 0.0, 0.02));
+    }
+
+    #[test]
+    fn item_filter() {
+        let opts = DiffOptions {
+            item_filter: vec![
+                "F*".to_string(),
+                "WBHP:P*".to_string(),
+                "BPR:120910".to_string(),
+            ],
+            exclude_performance: true,
+            ..DiffOptions::default()
+        };
+        let report = diff_report(&spe_10(), &spe_10(), opts);
+        assert!(report.is_partial());
+        let names: Vec<(&str, &str)> = report
+            .items
+            .iter()
+            .map(|item| (item.keyword.as_str(), item.qualifier.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("BPR", "Block #120910"),
+                ("FGIP", "Field"),
+                ("FOIP", "Field"),
+                ("FWIP", "Field"),
+                ("WBHP", "Well P1"),
+                ("WBHP", "Well P2"),
+                ("WBHP", "Well P3"),
+                ("WBHP", "Well P4"),
+            ]
+        );
+        assert!(report
+            .to_string()
+            .contains("Compared items: F*, WBHP:P*, BPR:120910"));
+    }
+
+    #[test]
+    fn shifted_timestamps() {
+        // Shifting the candidate by a day changes the values at most time iterations.
+        let mut candidate = spe_10();
+        for ts in candidate.timestamps.iter_mut() {
+            *ts += 86400;
+        }
+
+        let opts = DiffOptions {
+            item_filter: vec!["FOIP".to_string()],
+            ..DiffOptions::default()
+        };
+        let report = diff_report(&candidate, &spe_10(), opts.clone());
+        assert!(report.n_steps > 58);
+        assert!(!report.passed());
+
+        let errors = report.items[0].errors.unwrap();
+        assert!(errors.max_abs_error > 0.0);
+        assert!(errors.rms_error <= errors.max_abs_error);
+
+        let opts = DiffOptions {
+            abs_tolerance: errors.max_abs_error,
+            ..opts
+        };
+        assert!(diff_report(&candidate, &spe_10(), opts).passed());
+    }
+
+    #[test]
+    fn no_overlap() {
+        // The candidate starts after the end of the reference run.
+        let mut candidate = spe_10();
+        for ts in candidate.timestamps.iter_mut() {
+            *ts += 100 * 365 * 86400;
+        }
+
+        let opts = DiffOptions {
+            item_filter: vec!["FOIP".to_string()],
+            abs_tolerance: f64::INFINITY,
+            ..DiffOptions::default()
+        };
+        let report = diff_report(&candidate, &spe_10(), opts);
+        assert!(!report.passed());
+        assert_eq!(report.items[0].status, DiffStatus::NoOverlap);
+        assert_eq!(report.items[0].errors.unwrap().n_compared, 0);
+        assert!(report.to_string().contains("no time iterations in common"));
+        assert!(!compare(&candidate, &spe_10(), f64::INFINITY, 0.0));
+    }
+}
@@ -25,6 +25,38 @@ pub enum EclairError {
     #[error("Failed to read bytes from the std::io::Read instance")]
     ReadError(#[from] std::io::Error),
 
+    #[error("Input ended in the middle of record {record_name:?}. Expected at least {expected:?} bytes, got {got:?}.")]
+    TruncatedRecord {
+        record_name: String,
+        expected: usize,
+        got: usize,
+    },
+
+    #[error("Record {name:?} has a negative number of elements: {n_elements:?}.")]
+    NegativeElementCount { name: String, n_elements: i32 },
+
+    #[error("Record {name:?} takes {bytes:?} bytes, more than the reader allows.")]
+    RecordTooLarge { name: String, bytes: usize },
+
+    #[error(
+        "Record {name:?} has {n_elements:?} elements, more than the allowed {max_elements:?}."
+    )]
+    TooManyElements {
+        name: String,
+        n_elements: usize,
+        max_elements: usize,
+    },
+
+    #[error("Record name is longer than 8 characters: {0}")]
+    InvalidRecordName(String),
+
+    // FORMATTED PARSING ERRORS
+    #[error("Invalid formatted record header: {0}")]
+    InvalidFormattedHeader(String),
+
+    #[error("Invalid formatted record value: {0}")]
+    InvalidFormattedValue(String),
+
     // RECORD CONTENT ERRORS
     #[error("Binary record {name:?} has unexpected data type {found:?}. Expected {expected:?}.")]
     InvalidRecordDataType {
@@ -43,9 +75,18 @@ pub enum EclairError {
         found: usize,
     },
 
+    #[error("DIMENS declares {dimens:?} summary items, but the SMSPEC records hold {found:?}. DIMENS is likely corrupt.")]
+    InconsistentNlist { dimens: usize, found: usize },
+
     #[error("Missing or invalid record: {0}")]
     MissingRecord(String),
 
+    #[error("Invalid unit system identifier in INTEHEAD: {0}")]
+    InvalidUnitSystemId(i32),
+
+    #[error("Summary data contains neither a TIME nor a YEARS item")]
+    MissingTimeItem,
+
     #[error("MINISTEP value does not match the current amount of stored UNSMRY records. Expected {expected:?}, found {found:?}.")]
     InvalidMinistepValue { expected: usize, found: usize },
 
@@ -55,6 +96,33 @@ pub enum EclairError {
     #[error("Invalid file path requested: {0}")]
     InvalidFilePath(String),
 
+    #[error("Summary file does not exist: {0}")]
+    MissingSummaryFile(String),
+
+    #[error("Restarted runs form a cycle at: {0}")]
+    RestartCycle(String),
+
+    #[error("No summary named {0} has been found")]
+    SummaryNotFound(String),
+
+    #[error("A summary named {0} already exists")]
+    DuplicateSummaryName(String),
+
+    #[error("Summary index {index} is out of range for {length} summaries")]
+    SummaryIndexOutOfRange { index: usize, length: usize },
+
+    #[cfg(feature = "arrow")]
+    #[error("Arrow error")]
+    ArrowError(#[from] arrow::error::ArrowError),
+
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error")]
+    ParquetError(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "http")]
+    #[error("HTTP error")]
+    HttpError(#[from] Box<ureq::Error>),
+
     #[cfg(feature = "read_zmq")]
     #[error("ZeroMQ error")]
     ZeroMqError(#[from] zmq::Error),
@@ -64,10 +132,33 @@ pub enum EclairError {
     ZeroMqSocketDisconnected,
 
     #[cfg(feature = "read_zmq")]
+    #[error("ZeroMQ authentication failed: {0}")]
+    ZeroMqAuthFailed(String),
+
     #[error("Received Smspec JSON string is not valid UTF-8")]
     InvalidSmspecJson,
 
-    #[cfg(feature = "read_zmq")]
-    #[error("JSON deserealization error")]
+    #[error("Network connection has been closed by the server")]
+    ConnectionClosed,
+
+    #[error("Timed out waiting for the server")]
+    ConnectionTimedOut,
+
+    #[error("Frame payload of {0:?} bytes does not hold whole 4-byte values")]
+    InvalidFrameLength(usize),
+
+    #[error("JSON (de)serialization error")]
     DeJsonErr(#[from] serde_json::Error),
 }
+
+impl EclairError {
+    /// Whether the input ended in the middle of a record. For a file that is still being written
+    /// this means the record is incomplete yet, rather than corrupt.
+    pub fn is_truncation(&self) -> bool {
+        match self {
+            EclairError::TruncatedRecord { .. } => true,
+            EclairError::ReadError(e) => e.kind() == std::io::ErrorKind::UnexpectedEof,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Summary data polled from a REST gateway.
+//!
+//! The gateway serves the SMSPEC JSON at `GET {base_url}/case/{name}/smspec` and the time
+//! iterations starting at the given one at `GET {base_url}/case/{name}/params?from_step=N`. The
+//! params response is a sequence of rows, each holding the big-endian 4-byte step index followed
+//! by the big-endian `f32` PARAMS values, as in the network messages of the `net` module.
+
+use std::{io::Read, time::Duration};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+
+use crate::{
+    binary_parsing::{read_f32, read_i32, ByteOrder},
+    error::EclairError,
+    net::summary_from_smspec_json,
+    summary::{InitializeSummary, Params, Summary, UpdateSummary, DEFAULT_POLL_INTERVAL},
+    Result,
+};
+
+/// Percent-encode a URL path segment, keeping only the unreserved characters of RFC 3986.
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// A client of the REST gateway, used by both the reader and the updater.
+struct GatewayClient {
+    agent: ureq::Agent,
+    case_url: String,
+    bearer_token: Option<String>,
+}
+
+impl GatewayClient {
+    /// Fetch the response body of the case endpoint.
+    fn get(&self, endpoint: &str) -> Result<Vec<u8>> {
+        let mut request = self.agent.get(&format!("{}/{}", self.case_url, endpoint));
+        if let Some(token) = &self.bearer_token {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+
+        let response = request.call().map_err(Box::new)?;
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body)?;
+        Ok(body)
+    }
+
+    /// Fetch the time iterations starting at `from_step` and check that they follow each other
+    /// and have the right number of values.
+    fn get_params(&self, from_step: usize, n_items: usize) -> Result<Vec<Vec<f32>>> {
+        let body = self.get(&format!("params?from_step={}", from_step))?;
+
+        let row_size = 4 + n_items * std::mem::size_of::<f32>();
+        if body.len() % row_size != 0 {
+            // Report the number of values in the incomplete last row.
+            return Err(EclairError::UnexpectedRecordDataLength {
+                name: "HTTP_PARAMS".to_owned(),
+                expected: n_items,
+                found: (body.len() % row_size).saturating_sub(4) / std::mem::size_of::<f32>(),
+            });
+        }
+
+        body.chunks_exact(row_size)
+            .zip(from_step..)
+            .map(|(row, expected)| {
+                let current_step = read_i32(&row[..4], ByteOrder::BigEndian) as usize;
+                if current_step != expected {
+                    return Err(EclairError::InvalidMinistepValue {
+                        expected,
+                        found: current_step,
+                    });
+                }
+                Ok(row[4..]
+                    .chunks_exact(std::mem::size_of::<f32>())
+                    .map(|chunk| read_f32(chunk, ByteOrder::BigEndian))
+                    .collect())
+            })
+            .collect()
+    }
+}
+
+/// HttpSummaryReader builds Summary data from a case served by a REST gateway.
+pub struct HttpSummaryReader {
+    client: GatewayClient,
+    poll_interval: Duration,
+}
+
+impl HttpSummaryReader {
+    /// Create a reader for the named case served by the gateway at the base URL, e.g.
+    /// `https://gateway:8080`. The case name is percent-encoded.
+    pub fn new(base_url: &str, case_name: &str) -> Self {
+        Self {
+            client: GatewayClient {
+                agent: ureq::Agent::new(),
+                case_url: format!(
+                    "{}/case/{}",
+                    base_url.trim_end_matches('/'),
+                    encode_path_segment(case_name)
+                ),
+                bearer_token: None,
+            },
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Authenticate the requests with the bearer token.
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.client.bearer_token = Some(token.to_owned());
+        self
+    }
+
+    /// Set how often the updater polls the gateway for new data. Defaults to
+    /// `DEFAULT_POLL_INTERVAL`.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+}
+
+impl InitializeSummary for HttpSummaryReader {
+    type Updater = HttpUpdater;
+
+    fn init(self) -> Result<(Summary, Self::Updater)> {
+        let mut summary = summary_from_smspec_json(&self.client.get("smspec")?)?;
+        let n_items = summary.n_items();
+
+        // Take all the time iterations available so far.
+        let rows = self.client.get_params(0, n_items)?;
+        let n_steps = rows.len();
+        for row in rows {
+            summary.append_params(Params::F32(row))?;
+        }
+
+        Ok((
+            summary,
+            HttpUpdater {
+                client: self.client,
+                poll_interval: self.poll_interval,
+                n_items,
+                n_steps,
+            },
+        ))
+    }
+}
+
+/// HttpUpdater polls the gateway for new time iterations.
+pub struct HttpUpdater {
+    client: GatewayClient,
+    poll_interval: Duration,
+    n_items: usize,
+    n_steps: usize,
+}
+
+impl UpdateSummary for HttpUpdater {
+    fn update(&mut self, data_snd: Sender<Params>, term_rcv: Receiver<bool>) -> Result<()> {
+        loop {
+            match term_rcv.recv_timeout(self.poll_interval) {
+                Err(RecvTimeoutError::Timeout) => {}
+                _ => {
+                    log::debug!(target: "Updating Summary", "Stopping the HTTP updater");
+                    return Ok(());
+                }
+            }
+
+            let rows = match self.client.get_params(self.n_steps, self.n_items) {
+                Ok(rows) => rows,
+                // The gateway may be briefly unreachable, try again at the next poll.
+                Err(EclairError::HttpError(err)) if matches!(*err, ureq::Error::Transport(_)) => {
+                    log::warn!(target: "Updating Summary", "Failed to reach the gateway: {}", err);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            for row in rows {
+                self.n_steps += 1;
+                if data_snd.send(Params::F32(row)).is_err() {
+                    log::debug!(target: "Updating Summary", "Error while sending params over a channel");
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::SMSPEC_JSON;
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    const TOKEN: &str = "secret";
+
+    /// Serve the SMSPEC and the rows of the case "LIVE" to clients with the right token.
+    fn serve(listener: TcpListener, rows: Arc<Mutex<Vec<[f32; 2]>>>) {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut lines = BufReader::new(&stream).lines().map(|line| line.unwrap());
+            let path = lines.next().unwrap().split(' ').nth(1).unwrap().to_owned();
+            let headers: Vec<String> = lines.take_while(|line| !line.is_empty()).collect();
+            let authorized = headers.contains(&format!("Authorization: Bearer {}", TOKEN));
+
+            let (status, body) = if !authorized {
+                ("401 Unauthorized", Vec::new())
+            } else if path == "/case/LIVE/smspec" {
+                ("200 OK", SMSPEC_JSON.as_bytes().to_vec())
+            } else if let Some(from_step) = path.strip_prefix("/case/LIVE/params?from_step=") {
+                let mut body = Vec::new();
+                let rows = rows.lock().unwrap();
+                for (step, row) in rows.iter().enumerate().skip(from_step.parse().unwrap()) {
+                    body.extend_from_slice(&(step as i32).to_be_bytes());
+                    row.iter()
+                        .for_each(|v| body.extend_from_slice(&v.to_be_bytes()));
+                }
+                ("200 OK", body)
+            } else {
+                ("404 Not Found", Vec::new())
+            };
+
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                status,
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+        }
+    }
+
+    #[test]
+    fn poll_gateway() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let rows = Arc::new(Mutex::new(vec![[1.0, 10.0], [2.0, 20.0]]));
+        let server_rows = rows.clone();
+        thread::spawn(move || serve(listener, server_rows));
+
+        assert!(matches!(
+            HttpSummaryReader::new(&base_url, "LIVE").init(),
+            Err(EclairError::HttpError(_))
+        ));
+
+        let (summary, mut updater) = HttpSummaryReader::new(&base_url, "LIVE")
+            .with_bearer_token(TOKEN)
+            .with_poll_interval(Duration::from_millis(10))
+            .init()
+            .unwrap();
+        assert_eq!(summary.n_steps(), 2);
+
+        let (data_snd, data_rcv) = crossbeam_channel::unbounded();
+        let (term_snd, term_rcv) = crossbeam_channel::bounded(1);
+        let updater_thread = thread::spawn(move || updater.update(data_snd, term_rcv));
+
+        rows.lock().unwrap().push([3.0, 30.0]);
+        let params = data_rcv.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(params, Params::F32(vec![3.0, 30.0]));
+
+        term_snd.send(true).unwrap();
+        updater_thread.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn encoded_case_name() {
+        let reader = HttpSummaryReader::new("https://gateway:8080/", "SPE10 run/2?");
+        assert_eq!(
+            reader.client.case_url,
+            "https://gateway:8080/case/SPE10%20run%2F2%3F"
+        );
+    }
+}
@@ -1,10 +1,21 @@
 //! This crate provides a reader for the binary files written out by the Eclipse reservoir simulator.
 
+#[cfg(feature = "arrow")]
+mod arrow_export;
 mod binary_parsing;
+pub mod diff;
 pub mod error;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod net;
+#[cfg(feature = "parquet")]
+mod parquet_export;
+pub mod query;
 pub mod records;
 pub mod summary;
 pub mod summary_manager;
+pub mod to_csv;
+pub mod units;
 
 #[cfg(feature = "read_zmq")]
 pub mod zmq;
@@ -0,0 +1,410 @@
+//! Summary data received over a plain TCP connection, for setups where ZeroMQ is unavailable.
+//!
+//! The server sends length-prefixed frames, each starting with the frame length as a big-endian
+//! 4-byte integer. The first frame is the SMSPEC JSON, sent as soon as the client connects. Every
+//! subsequent frame holds a single time iteration: the big-endian 4-byte step index followed by
+//! the big-endian `f32` PARAMS values. This mirrors the layout of the ZeroMQ messages.
+//!
+//! Frame lengths come from the network, so frames longer than `DEFAULT_MAX_RECORD_BYTES`, or the
+//! limit set with `TcpConnection::with_max_frame_bytes`, are rejected before anything is allocated.
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io::{ErrorKind, Read},
+    net::TcpStream,
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+use serde::Deserialize;
+
+use crate::{
+    binary_parsing::{read_f32, read_i32, ByteOrder},
+    error::EclairError,
+    records::{RecordData, DEFAULT_MAX_RECORD_BYTES},
+    summary::{
+        InitializeSummary, Params, SmspecRecords, Summary, UpdateSummary, DEFAULT_POLL_INTERVAL,
+    },
+    FlexString, Result,
+};
+
+/// SMSPEC data as sent by the network servers.
+#[derive(Deserialize)]
+pub(crate) struct SmspecJson {
+    #[serde(rename = "DIMENS")]
+    dimens: Vec<i32>,
+
+    #[serde(rename = "KEYWORDS")]
+    keywords: Vec<FlexString>,
+
+    #[serde(rename = "NAMES")]
+    names: Vec<FlexString>,
+
+    #[serde(rename = "NUMS")]
+    nums: Vec<i32>,
+
+    #[serde(rename = "STARTDAT")]
+    start_date: Vec<i32>,
+
+    #[serde(rename = "UNITS")]
+    units: Vec<FlexString>,
+}
+
+impl From<SmspecJson> for SmspecRecords {
+    fn from(smspec_json: SmspecJson) -> Self {
+        use RecordData::*;
+
+        let mut records = HashMap::new();
+        records.insert("DIMENS", Some(Int(smspec_json.dimens)));
+        records.insert("STARTDAT", Some(Int(smspec_json.start_date)));
+        records.insert("KEYWORDS", Some(Chars(smspec_json.keywords)));
+        records.insert("WGNAMES", Some(Chars(smspec_json.names)));
+        records.insert("NUMS", Some(Int(smspec_json.nums)));
+        records.insert("UNITS", Some(Chars(smspec_json.units)));
+
+        SmspecRecords::new(records)
+    }
+}
+
+/// Build an empty Summary from the SMSPEC JSON bytes.
+pub(crate) fn summary_from_smspec_json(json: &[u8]) -> Result<Summary> {
+    let json = std::str::from_utf8(json).map_err(|_| EclairError::InvalidSmspecJson)?;
+    let smspec_json: SmspecJson = serde_json::from_str(json)?;
+    Summary::try_from(SmspecRecords::from(smspec_json))
+}
+
+/// How long `TcpConnection` waits for the SMSPEC frame by default.
+pub const DEFAULT_INIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read the frame length from its prefix and check it against the limit.
+fn frame_len(prefix: &[u8], max_frame_bytes: usize) -> Result<usize> {
+    let len = read_i32(prefix, ByteOrder::BigEndian) as u32 as usize;
+    if len > max_frame_bytes {
+        return Err(EclairError::RecordTooLarge {
+            name: "TCP_FRAME".to_owned(),
+            bytes: len,
+        });
+    }
+    Ok(len)
+}
+
+/// Split a complete frame off the buffer front. Returns the frame payload and the total number of
+/// bytes it takes, or None if the frame hasn't been fully received yet.
+fn split_frame(buffer: &[u8], max_frame_bytes: usize) -> Result<Option<(&[u8], usize)>> {
+    if buffer.len() < 4 {
+        return Ok(None);
+    }
+    let len = frame_len(&buffer[..4], max_frame_bytes)?;
+    Ok(buffer.get(4..4 + len).map(|payload| (payload, 4 + len)))
+}
+
+/// A TCP connection to the summary data server.
+pub struct TcpConnection {
+    stream: TcpStream,
+    poll_interval: Duration,
+    init_timeout: Duration,
+    max_frame_bytes: usize,
+}
+
+impl TcpConnection {
+    /// Connect to the server at the given host and port.
+    pub fn new(host: &str, port: u16) -> Result<Self> {
+        log::info!("Connecting to {}:{}", host, port);
+        let stream = TcpStream::connect((host, port))?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self {
+            stream,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            init_timeout: DEFAULT_INIT_TIMEOUT,
+            max_frame_bytes: DEFAULT_MAX_RECORD_BYTES,
+        })
+    }
+
+    /// Set how long the updater waits for new data before checking whether it should stop.
+    /// Defaults to `DEFAULT_POLL_INTERVAL`.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Set how long to wait for the SMSPEC frame before giving up. Defaults to
+    /// `DEFAULT_INIT_TIMEOUT`.
+    pub fn with_init_timeout(self, init_timeout: Duration) -> Self {
+        Self {
+            init_timeout,
+            ..self
+        }
+    }
+
+    /// Set the size of the largest frame to accept. Defaults to `DEFAULT_MAX_RECORD_BYTES`.
+    pub fn with_max_frame_bytes(self, max_frame_bytes: usize) -> Self {
+        Self {
+            max_frame_bytes,
+            ..self
+        }
+    }
+
+    /// Read a single frame, blocking until it has been received or the read times out.
+    fn read_frame(&mut self) -> Result<Vec<u8>> {
+        let timed_out = |e: std::io::Error| match e.kind() {
+            ErrorKind::WouldBlock | ErrorKind::TimedOut => EclairError::ConnectionTimedOut,
+            ErrorKind::UnexpectedEof => EclairError::ConnectionClosed,
+            _ => e.into(),
+        };
+
+        let mut len = [0u8; 4];
+        self.stream.read_exact(&mut len).map_err(timed_out)?;
+
+        let mut payload = vec![0u8; frame_len(&len, self.max_frame_bytes)?];
+        self.stream.read_exact(&mut payload).map_err(timed_out)?;
+        Ok(payload)
+    }
+}
+
+impl InitializeSummary for TcpConnection {
+    type Updater = TcpUpdater;
+
+    fn init(mut self) -> Result<(Summary, Self::Updater)> {
+        // A server that never sends the SMSPEC would otherwise block us forever.
+        self.stream.set_read_timeout(Some(self.init_timeout))?;
+        let summary = summary_from_smspec_json(&self.read_frame()?)?;
+        let n_items = summary.n_items();
+
+        // Time out the reads, so that the updater gets to check whether it should stop.
+        self.stream.set_read_timeout(Some(self.poll_interval))?;
+
+        Ok((
+            summary,
+            TcpUpdater {
+                stream: self.stream,
+                buffer: Vec::new(),
+                max_frame_bytes: self.max_frame_bytes,
+                n_items,
+                n_steps: 0,
+            },
+        ))
+    }
+}
+
+/// TcpUpdater receives the time iterations from the server.
+pub struct TcpUpdater {
+    stream: TcpStream,
+
+    // Received bytes that don't make up a complete frame yet.
+    buffer: Vec<u8>,
+
+    max_frame_bytes: usize,
+
+    n_items: usize,
+    n_steps: usize,
+}
+
+impl TcpUpdater {
+    /// Decode the values of a single time iteration and check that it is the expected one.
+    fn decode_params(&mut self, frame: &[u8]) -> Result<Vec<f32>> {
+        if frame.len() < 4 {
+            return Err(EclairError::NotEnoughBytes {
+                expected: 4,
+                found: frame.len(),
+            });
+        }
+
+        let current_step = read_i32(&frame[..4], ByteOrder::BigEndian) as usize;
+        if current_step != self.n_steps {
+            return Err(EclairError::InvalidMinistepValue {
+                expected: self.n_steps,
+                found: current_step,
+            });
+        }
+
+        if (frame.len() - 4) % std::mem::size_of::<f32>() != 0 {
+            return Err(EclairError::InvalidFrameLength(frame.len() - 4));
+        }
+
+        let params: Vec<f32> = frame[4..]
+            .chunks_exact(std::mem::size_of::<f32>())
+            .map(|chunk| read_f32(chunk, ByteOrder::BigEndian))
+            .collect();
+
+        if params.len() != self.n_items {
+            return Err(EclairError::UnexpectedRecordDataLength {
+                name: "TCP_PARAMS".to_owned(),
+                expected: self.n_items,
+                found: params.len(),
+            });
+        }
+
+        self.n_steps += 1;
+        Ok(params)
+    }
+}
+
+impl UpdateSummary for TcpUpdater {
+    fn update(&mut self, data_snd: Sender<Params>, term_rcv: Receiver<bool>) -> Result<()> {
+        let mut chunk = [0u8; 64 * 1024];
+        loop {
+            // First check if we were instructed to stop.
+            if term_rcv.try_recv().is_ok() {
+                log::debug!(target: "Updating Summary", "Stopping the TCP updater");
+                return Ok(());
+            }
+
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err(EclairError::ConnectionClosed),
+                Ok(n_bytes) => self.buffer.extend_from_slice(&chunk[..n_bytes]),
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    continue
+                }
+                Err(e) => return Err(e.into()),
+            }
+
+            let mut buffer = std::mem::take(&mut self.buffer);
+            let mut consumed = 0;
+            while let Some((frame, n_bytes)) =
+                split_frame(&buffer[consumed..], self.max_frame_bytes)?
+            {
+                consumed += n_bytes;
+
+                let params = self.decode_params(frame)?;
+                if data_snd.send(Params::F32(params)).is_err() {
+                    log::debug!(target: "Updating Summary", "Error while sending params over a channel");
+                    return Ok(());
+                }
+            }
+            buffer.drain(..consumed);
+            self.buffer = buffer;
+        }
+    }
+}
+
+/// SMSPEC data of a summary with TIME and FOPR in the JSON form, shared by the tests of the
+/// network sources.
+#[cfg(test)]
+pub(crate) const SMSPEC_JSON: &str = r#"{
+    "DIMENS": [2, 10, 10, 3, 0, -1],
+    "KEYWORDS": ["TIME", "FOPR"],
+    "NAMES": [":+:+:+:+", "FIELD"],
+    "NUMS": [0, 0],
+    "STARTDAT": [1, 1, 2020],
+    "UNITS": ["DAYS", "SM3/DAY"]
+}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summary_manager::{SummaryManager, SummarySourceStatus};
+    use std::{io::Write, net::TcpListener, thread};
+
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut frame = (payload.len() as u32).to_be_bytes().to_vec();
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn step_frame(step: i32, params: &[f32]) -> Vec<u8> {
+        let mut payload = step.to_be_bytes().to_vec();
+        params
+            .iter()
+            .for_each(|p| payload.extend_from_slice(&p.to_be_bytes()));
+        frame(&payload)
+    }
+
+    #[test]
+    fn tcp_source() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(&frame(SMSPEC_JSON.as_bytes())).unwrap();
+
+            // Frames may arrive split at arbitrary points.
+            let mut data = step_frame(0, &[1.0, 10.0]);
+            data.extend(step_frame(1, &[2.0, 20.0]));
+            for bytes in data.chunks(5) {
+                stream.write_all(bytes).unwrap();
+                stream.flush().unwrap();
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let mut manager = SummaryManager::new();
+        manager.add_from_tcp("127.0.0.1", port, None).unwrap();
+        assert_eq!(manager.name(0), format!("127.0.0.1:{}", port));
+
+        while manager.time_item(0, "TIME").map_or(0, |time| time.len()) < 2 {
+            manager.refresh_timeout(Duration::from_secs(10)).unwrap();
+        }
+        assert_eq!(manager.time_item(0, "TIME").unwrap()[..], [1.0, 2.0]);
+
+        // The source fails once the server has closed the connection.
+        server.join().unwrap();
+        while manager.health(0) == Some(SummarySourceStatus::Active) {
+            manager.refresh_timeout(Duration::from_secs(1)).unwrap();
+        }
+        assert_eq!(
+            manager.health(0),
+            Some(SummarySourceStatus::Failed(
+                EclairError::ConnectionClosed.to_string()
+            ))
+        );
+    }
+
+    /// Accept a single client and send it the bytes, then keep the connection open until the
+    /// client goes away.
+    fn serve_once(data: Vec<u8>) -> (u16, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(&data).unwrap();
+            let _ = stream.read(&mut [0u8; 1]);
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn bad_frames() {
+        // A server that never sends the SMSPEC.
+        let (port, server) = serve_once(vec![]);
+        let conn = TcpConnection::new("127.0.0.1", port)
+            .unwrap()
+            .with_init_timeout(Duration::from_millis(50));
+        assert!(matches!(conn.init(), Err(EclairError::ConnectionTimedOut)));
+        server.join().unwrap();
+
+        // A frame length that is too large to allocate.
+        let (port, server) = serve_once(u32::MAX.to_be_bytes().to_vec());
+        let conn = TcpConnection::new("127.0.0.1", port).unwrap();
+        assert!(matches!(
+            conn.init(),
+            Err(EclairError::RecordTooLarge { bytes, .. }) if bytes == u32::MAX as usize
+        ));
+        server.join().unwrap();
+
+        // PARAMS bytes that are not whole f32 values.
+        let mut data = frame(SMSPEC_JSON.as_bytes());
+        let mut payload = step_frame(0, &[1.0, 10.0])[4..].to_vec();
+        payload.push(0);
+        data.extend(frame(&payload));
+        let (port, server) = serve_once(data);
+
+        let mut manager = SummaryManager::new();
+        manager.add_from_tcp("127.0.0.1", port, None).unwrap();
+        while manager.health(0) == Some(SummarySourceStatus::Active) {
+            manager.refresh_timeout(Duration::from_secs(1)).unwrap();
+        }
+        assert_eq!(
+            manager.health(0),
+            Some(SummarySourceStatus::Failed(
+                EclairError::InvalidFrameLength(9).to_string()
+            ))
+        );
+        manager.remove(0).unwrap();
+        server.join().unwrap();
+    }
+}
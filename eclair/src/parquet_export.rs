@@ -0,0 +1,100 @@
+//! Export of summary data to Parquet files, using the Arrow layout of `Summary::to_arrow_batch`.
+//! Small summaries go into a single row group, larger ones are split into row groups of
+//! `PARQUET_ROW_GROUP_SIZE` time iterations.
+
+use std::io::Write;
+
+use parquet::{arrow::ArrowWriter, file::properties::WriterProperties};
+
+use crate::{summary::Summary, Result};
+
+/// Maximum number of time iterations in a Parquet row group.
+const PARQUET_ROW_GROUP_SIZE: usize = 65536;
+
+fn write_row_groups<W: Write + Send>(
+    summary: &Summary,
+    out: W,
+    row_group_size: usize,
+) -> Result<()> {
+    let batch = summary.to_arrow_batch()?;
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(row_group_size)
+        .build();
+    let mut writer = ArrowWriter::try_new(out, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+impl Summary {
+    /// Write all summary data in the Parquet format. Item units are kept in the column metadata.
+    pub fn write_parquet<W: Write + Send>(&self, out: W) -> Result<()> {
+        write_row_groups(self, out, PARQUET_ROW_GROUP_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrow_export::{TIMESTAMP_COLUMN, UNIT_METADATA_KEY};
+    use arrow::{
+        array::{Array, Float32Array, TimestampSecondArray},
+        record_batch::RecordBatch,
+    };
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::File;
+
+    #[test]
+    fn parquet_round_trip() {
+        let summary = Summary::open("../assets/SPE10").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("SPE10.parquet");
+        summary.write_parquet(File::create(&path).unwrap()).unwrap();
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(builder.metadata().num_row_groups(), 1);
+        let schema = builder.schema().clone();
+        assert_eq!(
+            schema.field_with_name("WBHP:P1").unwrap().metadata()[UNIT_METADATA_KEY],
+            "PSIA"
+        );
+
+        let batches: Vec<RecordBatch> = builder
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap())
+            .collect();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 58);
+        assert_eq!(batch.num_columns(), summary.n_items() + 1);
+
+        let timestamps = batch
+            .column_by_name(TIMESTAMP_COLUMN)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampSecondArray>()
+            .unwrap();
+        assert_eq!(timestamps.values().to_vec(), summary.timestamps);
+
+        for (id, &index) in &summary.item_ids {
+            let column = batch.column_by_name(&id.to_string()).unwrap();
+            let values = column.as_any().downcast_ref::<Float32Array>().unwrap();
+            assert_eq!(values.null_count(), 0);
+            assert_eq!(values.values()[..], *summary.item_values(index));
+        }
+    }
+
+    #[test]
+    fn row_groups() {
+        let summary = Summary::open("../assets/SPE10").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("SPE10.parquet");
+        write_row_groups(&summary, File::create(&path).unwrap(), 20).unwrap();
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        let row_groups = builder.metadata().row_groups();
+        assert_eq!(row_groups.len(), 3);
+        assert_eq!(row_groups.iter().map(|rg| rg.num_rows()).sum::<i64>(), 58);
+    }
+}
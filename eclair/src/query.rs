@@ -0,0 +1,227 @@
+//! Matching of summary item ids against wildcard patterns, e.g. `WOPR` for all wells or `WBHP` for
+//! wells named `PROD*`.
+//!
+//! Patterns support `*` (any sequence of characters) and `?` (any single character) and are case
+//! insensitive. The keyword pattern is matched against the item name. The qualifier pattern depends
+//! on the item qualifier:
+//!
+//! - well, group and network node names are matched directly, e.g. `PROD*`;
+//! - aquifer, block and region indices are matched against a comma-separated list of patterns,
+//!   e.g. `1,2,3` or `1?`. Named regions match by their name too;
+//! - completions and well segments are matched as `WELL:INDEX`, where the index part is optional;
+//! - cross region flows are matched as `FROM-TO`, e.g. `1-*`;
+//! - local grid items are matched as `LGR:WELL`, `LGR:WELL:I,J,K` or `LGR:I,J,K`, depending on the
+//!   item, where all but the local grid name are optional, e.g. `LGR1` or `LGR*:PROD1`;
+//! - items without a location (time, performance and field items) only match an empty pattern
+//!   or `*`.
+//!
+//! An empty qualifier pattern matches any qualifier.
+//!
+//! Both patterns can also be given as a single `KEYWORD:QUALIFIER` string, e.g. `WBHP:PROD*` or
+//! `COPR:PROD1:4`. The qualifier part is optional.
+
+use crate::summary::{ItemId, ItemQualifier};
+
+/// Case insensitive wildcard matching of the whole text.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_uppercase()).collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and of the text it currently matches up to.
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` consume one more character and try again.
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Match an index against a comma-separated list of patterns.
+fn index_match(pattern: &str, index: i32) -> bool {
+    let index = index.to_string();
+    pattern
+        .split(',')
+        .any(|pattern| glob_match(pattern.trim(), &index))
+}
+
+/// Check whether an item id matches the keyword and qualifier patterns.
+pub fn matches(id: &ItemId, keyword_glob: &str, qualifier_glob: &str) -> bool {
+    use ItemQualifier::*;
+
+    if !glob_match(keyword_glob, &id.name) {
+        return false;
+    }
+
+    if qualifier_glob.is_empty() {
+        return true;
+    }
+
+    match &id.qualifier {
+        Time | Performance | Field => glob_match(qualifier_glob, ""),
+        Well { wg_name } | Group { wg_name } | Node { wg_name } => {
+            glob_match(qualifier_glob, wg_name)
+        }
+        Aquifer { index } | Block { index } => index_match(qualifier_glob, *index),
+        Region { wg_name, index } => {
+            index_match(qualifier_glob, *index)
+                || wg_name
+                    .as_ref()
+                    .is_some_and(|name| glob_match(qualifier_glob, name))
+        }
+        Completion { wg_name, index } | Segment { wg_name, index } => {
+            let (well_glob, index_glob) = match qualifier_glob.rsplit_once(':') {
+                Some((well_glob, index_glob)) => (well_glob, index_glob),
+                None => (qualifier_glob, "*"),
+            };
+            glob_match(well_glob, wg_name) && index_match(index_glob, *index)
+        }
+        CrossRegionFlow { from, to } => glob_match(qualifier_glob, &format!("{}-{}", from, to)),
+        LocalWell { lgr, wg_name } => {
+            let (lgr_glob, well_glob) = qualifier_glob
+                .split_once(':')
+                .unwrap_or((qualifier_glob, "*"));
+            glob_match(lgr_glob, lgr) && glob_match(well_glob, wg_name)
+        }
+        LocalCompletion {
+            lgr,
+            wg_name,
+            i,
+            j,
+            k,
+        } => {
+            let (lgr_glob, rest) = qualifier_glob
+                .split_once(':')
+                .unwrap_or((qualifier_glob, "*"));
+            let (well_glob, cell_glob) = rest.split_once(':').unwrap_or((rest, "*"));
+            glob_match(lgr_glob, lgr)
+                && glob_match(well_glob, wg_name)
+                && glob_match(cell_glob, &format!("{},{},{}", i, j, k))
+        }
+        LocalBlock { lgr, i, j, k } => {
+            let (lgr_glob, cell_glob) = qualifier_glob
+                .split_once(':')
+                .unwrap_or((qualifier_glob, "*"));
+            glob_match(lgr_glob, lgr) && glob_match(cell_glob, &format!("{},{},{}", i, j, k))
+        }
+        Unrecognized { wg_name, .. } => glob_match(qualifier_glob, wg_name),
+    }
+}
+
+/// Check whether an item id matches a combined `KEYWORD:QUALIFIER` pattern.
+pub fn matches_pattern(id: &ItemId, pattern: &str) -> bool {
+    match pattern.split_once(':') {
+        Some((keyword_glob, qualifier_glob)) => matches(id, keyword_glob, qualifier_glob),
+        None => matches(id, pattern, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FlexString;
+
+    fn item_id(name: &str, qualifier: ItemQualifier) -> ItemId {
+        ItemId {
+            name: FlexString::from(name),
+            qualifier,
+        }
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("WOPR", "WOPR"));
+        assert!(glob_match("wopr", "WOPR"));
+        assert!(glob_match("W*", "WOPR"));
+        assert!(glob_match("W?PR", "WGPR"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("PROD*_N*", "PROD_1_NORTH"));
+        assert!(!glob_match("W?PR", "WOPRH"));
+        assert!(!glob_match("F*", "WOPR"));
+        assert!(!glob_match("", "WOPR"));
+    }
+
+    #[test]
+    fn well_items() {
+        let wbhp = item_id(
+            "WBHP",
+            ItemQualifier::Well {
+                wg_name: FlexString::from("PROD1"),
+            },
+        );
+        assert!(matches(&wbhp, "WBHP", "prod*"));
+        assert!(matches(&wbhp, "W*", ""));
+        assert!(!matches(&wbhp, "WBHP", "INJ*"));
+        assert!(!matches(&wbhp, "WOPR", "PROD1"));
+
+        let fopr = item_id("FOPR", ItemQualifier::Field);
+        assert!(matches(&fopr, "FOPR", "*"));
+        assert!(!matches(&fopr, "FOPR", "PROD1"));
+    }
+
+    #[test]
+    fn region_items() {
+        let rpr = item_id(
+            "RPR",
+            ItemQualifier::Region {
+                wg_name: None,
+                index: 12,
+            },
+        );
+        assert!(matches(&rpr, "RPR", "1,12,3"));
+        assert!(matches(&rpr, "RPR", "1?"));
+        assert!(!matches(&rpr, "RPR", "1,2,3"));
+
+        let named = item_id(
+            "RPR",
+            ItemQualifier::Region {
+                wg_name: Some(FlexString::from("NORTH")),
+                index: 2,
+            },
+        );
+        assert!(matches(&named, "RPR", "north"));
+        assert!(matches(&named, "RPR", "2"));
+
+        let rgft = item_id("RGFT", ItemQualifier::CrossRegionFlow { from: 1, to: 3 });
+        assert!(matches(&rgft, "R*FT", "1-*"));
+        assert!(!matches(&rgft, "R*FT", "3-1"));
+    }
+
+    #[test]
+    fn completion_items() {
+        let copr = item_id(
+            "COPR",
+            ItemQualifier::Completion {
+                wg_name: FlexString::from("PROD1"),
+                index: 4,
+            },
+        );
+        assert!(matches(&copr, "COPR", "PROD1"));
+        assert!(matches(&copr, "COPR", "PROD*:3,4"));
+        assert!(!matches(&copr, "COPR", "PROD1:5"));
+        assert!(!matches(&copr, "COPR", "INJ1:4"));
+
+        assert!(matches_pattern(&copr, "COPR"));
+        assert!(matches_pattern(&copr, "C*:PROD1:4"));
+        assert!(!matches_pattern(&copr, "COPR:PROD1:5"));
+    }
+}
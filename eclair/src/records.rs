@@ -1,12 +1,14 @@
 //! ## Eclipse Binary Format
 //!
-//! Eclipse binary output files are typically written using the **big-endian** ordering. A single
+//! Eclipse binary output files are typically written using the **big-endian** ordering, although
+//! some simulators use the native little-endian one. `RecordReader` detects the byte order from the
+//! first record header. A single
 //! binary block is written in the Fortran style, where the binary payload is surrounded by matching
 //! leading and tailing record markers. The marker is a 4-byte integer (`int32`), equal to the byte
 //! length of the data. For instance, if we have a binary block of 200 bytes, it will be written to
 //! disk as:
 //!
-//! ```
+//! ```text
 //! +-------+----------+-------+
 //! |  200  |   data   |  200  |
 //! +-------+----------+-------+
@@ -37,7 +39,7 @@
 //!
 //! Here is how a data array is laid out on disk if it is called `FOO` and is 1500 integers long:
 //!
-//! ```
+//! ```text
 //! +------+------------------+------+------+-----------------+------+------+--------------------+------+
 //! | head | NAME LENGTH TYPE | tail | head | VAL1 .. VAL1000 | tail | head | VAL1001 .. VAL1500 | tail |
 //! +------+------------------+------+------+-----------------+------+------+--------------------+------+
@@ -46,11 +48,30 @@
 //! ```
 //!
 //! Note that `FOO` will be padded with spaces to be exactly 8 characters long.
+//!
+//! ## Eclipse Formatted Format
+//!
+//! When asked to (e.g. with `FMTOUT`), Eclipse writes the same records as ASCII text instead. The
+//! header is a single line with the quoted name, the number of elements and the quoted type, and
+//! it is followed by lines of whitespace-separated values:
+//!
+//! ```text
+//!  'DIMENS  '           6 'INTE'
+//!           34         100         100          30           0          -1
+//!  'PARAMS  '           3 'REAL'
+//!    0.00000000E+00   0.12345678E+01  -0.12500000E-02
+//! ```
+//!
+//! String values are enclosed in apostrophes and may contain spaces, logicals are written as `T`
+//! or `F`, and double precision values use the Fortran `D` exponent (e.g. `0.31415927D+01`).
 
+pub use crate::binary_parsing::ByteOrder;
 use crate::{binary_parsing as bp, error::EclairError, FlexString, Result, FIXED_STRING_LENGTH};
 
 use std::{
+    convert::TryInto,
     fmt::{Display, Formatter},
+    io::{BufRead, Seek, SeekFrom},
     mem, str,
 };
 
@@ -64,13 +85,18 @@ pub enum RecordData {
     Int(Vec<i32>),
     Bool(Vec<i32>),
     Chars(Vec<FlexString>),
+    /// `C0nn` strings, which keep their declared width for writing them back out
+    LongChars {
+        width: usize,
+        values: Vec<FlexString>,
+    },
     F32(Vec<f32>),
     F64(Vec<f64>),
     Message,
 }
 
 /// Helper enum for type validation.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RecordDataKind {
     Int,
     Bool,
@@ -101,41 +127,157 @@ impl RecordData {
         match self {
             Int(_) => &RecordDataKind::Int,
             Bool(_) => &RecordDataKind::Bool,
-            Chars(_) => &RecordDataKind::Chars,
+            Chars(_) | LongChars { .. } => &RecordDataKind::Chars,
             F32(_) => &RecordDataKind::F32,
             F64(_) => &RecordDataKind::F64,
             Message => &RecordDataKind::Message,
         }
     }
 
-    /// The type mnemonic for the underlying data.
+    /// The type mnemonic for the underlying data. Long strings are reported with their width, e.g.
+    /// `C042`.
     pub fn kind_string(&self) -> String {
-        self.kind().to_string()
+        match self {
+            RecordData::LongChars { width, .. } => format!("C{:03}", width),
+            _ => self.kind().to_string(),
+        }
+    }
+
+    /// Integer values, if the data holds them.
+    pub fn as_i32(&self) -> Option<&[i32]> {
+        match self {
+            RecordData::Int(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Single precision values, if the data holds them.
+    pub fn as_f32(&self) -> Option<&[f32]> {
+        match self {
+            RecordData::F32(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Double precision values, if the data holds them.
+    pub fn as_f64(&self) -> Option<&[f64]> {
+        match self {
+            RecordData::F64(v) => Some(v),
+            _ => None,
+        }
     }
 
-    /// Push input bytes into the binary data instance interpreting them as necessary.
-    fn push(&mut self, input: &[u8], element_size: usize) {
-        // FIXME: How to best validate input bytes before pushing?
+    /// String values of either width, if the data holds them.
+    pub fn as_chars(&self) -> Option<&[FlexString]> {
+        match self {
+            RecordData::Chars(v) | RecordData::LongChars { values: v, .. } => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Drop the declared width of long strings, for consumers that need the values only.
+    pub(crate) fn without_width(self) -> Self {
+        match self {
+            RecordData::LongChars { values, .. } => RecordData::Chars(values),
+            data => data,
+        }
+    }
+
+    /// Whether the data is a message, i.e. holds no values.
+    pub fn is_message(&self) -> bool {
+        matches!(self, RecordData::Message)
+    }
+
+    /// Push input bytes into the binary data instance interpreting them as necessary. Strings that
+    /// are not valid UTF-8 are reported as errors.
+    fn push(&mut self, input: &[u8], element_size: usize, order: ByteOrder) -> Result<()> {
         use RecordData::*;
-        input
-            .chunks_exact(element_size)
-            .for_each(|chunk| match self {
-                Int(v) | Bool(v) => v.push(bp::read_i32(chunk)),
-                F32(v) => v.push(bp::read_f32(chunk)),
-                F64(v) => v.push(bp::read_f64(chunk)),
-                Chars(v) => v.push(FlexString::from(
-                    str::from_utf8(chunk)
-                        .unwrap_or("Utf8 error creating string record")
-                        .trim(),
-                )),
+        for chunk in input.chunks_exact(element_size) {
+            match self {
+                Int(v) | Bool(v) => v.push(bp::read_i32(chunk, order)),
+                F32(v) => v.push(bp::read_f32(chunk, order)),
+                F64(v) => v.push(bp::read_f64(chunk, order)),
+                Chars(v) | LongChars { values: v, .. } => {
+                    v.push(FlexString::from(str::from_utf8(chunk)?.trim()))
+                }
                 Message => unimplemented!("Attempted to push into a RecordData::Message instance."),
-            });
+            }
+        }
+        Ok(())
+    }
+
+    /// Reserve space for the given number of additional elements.
+    fn reserve(&mut self, additional: usize) {
+        use RecordData::*;
+        match self {
+            Int(v) | Bool(v) => v.reserve(additional),
+            Chars(v) | LongChars { values: v, .. } => v.reserve(additional),
+            F32(v) => v.reserve(additional),
+            F64(v) => v.reserve(additional),
+            Message => {}
+        }
+    }
+
+    /// Number of elements in the underlying data.
+    pub(crate) fn n_elements(&self) -> usize {
+        use RecordData::*;
+        match self {
+            Int(v) | Bool(v) => v.len(),
+            Chars(v) | LongChars { values: v, .. } => v.len(),
+            F32(v) => v.len(),
+            F64(v) => v.len(),
+            Message => 0,
+        }
+    }
+
+    /// The type identifier, the element size and the maximum number of elements per sub-block
+    /// used when writing the data out. Strings longer than 8 characters require the `C0nn` type,
+    /// long strings keep their declared width unless their values don't fit into it.
+    fn write_layout(&self) -> (String, usize, usize) {
+        use RecordData::*;
+        match self {
+            Int(_) | Bool(_) | F32(_) => (self.kind_string(), 4, NUM_BLOCK_LENGTH),
+            F64(_) => (self.kind_string(), 8, NUM_BLOCK_LENGTH),
+            Message => (self.kind_string(), 0, NUM_BLOCK_LENGTH),
+            Chars(v) => match v.iter().map(|s| s.len()).max() {
+                Some(len) if len > FIXED_STRING_LENGTH => {
+                    (format!("C{:03}", len), len, STR_BLOCK_LENGTH)
+                }
+                _ => (self.kind_string(), FIXED_STRING_LENGTH, STR_BLOCK_LENGTH),
+            },
+            LongChars { width, values } => {
+                let width = values.iter().map(|s| s.len()).fold(*width, usize::max);
+                (format!("C{:03}", width), width, STR_BLOCK_LENGTH)
+            }
+        }
+    }
+
+    /// Append the binary representation of all elements to the byte vector.
+    fn write_elements(&self, element_size: usize, output: &mut Vec<u8>) {
+        use RecordData::*;
+        match self {
+            Int(v) | Bool(v) => v.iter().for_each(|&x| bp::write_i32(x, output)),
+            F32(v) => v.iter().for_each(|&x| bp::write_f32(x, output)),
+            F64(v) => v.iter().for_each(|&x| bp::write_f64(x, output)),
+            Chars(v) | LongChars { values: v, .. } => v
+                .iter()
+                .for_each(|x| bp::write_str(x, element_size, output)),
+            Message => (),
+        }
     }
 
     /// Populate Data instance from the byte slice. Use header info to infer the number of bytes to
     /// read and how to interpret them. The function will panic if the input slice is not fully
     /// consumed.
-    fn populate(&mut self, header: &Header, input: &[u8]) -> Result<()> {
+    fn populate(&mut self, header: &Header, input: &[u8], order: ByteOrder) -> Result<()> {
+        // Messages hold no values, whatever the header claims, as for the formatted files.
+        if let RecordData::Message = self {
+            return Ok(());
+        }
+
+        // The header alone can't be trusted with the allocation size, but the complete body can.
+        self.reserve(header.n_elements);
+
         // keep reading bytes from the input until we collected the requested number of elements
         let mut n_remaining_elements = header.n_elements;
         let mut rest = input;
@@ -143,10 +285,11 @@ impl RecordData {
         while n_remaining_elements > 0 {
             // read at most the block_length number of elements
             let to_read = std::cmp::min(header.block_length, n_remaining_elements);
-            let (block_bytes, input) = bp::take_block_exact(to_read * header.element_size, rest)?;
+            let (block_bytes, input) =
+                bp::take_block_exact(to_read * header.element_size, rest, order)?;
 
             // add the current block to the constructed instance
-            self.push(block_bytes, header.element_size);
+            self.push(block_bytes, header.element_size, order)?;
 
             n_remaining_elements -= to_read;
             rest = input;
@@ -185,42 +328,31 @@ impl Header {
         use RecordData::*;
 
         let (element_size, block_length, data) = match type_id.as_bytes() {
-            b"INTE" => (
-                mem::size_of::<i32>(),
-                NUM_BLOCK_LENGTH,
-                Int(Vec::with_capacity(n_elements)),
-            ),
-            b"REAL" => (
-                mem::size_of::<f32>(),
-                NUM_BLOCK_LENGTH,
-                F32(Vec::with_capacity(n_elements)),
-            ),
-            b"DOUB" => (
-                mem::size_of::<f64>(),
-                NUM_BLOCK_LENGTH,
-                F64(Vec::with_capacity(n_elements)),
-            ),
+            b"INTE" => (mem::size_of::<i32>(), NUM_BLOCK_LENGTH, Int(Vec::new())),
+            b"REAL" => (mem::size_of::<f32>(), NUM_BLOCK_LENGTH, F32(Vec::new())),
+            b"DOUB" => (mem::size_of::<f64>(), NUM_BLOCK_LENGTH, F64(Vec::new())),
             // i32 is the underlying "logical" type in Eclipse files
-            b"LOGI" => (
-                mem::size_of::<i32>(),
-                NUM_BLOCK_LENGTH,
-                Bool(Vec::with_capacity(n_elements)),
-            ),
+            b"LOGI" => (mem::size_of::<i32>(), NUM_BLOCK_LENGTH, Bool(Vec::new())),
             b"MESS" => (0, NUM_BLOCK_LENGTH, Message),
-            b"CHAR" => (
-                FIXED_STRING_LENGTH,
-                STR_BLOCK_LENGTH,
-                Chars(Vec::with_capacity(n_elements)),
-            ),
+            b"CHAR" => (FIXED_STRING_LENGTH, STR_BLOCK_LENGTH, Chars(Vec::new())),
             [b'C', b'0', rest @ ..] => {
-                let len = if rest.iter().all(u8::is_ascii_digit) {
-                    unsafe { str::from_utf8_unchecked(rest).parse().unwrap() }
-                } else {
-                    return Err(EclairError::InvalidC0nnLength(
-                        String::from_utf8_lossy(rest).to_string(),
-                    ));
+                // Zero-length strings would make the body impossible to split into elements.
+                let len = match str::from_utf8(rest).ok().and_then(|len| len.parse().ok()) {
+                    Some(len) if len > 0 && rest.iter().all(u8::is_ascii_digit) => len,
+                    _ => {
+                        return Err(EclairError::InvalidC0nnLength(
+                            String::from_utf8_lossy(rest).to_string(),
+                        ))
+                    }
                 };
-                (len, STR_BLOCK_LENGTH, Chars(Vec::with_capacity(n_elements)))
+                (
+                    len,
+                    STR_BLOCK_LENGTH,
+                    LongChars {
+                        width: len,
+                        values: Vec::new(),
+                    },
+                )
             }
             _ => {
                 return Err(EclairError::InvalidDataType(type_id.to_string()));
@@ -241,15 +373,21 @@ impl Header {
 
 /// Extract information from the record header. Returns the header and the correct empty RecordData
 /// variant to be populated with values.
-fn extract_header_info(input: &[u8; 24]) -> Result<(Header, RecordData)> {
+fn extract_header_info(input: &[u8; 24], order: ByteOrder) -> Result<(Header, RecordData)> {
     // Strip the head/tail markers
-    let (header, _) = bp::take_block_exact(16, input)?;
+    let (header, _) = bp::take_block_exact(16, input, order)?;
 
     // 8-char long record name.
     let (name, header) = bp::take_str(8, header)?;
 
     // 4-byte integer for the number of elements in the body that follows the current header.
-    let (n_elements, header) = bp::take_i32(header)?;
+    let (n_elements, header) = bp::take_i32(header, order)?;
+    if n_elements < 0 {
+        return Err(EclairError::NegativeElementCount {
+            name: name.to_string(),
+            n_elements,
+        });
+    }
 
     // 4-char long data type identifier.
     let (type_id, header) = bp::take_str(4, header)?;
@@ -266,74 +404,788 @@ pub struct Record {
     pub(crate) data: RecordData,
 }
 
+impl Record {
+    /// Create a new record, e.g. to be written out with `WriteRecord`.
+    pub fn new(name: &str, data: RecordData) -> Self {
+        Self {
+            name: FlexString::from(name),
+            data,
+        }
+    }
+
+    /// The record name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The record body.
+    pub fn data(&self) -> &RecordData {
+        &self.data
+    }
+
+    /// Consume the record and return its body.
+    pub fn into_data(self) -> RecordData {
+        self.data
+    }
+}
+
+/// Record metadata, i.e. everything but the values. Useful for quickly indexing large files.
+#[derive(Debug, PartialEq)]
+pub struct RecordMeta {
+    pub name: FlexString,
+    pub kind: RecordDataKind,
+    pub n_elements: usize,
+}
+
+impl From<&Record> for RecordMeta {
+    fn from(record: &Record) -> Self {
+        Self {
+            name: record.name.clone(),
+            kind: *record.data.kind(),
+            n_elements: record.data.n_elements(),
+        }
+    }
+}
+
 /// Implementors of the `ReadRecord` can produce Eclipse records.
 pub trait ReadRecord {
     /// Read a new Eclipse record. If successful, this function will return
-    /// the total size of the record in bytes. Zero bytes mean that the stream has reached EOF.
+    /// the total size of the record in bytes. No record means that the stream has reached EOF. A
+    /// record cut short by the EOF is reported as an error for which
+    /// `EclairError::is_truncation` holds.
     fn read_record(&mut self) -> Result<(usize, Option<Record>)>;
 
+    /// Read the metadata of the next Eclipse record, skipping its body where possible. The return
+    /// value has the same meaning as for `read_record`.
+    fn scan_record(&mut self) -> Result<(usize, Option<RecordMeta>)> {
+        let (n_bytes, record) = self.read_record()?;
+        Ok((n_bytes, record.as_ref().map(RecordMeta::from)))
+    }
+
+    /// Reject records with more than `max_elements` elements as `EclairError::TooManyElements`
+    /// before reading their body, so that a garbled header doesn't make the reader allocate a huge
+    /// buffer. Readers that check the available input before allocating ignore the limit.
+    fn set_max_elements(&mut self, max_elements: Option<usize>) {
+        let _ = max_elements;
+    }
+
     /// Returns an iterator over the records of this reader.
     fn records(self) -> Records<Self>
     where
         Self: Sized,
     {
-        Records { buf: self }
+        Records {
+            buf: self,
+            failed: false,
+        }
     }
 }
 
-/// An iterator over the records of an instance of ReadRecord.
+/// Implementors of the `WriteRecord` can consume Eclipse records.
+pub trait WriteRecord {
+    /// Write an Eclipse record. If successful, this function will return the total size of the
+    /// record in bytes.
+    fn write_record(&mut self, record: &Record) -> Result<usize>;
+}
+
+/// An iterator over the records of an instance of ReadRecord. It ends after the first error, e.g.
+/// a record truncated by the EOF, since the records that follow a malformed one can't be located.
 pub struct Records<B> {
     buf: B,
+    failed: bool,
 }
 
 impl<B: ReadRecord> Iterator for Records<B> {
     type Item = Result<Record>;
 
     fn next(&mut self) -> Option<Result<Record>> {
+        if self.failed {
+            return None;
+        }
+
         match self.buf.read_record() {
-            Ok((0, None)) => None,
-            Ok((0, Some(_))) => {
-                unimplemented!("read_record() returned a record but encountered an EOF.")
-            }
-            Ok((_n, None)) => {
-                unimplemented!("read_record() returned None but did not encounter an EOF.")
+            Ok((_, None)) => None,
+            Ok((_, Some(record))) => Some(Ok(record)),
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
             }
-            Ok((_n, Some(record))) => Some(Ok(record)),
-            Err(e) => Some(Err(e)),
         }
     }
 }
 
 /// Implementation of ReadRecord for any type that implements std::io::Read (e.g. a file or
-/// a network socket).
+/// a network socket). The data is expected to be big-endian.
 impl<T> ReadRecord for T
 where
     T: std::io::Read,
 {
     fn read_record(&mut self) -> Result<(usize, Option<Record>)> {
-        // Read the header from the next 24 bytes.
-        let mut header_buf = [0u8; 24];
-        let header_bytes = self.read(&mut header_buf)?;
+        read_binary_record(
+            self,
+            &mut Some(ByteOrder::BigEndian),
+            &mut Vec::new(),
+            &RecordReaderOptions::default(),
+        )
+    }
+
+    fn scan_record(&mut self) -> Result<(usize, Option<RecordMeta>)> {
+        scan_binary_record(self, &mut Some(ByteOrder::BigEndian))
+    }
+}
+
+/// Read a single binary record header. If the byte order is not known yet, it is detected from the
+/// header. Returns None on EOF.
+fn read_binary_header<R>(
+    reader: &mut R,
+    order: &mut Option<ByteOrder>,
+) -> Result<Option<(Header, RecordData, ByteOrder)>>
+where
+    R: std::io::Read + ?Sized,
+{
+    // Read the header from the next 24 bytes.
+    let mut header_buf = [0u8; 24];
+    let header_bytes = read_up_to(reader, &mut header_buf)?;
+
+    if header_bytes == 0 {
+        // reached EOF
+        return Ok(None);
+    }
+
+    if header_bytes < 24 {
+        return Err(truncated_header(&header_buf[..header_bytes]));
+    }
+
+    parse_binary_header(&header_buf, order).map(Some)
+}
+
+/// Fill the buffer from the reader, stopping short only at EOF. Returns the number of bytes read.
+fn read_up_to<R>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize>
+where
+    R: std::io::Read + ?Sized,
+{
+    let mut n_bytes = 0;
+    while n_bytes < buf.len() {
+        match reader.read(&mut buf[n_bytes..]) {
+            Ok(0) => break,
+            Ok(n) => n_bytes += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n_bytes)
+}
+
+/// The error for a header cut short by the EOF. The record name follows the 4-byte head marker, we
+/// report as much of it as there is.
+fn truncated_header(header_bytes: &[u8]) -> EclairError {
+    let name = header_bytes.get(4..).unwrap_or_default();
+    EclairError::TruncatedRecord {
+        record_name: String::from_utf8_lossy(&name[..name.len().min(8)])
+            .trim_end()
+            .to_owned(),
+        expected: 24,
+        got: header_bytes.len(),
+    }
+}
+
+/// The error for a record body cut short by the EOF, with the byte counts of the whole record.
+fn truncated_body(header: &Header, body_bytes: usize) -> EclairError {
+    EclairError::TruncatedRecord {
+        record_name: header.name.to_string(),
+        expected: 24 + header.len_bytes(),
+        got: 24 + body_bytes,
+    }
+}
+
+/// Parse the header bytes, detecting the byte order first if it is not known yet.
+fn parse_binary_header(
+    header_buf: &[u8; 24],
+    order: &mut Option<ByteOrder>,
+) -> Result<(Header, RecordData, ByteOrder)> {
+    // Unrecognized markers are reported as errors while parsing the header in the default order.
+    let byte_order =
+        *order.get_or_insert_with(|| ByteOrder::detect(header_buf).unwrap_or(ByteOrder::BigEndian));
+
+    let (header, data) = extract_header_info(header_buf, byte_order)?;
+    Ok((header, data, byte_order))
+}
+
+/// Check the number of record elements against the limit, if any.
+fn check_n_elements(name: &str, n_elements: usize, max_elements: Option<usize>) -> Result<()> {
+    match max_elements {
+        Some(max_elements) if n_elements > max_elements => Err(EclairError::TooManyElements {
+            name: name.to_owned(),
+            n_elements,
+            max_elements,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Read a single binary record. If the byte order is not known yet, it is detected from the record
+/// header. The record body is read into the buffer, which grows as needed.
+fn read_binary_record<R>(
+    reader: &mut R,
+    order: &mut Option<ByteOrder>,
+    body_buf: &mut Vec<u8>,
+    options: &RecordReaderOptions,
+) -> Result<(usize, Option<Record>)>
+where
+    R: std::io::Read + ?Sized,
+{
+    let (header, mut data, byte_order) = match read_binary_header(reader, order)? {
+        Some(header) => header,
+        None => return Ok((0, None)),
+    };
+    check_n_elements(&header.name, header.n_elements, options.max_elements)?;
+    if header.len_bytes() > options.max_record_bytes {
+        return Err(EclairError::RecordTooLarge {
+            name: header.name.to_string(),
+            bytes: header.len_bytes(),
+        });
+    }
+
+    if body_buf.len() < header.len_bytes() {
+        body_buf.resize(header.len_bytes(), 0);
+    }
+    let body = &mut body_buf[..header.len_bytes()];
+    let body_bytes = read_up_to(reader, body)?;
+    if body_bytes < body.len() {
+        return Err(truncated_body(&header, body_bytes));
+    }
+
+    data.populate(&header, body, byte_order)?;
+
+    let total_bytes = 24 + header.len_bytes();
+
+    Ok((
+        total_bytes,
+        Some(Record {
+            name: header.name,
+            data,
+        }),
+    ))
+}
+
+/// Read a single binary record header and discard the record body.
+fn scan_binary_record<R>(
+    reader: &mut R,
+    order: &mut Option<ByteOrder>,
+) -> Result<(usize, Option<RecordMeta>)>
+where
+    R: std::io::Read + ?Sized,
+{
+    let (header, data, _) = match read_binary_header(reader, order)? {
+        Some(header) => header,
+        None => return Ok((0, None)),
+    };
+
+    let body_bytes = header.len_bytes() as u64;
+    let skipped = std::io::copy(
+        &mut std::io::Read::take(&mut *reader, body_bytes),
+        &mut std::io::sink(),
+    )?;
+    if skipped < body_bytes {
+        return Err(truncated_body(&header, skipped as usize));
+    }
+
+    Ok((
+        24 + header.len_bytes(),
+        Some(RecordMeta {
+            name: header.name,
+            kind: *data.kind(),
+            n_elements: header.n_elements,
+        }),
+    ))
+}
+
+/// The default limit on the size of a single binary record body.
+pub const DEFAULT_MAX_RECORD_BYTES: usize = 256 * 1024 * 1024;
+
+/// Limits applied by `RecordReader` before it allocates the body of a record, which protect it
+/// from corrupt or malicious headers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordReaderOptions {
+    /// Records with larger bodies are rejected as `EclairError::RecordTooLarge`
+    pub max_record_bytes: usize,
+
+    /// Records with more elements are rejected as `EclairError::TooManyElements`
+    pub max_elements: Option<usize>,
+}
+
+impl Default for RecordReaderOptions {
+    fn default() -> Self {
+        Self {
+            max_record_bytes: DEFAULT_MAX_RECORD_BYTES,
+            max_elements: None,
+        }
+    }
+}
+
+/// A reader for binary Eclipse files of either byte order. Unless specified upfront, the byte order
+/// is detected from the first record header. Record bodies are read into a buffer that is reused
+/// across records, so that reading many records doesn't allocate a new one for each.
+pub struct RecordReader<R> {
+    inner: R,
+    byte_order: Option<ByteOrder>,
+    body_buf: Vec<u8>,
+    options: RecordReaderOptions,
+
+    // Length of the stream as last measured by `skip_record`.
+    stream_len: Option<u64>,
+}
+
+impl<R: std::io::Read> RecordReader<R> {
+    /// Create a reader that detects the byte order of the data.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            byte_order: None,
+            body_buf: Vec::new(),
+            options: RecordReaderOptions::default(),
+            stream_len: None,
+        }
+    }
+
+    /// Create a reader for the data of the known byte order.
+    pub fn with_byte_order(inner: R, byte_order: ByteOrder) -> Self {
+        Self {
+            inner,
+            byte_order: Some(byte_order),
+            body_buf: Vec::new(),
+            options: RecordReaderOptions::default(),
+            stream_len: None,
+        }
+    }
+
+    /// Set the limits on the records to read. Defaults to `RecordReaderOptions::default()`.
+    pub fn with_options(self, options: RecordReaderOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    /// The byte order of the data, if known.
+    pub fn byte_order(&self) -> Option<ByteOrder> {
+        self.byte_order
+    }
+}
+
+impl<R: std::io::Read> ReadRecord for RecordReader<R> {
+    fn read_record(&mut self) -> Result<(usize, Option<Record>)> {
+        read_binary_record(
+            &mut self.inner,
+            &mut self.byte_order,
+            &mut self.body_buf,
+            &self.options,
+        )
+    }
+
+    fn scan_record(&mut self) -> Result<(usize, Option<RecordMeta>)> {
+        scan_binary_record(&mut self.inner, &mut self.byte_order)
+    }
+
+    fn set_max_elements(&mut self, max_elements: Option<usize>) {
+        self.options.max_elements = max_elements;
+    }
+}
+
+impl<R: std::io::Read + Seek> RecordReader<R> {
+    /// Read the metadata of the next record and seek past its body instead of reading it through.
+    /// The return value has the same meaning as for `scan_record`, which is the fallback for
+    /// readers that can't seek.
+    pub fn skip_record(&mut self) -> Result<(usize, Option<RecordMeta>)> {
+        let (header, data, _) = match read_binary_header(&mut self.inner, &mut self.byte_order)? {
+            Some(header) => header,
+            None => return Ok((0, None)),
+        };
+
+        let body_bytes = header.len_bytes() as u64;
+        let body_start = self.inner.stream_position()?;
+        let body_end = body_start + body_bytes;
+
+        // Seeking past the end of a file succeeds, so check the body is all there. The stream length
+        // is only measured again when the file may have grown since.
+        let stream_len = match self.stream_len {
+            Some(len) if len >= body_end => len,
+            _ => {
+                let len = self.inner.seek(SeekFrom::End(0))?;
+                self.inner.seek(SeekFrom::Start(body_start))?;
+                self.stream_len = Some(len);
+                len
+            }
+        };
+        if stream_len < body_end {
+            let available = stream_len.saturating_sub(body_start);
+            return Err(truncated_body(&header, available as usize));
+        }
+        self.inner.seek(SeekFrom::Current(body_bytes as i64))?;
+
+        Ok((
+            24 + header.len_bytes(),
+            Some(RecordMeta {
+                name: header.name,
+                kind: *data.kind(),
+                n_elements: header.n_elements,
+            }),
+        ))
+    }
+}
+
+impl<R: Seek> Seek for RecordReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// A parsed record header along with the borrowed bytes of the record body.
+type RawRecord<'a> = (Header, RecordData, ByteOrder, &'a [u8]);
+
+/// A reader for binary Eclipse records stored in memory, e.g. a network frame. Unlike the
+/// `std::io::Read` path, record bodies are parsed directly from the borrowed slice without copying
+/// them into an intermediate buffer first. The byte order is detected as in `RecordReader`.
+pub struct SliceReader<'a> {
+    input: &'a [u8],
+    byte_order: Option<ByteOrder>,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Create a reader that detects the byte order of the data.
+    pub fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            byte_order: None,
+        }
+    }
+
+    /// Create a reader for the data of the known byte order.
+    pub fn with_byte_order(input: &'a [u8], byte_order: ByteOrder) -> Self {
+        Self {
+            input,
+            byte_order: Some(byte_order),
+        }
+    }
+
+    /// The byte order of the data, if known.
+    pub fn byte_order(&self) -> Option<ByteOrder> {
+        self.byte_order
+    }
+
+    /// The bytes that have not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.input
+    }
+
+    /// Split the next record into its header and body bytes. Returns None once the input is empty.
+    fn next_record(&mut self) -> Result<Option<RawRecord<'a>>> {
+        if self.input.is_empty() {
+            return Ok(None);
+        }
+
+        if self.input.len() < 24 {
+            return Err(truncated_header(self.input));
+        }
+        let (header_bytes, rest) = self.input.split_at(24);
+        let (header, data, byte_order) =
+            parse_binary_header(header_bytes.try_into().unwrap(), &mut self.byte_order)?;
+
+        if rest.len() < header.len_bytes() {
+            return Err(truncated_body(&header, rest.len()));
+        }
+        let (body, rest) = rest.split_at(header.len_bytes());
+        self.input = rest;
+
+        Ok(Some((header, data, byte_order, body)))
+    }
+}
+
+impl<'a> ReadRecord for SliceReader<'a> {
+    fn read_record(&mut self) -> Result<(usize, Option<Record>)> {
+        let (header, mut data, byte_order, body) = match self.next_record()? {
+            Some(record) => record,
+            None => return Ok((0, None)),
+        };
+
+        data.populate(&header, body, byte_order)?;
+
+        Ok((
+            24 + body.len(),
+            Some(Record {
+                name: header.name,
+                data,
+            }),
+        ))
+    }
+
+    fn scan_record(&mut self) -> Result<(usize, Option<RecordMeta>)> {
+        let (header, data, _, body) = match self.next_record()? {
+            Some(record) => record,
+            None => return Ok((0, None)),
+        };
+
+        Ok((
+            24 + body.len(),
+            Some(RecordMeta {
+                name: header.name,
+                kind: *data.kind(),
+                n_elements: header.n_elements,
+            }),
+        ))
+    }
+}
+
+/// A reader for binary Eclipse files mapped into memory, which parses the records directly from
+/// the mapped bytes like `SliceReader` does. If the file grows, the new data is mapped once the
+/// reader reaches the end of the current mapping or seeks.
+#[cfg(feature = "mmap")]
+pub struct MmapReader {
+    file: std::fs::File,
+    map: memmap2::Mmap,
+    pos: usize,
+    byte_order: Option<ByteOrder>,
+}
+
+#[cfg(feature = "mmap")]
+impl MmapReader {
+    /// Map the file into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be truncated or modified in place while the reader exists, only appended
+    /// to. Reading bytes that have been truncated away crashes the process (e.g. with `SIGBUS`),
+    /// and modified bytes break the assumptions of the parser about the data it has validated.
+    pub unsafe fn new(file: std::fs::File) -> Result<Self> {
+        let map = memmap2::Mmap::map(&file)?;
+        Ok(Self {
+            file,
+            map,
+            pos: 0,
+            byte_order: None,
+        })
+    }
+
+    /// The byte order of the data, if known.
+    pub fn byte_order(&self) -> Option<ByteOrder> {
+        self.byte_order
+    }
+
+    /// Map the file again if it has grown since it was last mapped.
+    fn remap_if_grown(&mut self) -> std::io::Result<()> {
+        if self.file.metadata()?.len() > self.map.len() as u64 {
+            // SAFETY: the caller of `new` has promised not to truncate or modify the file.
+            self.map = unsafe { memmap2::Mmap::map(&self.file)? };
+        }
+        Ok(())
+    }
+
+    /// Run the reading function on the unread mapped bytes and advance past the consumed ones.
+    fn read_with<T>(&mut self, read: impl FnOnce(&mut SliceReader) -> Result<T>) -> Result<T> {
+        if self.pos >= self.map.len() {
+            self.remap_if_grown()?;
+        }
+
+        let start = self.pos.min(self.map.len());
+        let mut reader = SliceReader {
+            input: &self.map[start..],
+            byte_order: self.byte_order,
+        };
+        let result = read(&mut reader)?;
+
+        self.pos = self.map.len() - reader.remaining().len();
+        self.byte_order = reader.byte_order();
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl ReadRecord for MmapReader {
+    fn read_record(&mut self) -> Result<(usize, Option<Record>)> {
+        self.read_with(|reader| reader.read_record())
+    }
+
+    fn scan_record(&mut self) -> Result<(usize, Option<RecordMeta>)> {
+        self.read_with(|reader| reader.scan_record())
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Seek for MmapReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.remap_if_grown()?;
+
+        let target = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => self.map.len() as i64 + offset,
+            SeekFrom::Current(offset) => self.pos as i64 + offset,
+        };
+        if target < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative position",
+            ));
+        }
+
+        self.pos = target as usize;
+        Ok(self.pos as u64)
+    }
+}
+
+/// A reader for formatted (ASCII) Eclipse files, e.g. `.FSMSPEC` and `.FUNSMRY`. It produces the
+/// same records as the binary reader does for the equivalent binary files.
+pub struct FormattedReader<R> {
+    inner: R,
+    line: String,
+    max_elements: Option<usize>,
+}
+
+impl<R: BufRead> FormattedReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            line: String::new(),
+            max_elements: None,
+        }
+    }
 
-        if header_bytes == 0 {
-            // reached EOF
-            return Ok((0, None));
+    /// Read the next line into the internal buffer and return the number of bytes read. Zero bytes
+    /// mean that the stream has reached EOF.
+    fn next_line(&mut self) -> Result<usize> {
+        self.line.clear();
+        Ok(self.inner.read_line(&mut self.line)?)
+    }
+}
+
+/// Split a line of formatted values into individual tokens. Tokens are separated by whitespace,
+/// unless enclosed in apostrophes, in which case the apostrophes are stripped and the token is
+/// taken verbatim.
+fn split_formatted_line(line: &str) -> Result<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut rest = line.trim_start();
+
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('\'') {
+            let end = quoted.find('\'').ok_or_else(|| {
+                EclairError::InvalidFormattedValue(format!("Unterminated string: {}", rest))
+            })?;
+            tokens.push(&quoted[..end]);
+            rest = quoted[end + 1..].trim_start();
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            tokens.push(&rest[..end]);
+            rest = rest[end..].trim_start();
         }
+    }
+    Ok(tokens)
+}
+
+/// Parse a Fortran-style floating point number. Besides the usual `E` notation, Fortran may use
+/// `D` for double precision exponents and drop the exponent letter altogether for three-digit
+/// exponents (e.g. `0.10000000-100`).
+fn parse_fortran_float(token: &str) -> Result<f64> {
+    let invalid = || EclairError::InvalidFormattedValue(token.to_string());
+
+    let normalized = token.replace(['D', 'd'], "E");
+    if let Ok(v) = normalized.parse() {
+        return Ok(v);
+    }
+
+    // Look for the exponent sign that is not preceded by an exponent letter.
+    let split = normalized[1..]
+        .rfind(['+', '-'])
+        .map(|pos| pos + 1)
+        .ok_or_else(invalid)?;
+    format!("{}E{}", &normalized[..split], &normalized[split..])
+        .parse()
+        .map_err(|_| invalid())
+}
+
+impl RecordData {
+    /// Push a single formatted value into the binary data instance interpreting it as necessary.
+    fn push_formatted(&mut self, token: &str) -> Result<()> {
+        use RecordData::*;
 
-        // If we are close to the EOF, we might not get the entire header from calling the read()
-        // above.
-        if header_bytes < 24 {
-            self.read_exact(&mut header_buf[header_bytes..])?;
+        let invalid = || EclairError::InvalidFormattedValue(token.to_string());
+        match self {
+            Int(v) => v.push(token.parse().map_err(|_| invalid())?),
+            // Eclipse uses -1 for true and 0 for false.
+            Bool(v) => v.push(match token {
+                "T" | "t" => -1,
+                "F" | "f" => 0,
+                _ => return Err(invalid()),
+            }),
+            F32(v) => v.push(parse_fortran_float(token)? as f32),
+            F64(v) => v.push(parse_fortran_float(token)?),
+            Chars(v) | LongChars { values: v, .. } => v.push(FlexString::from(token.trim())),
+            Message => unimplemented!("Attempted to push into a RecordData::Message instance."),
         }
+        Ok(())
+    }
+}
 
-        let (header, mut data) = extract_header_info(&header_buf)?;
+impl<R: BufRead> ReadRecord for FormattedReader<R> {
+    fn read_record(&mut self) -> Result<(usize, Option<Record>)> {
+        // Skip any blank lines preceding the header.
+        let mut total_bytes = 0;
+        loop {
+            let n_bytes = self.next_line()?;
+            if n_bytes == 0 {
+                // reached EOF
+                return Ok((0, None));
+            }
+            total_bytes += n_bytes;
+            if !self.line.trim().is_empty() {
+                break;
+            }
+        }
 
-        let mut body_buf = vec![0u8; header.len_bytes()];
-        self.read_exact(&mut body_buf)?;
+        let (header, mut data) = match split_formatted_line(&self.line)?.as_slice() {
+            [name, n_elements, type_id] => {
+                let n_elements = n_elements.parse().map_err(|_| {
+                    EclairError::InvalidFormattedHeader(self.line.trim().to_string())
+                })?;
+                check_n_elements(name.trim(), n_elements, self.max_elements)?;
+                Header::with_record_data(
+                    FlexString::from(name.trim()),
+                    FlexString::from(type_id.trim()),
+                    n_elements,
+                )?
+            }
+            _ => {
+                return Err(EclairError::InvalidFormattedHeader(
+                    self.line.trim().to_string(),
+                ))
+            }
+        };
 
-        data.populate(&header, &body_buf)?;
+        let mut n_remaining_elements = if let RecordData::Message = data {
+            0
+        } else {
+            header.n_elements
+        };
 
-        let total_bytes = 24 + header.len_bytes();
+        while n_remaining_elements > 0 {
+            let n_bytes = self.next_line()?;
+            if n_bytes == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            total_bytes += n_bytes;
+
+            // An invalid last line without the line break may still be being written.
+            let line_complete = self.line.ends_with('\n');
+            let truncated = |err| {
+                if line_complete {
+                    err
+                } else {
+                    std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()
+                }
+            };
+            for token in split_formatted_line(&self.line).map_err(truncated)? {
+                if n_remaining_elements == 0 {
+                    return Err(EclairError::InvalidFormattedValue(token.to_string()));
+                }
+                data.push_formatted(token).map_err(truncated)?;
+                n_remaining_elements -= 1;
+            }
+        }
 
         Ok((
             total_bytes,
@@ -343,6 +1195,55 @@ where
             }),
         ))
     }
+
+    fn set_max_elements(&mut self, max_elements: Option<usize>) {
+        self.max_elements = max_elements;
+    }
+}
+
+/// Records always start on a new line, so seeking the formatted reader is as good as seeking the
+/// underlying stream.
+impl<R: Seek> Seek for FormattedReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// Implementation of WriteRecord for any type that implements std::io::Write (e.g. a file or
+/// a network socket).
+impl<T> WriteRecord for T
+where
+    T: std::io::Write,
+{
+    fn write_record(&mut self, record: &Record) -> Result<usize> {
+        if record.name.len() > FIXED_STRING_LENGTH {
+            return Err(EclairError::InvalidRecordName(record.name.to_string()));
+        }
+
+        let (type_id, element_size, block_length) = record.data.write_layout();
+        let n_elements = record.data.n_elements();
+
+        // The header block.
+        let mut header = Vec::with_capacity(16);
+        bp::write_str(&record.name, FIXED_STRING_LENGTH, &mut header);
+        bp::write_i32(n_elements as i32, &mut header);
+        bp::write_str(&type_id, 4, &mut header);
+
+        let mut output = Vec::new();
+        bp::write_block(&header, &mut output);
+
+        // The body is split into sub-blocks of at most block_length elements each.
+        let mut body = Vec::with_capacity(n_elements * element_size);
+        record.data.write_elements(element_size, &mut body);
+
+        if element_size > 0 {
+            body.chunks(block_length * element_size)
+                .for_each(|block| bp::write_block(block, &mut output));
+        }
+
+        self.write_all(&output)?;
+        Ok(output.len())
+    }
 }
 
 #[cfg(test)]
@@ -352,11 +1253,12 @@ mod tests {
     use std::{
         fs::File,
         io::{BufReader, Cursor},
+        iter,
     };
 
     #[test]
     fn single_data_array_short() {
-        let input = include_bytes!("../assets/single_data_array.bin");
+        let input = include_bytes!("../../assets/single_data_array.bin");
         let mut cursor = Cursor::new(input.as_ref());
 
         let (n_bytes, record) = cursor.read_record().unwrap();
@@ -383,7 +1285,7 @@ mod tests {
 
     #[test]
     fn read_spe_10() {
-        let file = File::open("assets/SPE10.SMSPEC").unwrap();
+        let file = File::open("../assets/SPE10.SMSPEC").unwrap();
         let buf_reader = BufReader::new(file);
 
         let records: Vec<Record> = buf_reader.records().map(|r| r.unwrap()).collect();
@@ -406,4 +1308,414 @@ mod tests {
             }
         );
     }
+
+    /// Convert big-endian binary records into little-endian ones.
+    fn to_little_endian(mut input: &[u8]) -> Vec<u8> {
+        // Copy a single block reversing the byte order of its elements. Returns the data length.
+        fn swap_block(input: &mut &[u8], element_size: usize, output: &mut Vec<u8>) -> usize {
+            let len = bp::read_i32(&input[..4], ByteOrder::BigEndian) as usize;
+            output.extend_from_slice(&(len as i32).to_le_bytes());
+            for element in input[4..4 + len].chunks(element_size) {
+                output.extend(element.iter().rev());
+            }
+            output.extend_from_slice(&(len as i32).to_le_bytes());
+            *input = &input[len + 8..];
+            len
+        }
+
+        let mut output = Vec::new();
+        while !input.is_empty() {
+            // Only the number of elements needs swapping in the header.
+            let n_elements = bp::read_i32(&input[12..16], ByteOrder::BigEndian);
+            let type_id = &input[16..20];
+            output.extend_from_slice(&16i32.to_le_bytes());
+            output.extend_from_slice(&input[4..12]);
+            output.extend_from_slice(&n_elements.to_le_bytes());
+            output.extend_from_slice(type_id);
+            output.extend_from_slice(&16i32.to_le_bytes());
+
+            let (element_size, swapped) = match type_id {
+                b"INTE" | b"REAL" | b"LOGI" => (4, true),
+                b"DOUB" => (8, true),
+                b"CHAR" => (8, false),
+                b"MESS" => (0, false),
+                _ => (
+                    str::from_utf8(&type_id[1..]).unwrap().parse().unwrap(),
+                    false,
+                ),
+            };
+            input = &input[24..];
+
+            let mut n_bytes = n_elements as usize * element_size;
+            while n_bytes > 0 {
+                n_bytes -= swap_block(
+                    &mut input,
+                    if swapped { element_size } else { 1 },
+                    &mut output,
+                );
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn little_endian_spe_10() {
+        for path in &["../assets/SPE10.SMSPEC", "../assets/SPE10.UNSMRY"] {
+            let input = std::fs::read(path).unwrap();
+            let expected: Vec<Record> = Cursor::new(&input).records().map(|r| r.unwrap()).collect();
+
+            let mut reader = RecordReader::new(Cursor::new(to_little_endian(&input)));
+            assert_eq!(reader.byte_order(), None);
+            let records: Vec<Record> = iter::from_fn(|| reader.read_record().unwrap().1).collect();
+            assert_eq!(reader.byte_order(), Some(ByteOrder::LittleEndian));
+            assert_eq!(records, expected);
+
+            // Big-endian data is still detected correctly.
+            let mut reader = RecordReader::new(Cursor::new(&input));
+            let records: Vec<Record> = iter::from_fn(|| reader.read_record().unwrap().1).collect();
+            assert_eq!(reader.byte_order(), Some(ByteOrder::BigEndian));
+            assert_eq!(records, expected);
+        }
+    }
+
+    #[test]
+    fn slice_reader_spe_10() {
+        for path in &["../assets/SPE10.SMSPEC", "../assets/SPE10.UNSMRY"] {
+            let input = std::fs::read(path).unwrap();
+            let expected: Vec<Record> = Cursor::new(&input).records().map(|r| r.unwrap()).collect();
+
+            let records: Vec<Record> = SliceReader::new(&input)
+                .records()
+                .map(|r| r.unwrap())
+                .collect();
+            assert_eq!(records, expected);
+
+            let little_endian = to_little_endian(&input);
+            let mut reader = SliceReader::new(&little_endian);
+            let records: Vec<Record> = iter::from_fn(|| reader.read_record().unwrap().1).collect();
+            assert_eq!(reader.byte_order(), Some(ByteOrder::LittleEndian));
+            assert_eq!(records, expected);
+
+            let mut reader = SliceReader::new(&input);
+            let mut n_bytes = 0;
+            for record in &expected {
+                let (n, meta) = reader.scan_record().unwrap();
+                assert_eq!(meta.unwrap(), RecordMeta::from(record));
+                n_bytes += n;
+            }
+            assert_eq!(n_bytes, input.len());
+            assert_eq!(reader.scan_record().unwrap(), (0, None));
+        }
+
+        // A truncated record body is reported instead of being silently dropped.
+        let input = std::fs::read("../assets/SPE10.SMSPEC").unwrap();
+        let mut reader = SliceReader::new(&input[..input.len() - 1]);
+        assert!(iter::from_fn(|| Some(reader.read_record()))
+            .find(|r| !matches!(r, Ok((_, Some(_)))))
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_utf8_string() {
+        let mut header = Vec::new();
+        bp::write_str("WGNAMES", 8, &mut header);
+        bp::write_i32(2, &mut header);
+        bp::write_str("CHAR", 4, &mut header);
+
+        let mut input = Vec::new();
+        bp::write_block(&header, &mut input);
+        bp::write_block(b"P1      \xff\xfe      ", &mut input);
+
+        assert!(matches!(
+            Cursor::new(input).read_record(),
+            Err(EclairError::InvalidStringBytes(_))
+        ));
+    }
+
+    #[test]
+    fn scan_spe_10() {
+        for path in &["../assets/SPE10.SMSPEC", "../assets/SPE10.UNSMRY"] {
+            let input = std::fs::read(path).unwrap();
+
+            let mut reader = Cursor::new(&input);
+            let mut scanner = BufReader::new(File::open(path).unwrap());
+            let mut skipper = RecordReader::new(BufReader::new(File::open(path).unwrap()));
+            let mut total_bytes = 0;
+            loop {
+                let (n_bytes, record) = reader.read_record().unwrap();
+                let (n_scanned, meta) = scanner.scan_record().unwrap();
+                assert_eq!(n_scanned, n_bytes);
+                assert_eq!(meta, record.as_ref().map(RecordMeta::from));
+                assert_eq!(skipper.skip_record().unwrap(), (n_scanned, meta));
+                if record.is_none() {
+                    break;
+                }
+                total_bytes += n_bytes;
+            }
+            assert_eq!(total_bytes, input.len());
+        }
+
+        // A truncated body is an error.
+        let input = std::fs::read("../assets/SPE10.SMSPEC").unwrap();
+        let mut scanner = Cursor::new(&input[..30]);
+        assert!(matches!(
+            scanner.scan_record(),
+            Err(EclairError::TruncatedRecord { .. })
+        ));
+        assert!(matches!(
+            RecordReader::new(Cursor::new(&input[..30])).skip_record(),
+            Err(EclairError::TruncatedRecord { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_trailing_record() {
+        let input = std::fs::read("../assets/SPE10.SMSPEC").unwrap();
+        let n_records = Cursor::new(&input).records().count();
+
+        // Cut the last record (72 bytes long) short, both in its header and in its body.
+        for &len in &[input.len() - 60, input.len() - 10] {
+            for records in [
+                Cursor::new(&input[..len]).records().collect::<Vec<_>>(),
+                SliceReader::new(&input[..len]).records().collect(),
+            ] {
+                assert_eq!(records.len(), n_records);
+                assert!(records[..n_records - 1].iter().all(|r| r.is_ok()));
+                assert!(matches!(
+                    records.last(),
+                    Some(Err(EclairError::TruncatedRecord { .. }))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn max_elements() {
+        let input = std::fs::read("../assets/SPE10.SMSPEC").unwrap();
+        let mut reader = RecordReader::new(Cursor::new(&input));
+        reader.set_max_elements(Some(10));
+
+        let error = reader.records().find_map(|record| record.err()).unwrap();
+        assert!(matches!(
+            error,
+            EclairError::TooManyElements {
+                n_elements: 34,
+                max_elements: 10,
+                ..
+            }
+        ));
+    }
+
+    /// A binary record header with the given element count and data type.
+    fn raw_header(name: &str, n_elements: i32, type_id: &str) -> Vec<u8> {
+        let mut header = 16i32.to_be_bytes().to_vec();
+        header.extend(format!("{:<8}", name).bytes());
+        header.extend(n_elements.to_be_bytes());
+        header.extend(format!("{:<4}", type_id).bytes());
+        header.extend(16i32.to_be_bytes());
+        header
+    }
+
+    #[test]
+    fn malformed_headers() {
+        let mut reader = RecordReader::new(Cursor::new(raw_header("HUGE", 2_000_000_000, "INTE")));
+        assert!(matches!(
+            reader.read_record(),
+            Err(EclairError::RecordTooLarge { .. })
+        ));
+
+        let options = RecordReaderOptions {
+            max_record_bytes: 100,
+            ..RecordReaderOptions::default()
+        };
+        let mut reader =
+            RecordReader::new(Cursor::new(raw_header("BIG", 30, "DOUB"))).with_options(options);
+        assert!(matches!(
+            reader.read_record(),
+            Err(EclairError::RecordTooLarge { bytes: 248, .. })
+        ));
+
+        let mut reader = RecordReader::new(Cursor::new(raw_header("NEG", -1, "INTE")));
+        assert!(matches!(
+            reader.read_record(),
+            Err(EclairError::NegativeElementCount { n_elements: -1, .. })
+        ));
+
+        // The elements of a message are ignored.
+        let mut input = raw_header("MESSAGE", 1, "MESS");
+        input.extend([0; 8]);
+        let (n_bytes, record) = Cursor::new(&input).read_record().unwrap();
+        assert_eq!(n_bytes, 32);
+        assert!(record.unwrap().data.is_message());
+
+        // Random headers and bodies must produce errors rather than panics.
+        let types = [
+            "INTE", "REAL", "DOUB", "LOGI", "CHAR", "MESS", "C000", "C0", "C099", "C0AB", "XYZ",
+        ];
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+        for _ in 0..10_000 {
+            let n_elements = next(3000) as i32 - 10;
+            let type_id = types[next(types.len() as u64) as usize];
+            let mut input = raw_header("FUZZ", n_elements, type_id);
+            let body_len = next(300);
+            input.extend((0..body_len).map(|_| next(256) as u8));
+
+            let _ = Cursor::new(&input).records().count();
+            let _ = Cursor::new(&input).scan_record();
+            let _ = SliceReader::new(&input).records().count();
+        }
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let int = RecordData::Int(vec![1, 2]);
+        assert_eq!(int.as_i32(), Some(&[1, 2][..]));
+        assert_eq!(int.as_f32(), None);
+        assert!(!int.is_message());
+
+        let real = RecordData::F32(vec![1.5]);
+        assert_eq!(real.as_f32(), Some(&[1.5][..]));
+        assert_eq!(real.as_f64(), None);
+
+        let double = RecordData::F64(vec![2.5]);
+        assert_eq!(double.as_f64(), Some(&[2.5][..]));
+        assert_eq!(double.as_i32(), None);
+
+        let chars = RecordData::Chars(vec![FlexString::from("WOPR")]);
+        assert_eq!(chars.as_chars(), Some(&[FlexString::from("WOPR")][..]));
+        assert_eq!(chars.as_i32(), None);
+
+        // Logicals are not integers, even though they are stored as such.
+        assert_eq!(RecordData::Bool(vec![-1]).as_i32(), None);
+
+        assert!(RecordData::Message.is_message());
+        assert_eq!(RecordData::Message.as_chars(), None);
+    }
+
+    #[test]
+    fn write_spe_10() {
+        for path in &["../assets/SPE10.SMSPEC", "../assets/SPE10.UNSMRY"] {
+            let input = std::fs::read(path).unwrap();
+            let records: Vec<Record> = Cursor::new(&input).records().map(|r| r.unwrap()).collect();
+
+            let mut output = Vec::new();
+            for record in &records {
+                output.write_record(record).unwrap();
+            }
+            assert_eq!(output, input);
+
+            let read_back: Vec<Record> =
+                Cursor::new(&output).records().map(|r| r.unwrap()).collect();
+            assert_eq!(read_back, records);
+        }
+    }
+
+    #[test]
+    fn write_long_strings() {
+        let record = Record::new(
+            "NAMES",
+            RecordData::Chars(
+                vec!["PRODUCER_NORTH", "P2"]
+                    .into_iter()
+                    .map(FlexString::from)
+                    .collect(),
+            ),
+        );
+
+        let mut output = Vec::new();
+        let n_bytes = output.write_record(&record).unwrap();
+        assert_eq!(n_bytes, 24 + 2 * 14 + 8);
+        assert_eq!(&output[16..20], b"C014");
+
+        // The strings are read back with the width they were written with.
+        let (_, read_back) = Cursor::new(&output).read_record().unwrap();
+        let read_back = read_back.unwrap();
+        assert_eq!(read_back.data.kind_string(), "C014");
+        assert_eq!(read_back.data.as_chars(), record.data.as_chars());
+
+        // The declared width survives writing the strings out, even if they are all short.
+        let record = Record::new(
+            "NAMES",
+            RecordData::LongChars {
+                width: 20,
+                values: vec![FlexString::from("P1")],
+            },
+        );
+        let mut output = Vec::new();
+        output.write_record(&record).unwrap();
+        assert_eq!(&output[16..20], b"C020");
+        let (_, read_back) = Cursor::new(&output).read_record().unwrap();
+        assert_eq!(read_back.unwrap(), record);
+    }
+
+    #[test]
+    fn formatted_records() {
+        let input = concat!(
+            " 'DIMENS  '           6 'INTE'\n",
+            "           34         100         100          30           0          -1\n",
+            " 'WGNAMES '           3 'CHAR'\n",
+            " 'P1      ' 'WELL 2  ' ':+:+:+:+'\n",
+            " 'PARAMS  '           5 'REAL'\n",
+            "   0.00000000E+00   0.12345678E+01  -0.12500000E-02   0.10000000-100\n",
+            "   0.20000000E+01\n",
+            " 'RUNTIMED'           2 'DOUB'\n",
+            "   0.12345678901234D+01  -0.25000000000000D+00\n",
+            " 'FLAGS   '           2 'LOGI'\n",
+            "  T  F\n",
+            " 'SEQHDR  '           0 'MESS'\n",
+        );
+        let reader = FormattedReader::new(Cursor::new(input));
+        let records: Vec<Record> = reader.records().map(|r| r.unwrap()).collect();
+
+        assert_eq!(records.len(), 6);
+        assert_eq!(
+            records[0].data,
+            RecordData::Int(vec![34, 100, 100, 30, 0, -1])
+        );
+        assert_eq!(
+            records[1].data,
+            RecordData::Chars(
+                vec!["P1", "WELL 2", ":+:+:+:+"]
+                    .into_iter()
+                    .map(FlexString::from)
+                    .collect()
+            )
+        );
+        assert_eq!(
+            records[2].data,
+            RecordData::F32(vec![0.0, 1.2345678, -0.00125, 0.0, 2.0])
+        );
+        assert_eq!(
+            records[3].data,
+            RecordData::F64(vec![1.2345678901234, -0.25])
+        );
+        assert_eq!(records[4].data, RecordData::Bool(vec![-1, 0]));
+        assert_eq!(&records[5].name, "SEQHDR");
+        assert_eq!(records[5].data, RecordData::Message);
+    }
+
+    #[test]
+    fn formatted_record_truncated() {
+        let input = " 'DIMENS  '           6 'INTE'\n          34         100\n";
+        let mut reader = FormattedReader::new(Cursor::new(input));
+        assert!(reader.read_record().unwrap_err().is_truncation());
+
+        // A last line cut in the middle of a value is incomplete, unlike a valid one.
+        let input = " 'DIMENS  '           6 'INTE'\n          34         100         100          30           0          -";
+        let mut reader = FormattedReader::new(Cursor::new(input));
+        assert!(reader.read_record().unwrap_err().is_truncation());
+
+        let mut reader = FormattedReader::new(Cursor::new(&input[..input.len() - 1]));
+        assert!(reader.read_record().unwrap_err().is_truncation());
+        let input = format!("{}1", input);
+        let mut reader = FormattedReader::new(Cursor::new(input));
+        assert!(reader.read_record().unwrap().1.is_some());
+    }
 }
@@ -7,13 +7,20 @@
 //!
 //! - A "unified" summary file (`.UNSMRY`) which holds the time series.
 //!
+//! Instead of the unified summary file, a run may write multiple non-unified summary files
+//! (`.S0001`, `.S0002`, ...), one per report step. These are read in order as a single stream.
+//!
 //! Both are standard Eclipse binary files, i.e. the consist of a series of Eclipse binary records.
+//! Their formatted counterparts (`.FSMSPEC` and `.FUNSMRY`) are supported as well.
 //!
 //! ### Specification file layout
 //!
 //! A full list of records present in the `.SMSPEC` files can be found it the Eclipse manual. Here
 //! we list only those read by `eclair-io`:
 //!
+//! - `INTEHEAD`: 2 INTE items - the unit system (1 - Metric, 2 - Field, 3 - Lab, 4 - PVT-M) and the
+//!   simulator identifier. This record is optional;
+//! - `RESTART`: 9 CHAR items - the name of the case this run was restarted from, if any;
 //! - `DIMENS`: 6 INTE items. The first one (NLIST) in the most important - it indicates the total
 //!   number of time series in the summary. The next three items correspond to the nubmer of cells
 //!   in X, Y and Z directions;
@@ -22,6 +29,12 @@
 //! - `NAMES`: NLIST C0nn items - alternative to `WGNAMES` when long (>8 chars) names are used;
 //! - `NUMS`: NLIST INTE items - integer cell or region numbers associated with time series;
 //! - `UNITS`: NLIST CHAR items - physical units for time series;
+//! - `LGRS`: NLIST CHAR items - names of the local grids of the local items, blank for others.
+//!   This record is optional;
+//! - `NUMLX`, `NUMLY`, `NUMLZ`: NLIST INTE items - the local grid cell coordinates of the local
+//!   items. These records are optional and only used together with `LGRS`;
+//! - `MEASRMNT`: a multiple of NLIST CHAR items - descriptions of the measured quantities, split
+//!   into the same number of strings for each time series. This record is optional;
 //! - `STARTDAT`: 6 INTE items - day (1-31), month (1-12), year (YYYY), hour (0-23), minute (0-59),
 //!   microsecond (0 - 59,999,999) for the datetime of the simulation start.
 //!
@@ -31,31 +44,57 @@
 //! relevant:
 //! - `SEQHDR`: 1 INTE item - ignored;
 //! - `MINISTEP`: 1 INTE item - the running timestep counter;
-//! - `PARAMS`: NLIST REAL items - time series data for the current timestep.
+//! - `PARAMS`: NLIST REAL items - time series data for the current timestep. Some simulators write
+//!   DOUB items instead, these are kept in double precision.
 //!
 //! In the code and comments below, time series are referred to as summary items.
+//!
+//! ### Storage layout
+//!
+//! By default the values of every summary item are stored in a vector of their own, so that the
+//! getters can hand out plain slices. With the `row_major` feature the values of all items are
+//! stored in a single vector instead, one time iteration after another, the way PARAMS records
+//! arrive. Appending a time iteration to a summary with many items is then much cheaper, but the
+//! values of an item have to be gathered from every row: `ValuesRef` becomes a `Cow` and
+//! `SummaryItem` keeps only the unit.
 
 use std::{
-    collections::{HashMap, HashSet},
+    borrow::Cow,
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     convert::{TryFrom, TryInto},
     fmt::{Display, Formatter},
     fs::File,
-    io::{BufReader, Seek, SeekFrom},
+    io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write},
+    iter,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc},
     thread::sleep,
-    time,
+    time::{self, SystemTime},
 };
 
-use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
-use crossbeam_channel::{Receiver, Sender};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use itertools::multizip;
 use once_cell::sync::Lazy;
+use serde::{
+    de::{self, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 use crate::{
     error::EclairError,
-    records::{ReadRecord, Record, RecordData, RecordDataKind},
-    FlexString, Result,
+    records::{
+        FormattedReader, ReadRecord, Record, RecordData, RecordDataKind, RecordReader, WriteRecord,
+    },
+    to_csv::{to_csv_to_writer, CsvHeader, CsvOptions},
+    FlexString, Result, FIXED_STRING_LENGTH,
 };
 
+#[cfg(feature = "mmap")]
+use crate::records::MmapReader;
+
 static SMSPEC_RECORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     let mut s = HashSet::new();
     s.insert("INTEHEAD");
@@ -86,6 +125,11 @@ static SMSPEC_RECORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     s
 });
 
+/// SMSPEC records with a single value per summary item.
+const NLIST_RECORDS: [&str; 10] = [
+    "KEYWORDS", "WGNAMES", "NUMS", "UNITS", "XCOORD", "YCOORD", "LGRS", "NUMLX", "NUMLY", "NUMLZ",
+];
+
 static TIMING_KEYWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     let mut s = HashSet::new();
     s.insert("TIME");
@@ -114,12 +158,18 @@ static PERFORMANCE_KEYWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     s
 });
 
-const UNKNOWN_WG_NAME: &str = ":+:+:+:+";
+pub(crate) const UNKNOWN_WG_NAME: &str = ":+:+:+:+";
+
+/// How many levels of restarts `SummaryFileReader::from_path` follows.
+pub const DEFAULT_MAX_RESTART_DEPTH: usize = 16;
+
+/// How often updaters check for new summary data by default.
+pub const DEFAULT_POLL_INTERVAL: time::Duration = time::Duration::from_millis(100);
 
 /// ItemId is an item identifier derived from the SMSPEC metadata. It consists of a name, which
 /// corresponds to the physical quantity the item represents (e.g. WBHP for the well bottom hole
 /// pressure) and a qualifier, which roughly corresponds to the location (e.g. well named WELL_1).
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct ItemId {
     pub name: FlexString,
     pub qualifier: ItemQualifier,
@@ -157,10 +207,12 @@ impl ItemId {
                 },
                 [b'W', ..] if wg_valid => Well { wg_name },
                 [b'C', ..] if wg_valid && num_valid => Completion { wg_name, index },
+                [b'S', ..] if wg_valid && num_valid => Segment { wg_name, index },
                 [b'G', ..] if wg_valid => Group { wg_name },
+                [b'N', ..] if wg_valid => Node { wg_name },
                 [b'B', ..] if num_valid => Block { index },
                 _ => {
-                    log::info!(target: "Building SummaryItem",
+                    log::trace!(target: "Building SummaryItem",
                                "Unrecognized summary item. KEYWORD: {}, WGNAME: {}, NUM: {}",
                                name, wg_name, index
                     );
@@ -170,10 +222,107 @@ impl ItemId {
         };
         ItemId { name, qualifier }
     }
+
+    /// Interpret an item of a local grid refinement, i.e. one with a non-blank LGRS name. Local
+    /// well, completion and block keywords are prefixed with `L`, e.g. LWBHP or LBPR. Anything else
+    /// is interpreted as a global item.
+    fn new_local(
+        name: FlexString,
+        wg_name: FlexString,
+        index: i32,
+        lgr: FlexString,
+        [i, j, k]: [i32; 3],
+    ) -> Self {
+        use ItemQualifier::*;
+
+        let wg_valid = !wg_name.is_empty() && wg_name != UNKNOWN_WG_NAME;
+        let ijk_valid = i > 0 && j > 0 && k > 0;
+
+        let qualifier = match name.as_bytes() {
+            [b'L', b'W', ..] if wg_valid => LocalWell { lgr, wg_name },
+            [b'L', b'C', ..] if wg_valid && ijk_valid => LocalCompletion {
+                lgr,
+                wg_name,
+                i,
+                j,
+                k,
+            },
+            [b'L', b'B', ..] if ijk_valid => LocalBlock { lgr, i, j, k },
+            _ => return ItemId::new(name, wg_name, index),
+        };
+        ItemId { name, qualifier }
+    }
+
+    /// The inverse of `new`: the well or group name and the integer index to be stored in SMSPEC.
+    pub(crate) fn smspec_fields(&self) -> (FlexString, i32) {
+        use ItemQualifier::*;
+
+        let unknown = || FlexString::from(UNKNOWN_WG_NAME);
+        match &self.qualifier {
+            Time | Performance | Field => (unknown(), 0),
+            Aquifer { index } | Block { index } => (unknown(), *index),
+            Region { wg_name, index } => (wg_name.clone().unwrap_or_else(unknown), *index),
+            CrossRegionFlow { from, to } => (unknown(), from + 32768 * (to + 10)),
+            Well { wg_name } | Group { wg_name } | Node { wg_name } => (wg_name.clone(), 0),
+            Completion { wg_name, index }
+            | Segment { wg_name, index }
+            | Unrecognized { wg_name, index } => (wg_name.clone(), *index),
+            LocalWell { wg_name, .. } | LocalCompletion { wg_name, .. } => (wg_name.clone(), 0),
+            LocalBlock { .. } => (unknown(), 0),
+        }
+    }
+
+    /// The inverse of `new_local`: the local grid name and the local cell coordinates to be stored
+    /// in SMSPEC. Global items have a blank name and zero coordinates.
+    pub(crate) fn smspec_lgr_fields(&self) -> (FlexString, [i32; 3]) {
+        use ItemQualifier::*;
+
+        match &self.qualifier {
+            LocalWell { lgr, .. } => (lgr.clone(), [0, 0, 0]),
+            LocalCompletion { lgr, i, j, k, .. } | LocalBlock { lgr, i, j, k } => {
+                (lgr.clone(), [*i, *j, *k])
+            }
+            _ => (FlexString::new(), [0, 0, 0]),
+        }
+    }
+}
+
+/// The full item name in the usual `KEYWORD:QUALIFIER` notation, e.g. `WBHP:PROD1`, `COPR:PROD1:4`
+/// or `RGFT:1-2`. Items without a location are named by their keyword alone. Local items are
+/// qualified by their local grid first, e.g. `LWBHP:LGR1:PROD1` or `LBPR:LGR1:1,2,3`.
+impl Display for ItemId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ItemQualifier::*;
+        match &self.qualifier {
+            Time | Performance | Field => write!(f, "{}", self.name),
+            Aquifer { index } | Block { index } | Region { index, .. } => {
+                write!(f, "{}:{}", self.name, index)
+            }
+            CrossRegionFlow { from, to } => write!(f, "{}:{}-{}", self.name, from, to),
+            Well { wg_name } | Group { wg_name } | Node { wg_name } => {
+                write!(f, "{}:{}", self.name, wg_name)
+            }
+            Completion { wg_name, index }
+            | Segment { wg_name, index }
+            | Unrecognized { wg_name, index } => write!(f, "{}:{}:{}", self.name, wg_name, index),
+            LocalWell { lgr, wg_name } => write!(f, "{}:{}:{}", self.name, lgr, wg_name),
+            LocalCompletion {
+                lgr,
+                wg_name,
+                i,
+                j,
+                k,
+            } => write!(f, "{}:{}:{}:{},{},{}", self.name, lgr, wg_name, i, j, k),
+            LocalBlock { lgr, i, j, k } => write!(f, "{}:{}:{},{},{}", self.name, lgr, i, j, k),
+        }
+    }
 }
 
-/// ItemQualifier is used to associate a location or a category with a summary item.
-#[derive(Debug, Eq, Hash, PartialEq)]
+/// ItemQualifier is used to associate a location or a category with a summary item. It is
+/// serialized as a map with the variant name under the `kind` key, e.g.
+/// `{"kind": "Well", "wg_name": "PROD1"}`.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(tag = "kind")]
 pub enum ItemQualifier {
     Time,
     Performance,
@@ -196,12 +345,36 @@ pub enum ItemQualifier {
         wg_name: FlexString,
         index: i32,
     },
+    Segment {
+        wg_name: FlexString,
+        index: i32,
+    },
     Group {
         wg_name: FlexString,
     },
+    Node {
+        wg_name: FlexString,
+    },
     Block {
         index: i32,
     },
+    LocalWell {
+        lgr: FlexString,
+        wg_name: FlexString,
+    },
+    LocalCompletion {
+        lgr: FlexString,
+        wg_name: FlexString,
+        i: i32,
+        j: i32,
+        k: i32,
+    },
+    LocalBlock {
+        lgr: FlexString,
+        i: i32,
+        j: i32,
+        k: i32,
+    },
     Unrecognized {
         wg_name: FlexString,
         index: i32,
@@ -212,6 +385,51 @@ impl ItemQualifier {
     pub fn is_recognized(&self) -> bool {
         !matches!(self, ItemQualifier::Unrecognized { .. })
     }
+
+    /// The 1-based (i, j, k) coordinates of the grid cell of a block or a completion item in a grid
+    /// of the given dimensions. None for other items and for cell numbers outside the grid.
+    pub fn block_ijk(&self, dims: [i32; 3]) -> Option<(i32, i32, i32)> {
+        let index = match self {
+            ItemQualifier::Block { index } | ItemQualifier::Completion { index, .. } => *index,
+            _ => return None,
+        };
+
+        let [nx, ny, nz] = [dims[0] as i64, dims[1] as i64, dims[2] as i64];
+        if nx <= 0 || ny <= 0 || nz <= 0 || index < 1 || index as i64 > nx * ny * nz {
+            return None;
+        }
+
+        // The cell number is i + (j - 1) * nx + (k - 1) * nx * ny.
+        let n = index as i64 - 1;
+        Some((
+            (n % nx + 1) as i32,
+            (n / nx % ny + 1) as i32,
+            (n / (nx * ny) + 1) as i32,
+        ))
+    }
+
+    /// Name of the qualifier variant, the same as its serialized `kind`.
+    fn kind(&self) -> &'static str {
+        use ItemQualifier::*;
+        match self {
+            Time => "Time",
+            Performance => "Performance",
+            Field => "Field",
+            Aquifer { .. } => "Aquifer",
+            Region { .. } => "Region",
+            CrossRegionFlow { .. } => "CrossRegionFlow",
+            Well { .. } => "Well",
+            Completion { .. } => "Completion",
+            Segment { .. } => "Segment",
+            Group { .. } => "Group",
+            Node { .. } => "Node",
+            Block { .. } => "Block",
+            LocalWell { .. } => "LocalWell",
+            LocalCompletion { .. } => "LocalCompletion",
+            LocalBlock { .. } => "LocalBlock",
+            Unrecognized { .. } => "Unrecognized",
+        }
+    }
 }
 
 impl Display for ItemQualifier {
@@ -229,8 +447,23 @@ impl Display for ItemQualifier {
             CrossRegionFlow { from, to } => write!(f, "CrossRegionFlow {} => {}", from, to),
             Well { wg_name } => write!(f, "Well {}", wg_name),
             Completion { wg_name, index } => write!(f, "Completion #{} @ {}", index, wg_name),
+            Segment { wg_name, index } => write!(f, "Segment #{} @ {}", index, wg_name),
             Group { wg_name } => write!(f, "Group {}", wg_name),
+            Node { wg_name } => write!(f, "Node {}", wg_name),
             Block { index } => write!(f, "Block #{}", index),
+            LocalWell { lgr, wg_name } => write!(f, "Well {} in {}", wg_name, lgr),
+            LocalCompletion {
+                lgr,
+                wg_name,
+                i,
+                j,
+                k,
+            } => write!(
+                f,
+                "Completion ({}, {}, {}) @ {} in {}",
+                i, j, k, wg_name, lgr
+            ),
+            LocalBlock { lgr, i, j, k } => write!(f, "Block ({}, {}, {}) in {}", i, j, k, lgr),
             Unrecognized { wg_name, index } => write!(
                 f,
                 "Unrecognized qualifier. Name: {}, index: {}",
@@ -240,477 +473,4480 @@ impl Display for ItemQualifier {
     }
 }
 
-/// An individual summary item.
-#[derive(Debug)]
-pub struct SummaryItem {
-    /// Physical unit
-    pub unit: FlexString,
+/// A custom classification of summary items by their keyword, well or group name and NUMS value.
+/// See `SummaryFileReader::with_classifier`.
+pub type ItemClassifier = dyn Fn(&str, &str, i32) -> Option<ItemQualifier> + Send + Sync;
 
-    /// Time series values
-    pub values: Vec<f32>,
+/// The unit system of a simulation.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum UnitSystem {
+    Metric,
+    Field,
+    Lab,
+    PvtM,
 }
 
-/// A union of (a subset of) data from both `SMSPEC` and `UNSMRY` files. The subset may eventually
-/// expand to cover more of the summary data, but right now we ignore data related to LGRs,
-/// horizontal wells, measurement descriptions, completion coordinates, run-time monitoring.
-#[derive(Debug)]
-pub struct Summary {
-    /// Grid dimensions of a simulation
-    pub dims: [i32; 3],
-
-    /// Simulation unix timestamps
-    pub timestamps: Vec<i64>,
-
-    /// ItemId to its index in the items vector
-    pub item_ids: HashMap<ItemId, usize>,
-
-    /// Simulation data
-    pub items: Vec<SummaryItem>,
-
-    // Index of the time item.
-    time_index: usize,
-
-    start_timestamp: i64,
+impl From<UnitSystem> for i32 {
+    fn from(value: UnitSystem) -> Self {
+        use UnitSystem::*;
+        match value {
+            Metric => 1,
+            Field => 2,
+            Lab => 3,
+            PvtM => 4,
+        }
+    }
 }
 
-impl Summary {
-    /// Total number of summary items.
-    pub fn n_items(&self) -> usize {
-        self.items.len()
-    }
+impl TryFrom<i32> for UnitSystem {
+    type Error = EclairError;
 
-    /// Number of time iterations that this Summary stores data for.
-    pub fn n_steps(&self) -> usize {
-        match self.items.first() {
-            Some(items) => items.values.len(),
-            None => 0,
+    fn try_from(value: i32) -> Result<Self> {
+        use UnitSystem::*;
+        match value {
+            1 => Ok(Metric),
+            2 => Ok(Field),
+            3 => Ok(Lab),
+            4 => Ok(PvtM),
+            _ => Err(EclairError::InvalidUnitSystemId(value)),
         }
     }
+}
 
-    /// This function expects the size of params to equal the size of items.
-    pub fn append(&mut self, params: Vec<f32>) {
-        let new_time = params[self.time_index];
-        let new_ts =
-            self.start_timestamp + Duration::seconds((new_time * 86400.0) as i64).num_seconds();
-        self.timestamps.push(new_ts);
-
-        for (item, param) in self.items.iter_mut().zip(params) {
-            item.values.push(param);
+impl Display for UnitSystem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use UnitSystem::*;
+        match self {
+            Metric => write!(f, "Metric"),
+            Field => write!(f, "Field"),
+            Lab => write!(f, "Lab"),
+            PvtM => write!(f, "PVT-M"),
         }
     }
 }
 
-/// Intermediate type for Smspec data to facilitate input validation. It contains a subset of
-/// records from which a valid Summary COULD be constructed. At the point of its construction the
-/// only input error we check for is the presence of duplicate records.
-#[derive(Debug)]
-pub(crate) struct SmspecRecords {
-    records: HashMap<&'static str, Option<RecordData>>,
+/// Time series values of a summary item. Values read from double precision data are kept as such,
+/// along with their single precision copy for consumers working with `f32` values.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ItemValues {
+    single: Vec<f32>,
+    double: Option<Vec<f64>>,
 }
 
-impl Default for SmspecRecords {
-    fn default() -> Self {
-        let mut records = HashMap::new();
-        records.insert("DIMENS", None);
-        records.insert("STARTDAT", None);
-        records.insert("KEYWORDS", None);
-        records.insert("WGNAMES", None);
-        records.insert("NUMS", None);
-        records.insert("UNITS", None);
-        SmspecRecords { records }
+impl ItemValues {
+    /// Number of values.
+    pub fn len(&self) -> usize {
+        self.single.len()
     }
-}
 
-impl SmspecRecords {
-    pub(crate) fn new(records: HashMap<&'static str, Option<RecordData>>) -> Self {
-        SmspecRecords { records }
+    pub fn is_empty(&self) -> bool {
+        self.single.is_empty()
     }
 
-    fn is_full(&self) -> bool {
-        self.records.values().all(|val| val.is_some())
+    /// Whether the values are stored in double precision.
+    pub fn is_double_precision(&self) -> bool {
+        self.double.is_some()
     }
-}
 
-macro_rules! validate {
-            ($field_data: ident, $field_name: literal, $kind: ident, $($valid_len: expr),+ $(,)?) => {
-                loop {
-                    let values = if let RecordData::$kind(values) = $field_data {
-                        values
-                    } else {
-                        return Err(EclairError::InvalidRecordDataType {
-                            name: $field_name.to_string(),
-                            expected: RecordDataKind::$kind.to_string(),
-                            found: $field_data.kind_string(),
-                        });
-                    };
+    /// Values in single precision.
+    pub fn as_f32_slice(&self) -> &[f32] {
+        &self.single
+    }
 
-                    let len_err = |expected| Err(EclairError::UnexpectedRecordDataLength {
-                        name: $field_name.to_string(),
-                        expected,
-                        found: values.len(),
-                    });
+    /// Values in double precision. Single precision values are converted.
+    pub fn as_f64_vec(&self) -> Vec<f64> {
+        match &self.double {
+            Some(double) => double.clone(),
+            None => self.single.iter().map(|&v| v as f64).collect(),
+        }
+    }
 
-                    let mut expected_len;
-                    $(
-                        expected_len = $valid_len;
-                        match values.len().cmp(&$valid_len) {
-                            std::cmp::Ordering::Less => return len_err($valid_len),
-                            std::cmp::Ordering::Equal => break values,
-                            std::cmp::Ordering::Greater => (),
-                        };
-                    )+
+    fn get_f64(&self, index: usize) -> f64 {
+        match &self.double {
+            Some(double) => double[index],
+            None => self.single[index] as f64,
+        }
+    }
 
-                    return len_err(expected_len);
-                }
-            };
+    fn reserve(&mut self, additional: usize) {
+        self.single.reserve(additional);
+        if let Some(double) = &mut self.double {
+            double.reserve(additional);
         }
+    }
 
-impl TryFrom<SmspecRecords> for Summary {
-    type Error = EclairError;
+    fn push_f32(&mut self, value: f32) {
+        self.single.push(value);
+        if let Some(double) = &mut self.double {
+            double.push(value as f64);
+        }
+    }
 
-    fn try_from(mut value: SmspecRecords) -> Result<Self> {
-        use EclairError::*;
+    /// Push single precision values, keeping the precision of the storage.
+    #[cfg(feature = "row_major")]
+    fn extend_f32<I: Iterator<Item = f32> + Clone>(&mut self, values: I) {
+        if let Some(double) = &mut self.double {
+            double.extend(values.clone().map(f64::from));
+        }
+        self.single.extend(values);
+    }
 
-        macro_rules! extract_and_validate {
-            ($field_name: literal, $kind: ident, $($valid_len: expr),+ $(,)?) => {
-                {
-                   let field_data = value
-                    .records
-                    .remove($field_name)
-                    .unwrap()
-                    .ok_or_else(|| MissingRecord($field_name.to_string()))?;
+    /// Push a double precision value, switching the storage to double precision if necessary.
+    fn push_f64(&mut self, value: f64) {
+        if self.double.is_none() {
+            let mut double = Vec::with_capacity(self.single.capacity());
+            double.extend(self.single.iter().map(|&v| v as f64));
+            self.double = Some(double);
+        }
+        if let Some(double) = &mut self.double {
+            double.push(value);
+        }
+        self.single.push(value as f32);
+    }
 
-                   validate!(field_data, $field_name, $kind, $($valid_len),+)
+    /// Replace every `step`-th value starting at `start` by the result of the function, keeping
+    /// the precision.
+    fn map_values<F: Fn(f64) -> f64>(&mut self, start: usize, step: usize, f: F) {
+        match &mut self.double {
+            Some(double) => {
+                let values = double.iter_mut().zip(&mut self.single);
+                for (value, single) in values.skip(start).step_by(step) {
+                    *value = f(*value);
+                    *single = *value as f32;
                 }
-            };
+            }
+            None => {
+                for value in self.single.iter_mut().skip(start).step_by(step) {
+                    *value = f(*value as f64) as f32;
+                }
+            }
         }
+    }
 
-        let dimens = extract_and_validate!("DIMENS", Int, 6);
-        let nlist = dimens[0] as usize;
+    /// Every `step`-th value starting at `start`, e.g. the values of a single item in row-major
+    /// storage.
+    #[cfg(feature = "row_major")]
+    fn strided(&self, start: usize, step: usize) -> ItemValues {
+        let strided = |values: &[f32]| values.iter().skip(start).step_by(step).copied().collect();
+        ItemValues {
+            single: strided(&self.single),
+            double: self
+                .double
+                .as_ref()
+                .map(|double| double.iter().skip(start).step_by(step).copied().collect()),
+        }
+    }
 
-        let start_dat = extract_and_validate!("STARTDAT", Int, 3, 6);
-        let keywords = extract_and_validate!("KEYWORDS", Chars, nlist);
-        let wg_names = extract_and_validate!("WGNAMES", Chars, nlist);
-        let nums = extract_and_validate!("NUMS", Int, nlist);
-        let units = extract_and_validate!("UNITS", Chars, nlist);
+    /// Truncate the values or pad them with NaNs to the given length.
+    fn resize(&mut self, len: usize) {
+        self.single.resize(len, f32::NAN);
+        if let Some(double) = &mut self.double {
+            double.resize(len, f64::NAN);
+        }
+    }
 
-        // Now we prepare to construct the Summary object.
-        let dims = dimens[1..4].try_into().unwrap();
+    /// Move all values of `other` to the end. The result is in double precision if either of the
+    /// two is.
+    fn append(&mut self, mut other: ItemValues) {
+        if self.double.is_some() || other.double.is_some() {
+            let mut double = self.as_f64_vec();
+            double.append(&mut other.as_f64_vec());
+            self.double = Some(double);
+        }
+        self.single.append(&mut other.single);
+    }
+}
 
-        let d = NaiveDate::from_ymd(start_dat[2], start_dat[1] as u32, start_dat[0] as u32);
+/// Values of a summary item as handed out by the getters. They are borrowed from the item, unless
+/// the `row_major` feature is on, in which case they are gathered from the rows of all time
+/// iterations.
+#[cfg(not(feature = "row_major"))]
+pub type ValuesRef<'a> = &'a [f32];
 
-        let ts = if start_dat.len() == 3 {
-            d.and_hms(0, 0, 0)
-        } else {
-            d.and_hms_milli(
-                start_dat[3] as u32,
-                start_dat[4] as u32,
-                (start_dat[5] / 1_000_000) as u32,
-                (start_dat[5] % 1_000_000) as u32,
-            )
-        };
+/// Values of a summary item as handed out by the getters. They are borrowed from the item, unless
+/// the `row_major` feature is on, in which case they are gathered from the rows of all time
+/// iterations.
+#[cfg(feature = "row_major")]
+pub type ValuesRef<'a> = Cow<'a, [f32]>;
 
-        let mut item_ids = HashMap::new();
-        let mut items = Vec::with_capacity(nlist);
+/// How to compute item values between the stored time iterations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ResampleMethod {
+    /// Linear interpolation between the neighbouring values.
+    Linear,
+    /// The value at the preceding time iteration.
+    Previous,
+    /// Linear interpolation for rates and time items and the previous value for everything else,
+    /// e.g. cumulative totals or pressures. Rates are recognized by their keyword, which has `R`
+    /// as the fourth character (e.g. FOPR or WWIR).
+    Auto,
+}
 
-        for vals in multizip((keywords, wg_names, nums, units)) {
-            let (name, wg_name, index, unit) = vals;
-            let item_id = ItemId::new(name, wg_name, index);
-            item_ids.insert(item_id, items.len());
-            items.push(SummaryItem {
-                unit,
-                values: Vec::new(),
-            });
-        }
+/// Resample values given at the ascending times onto the target times. Values outside of the time
+/// range are NaNs.
+fn resample_values(times: &[i64], values: &[f32], targets: &[i64], linear: bool) -> Vec<f32> {
+    let (first, last) = match (times.first(), times.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return vec![f32::NAN; targets.len()],
+    };
 
-        // We will panic if there is no "TIME" in the data. Make this an error instead.
-        let time_index = *item_ids
-            .get(&ItemId {
-                name: FlexString::from_str("TIME"),
-                qualifier: ItemQualifier::Time,
-            })
-            .unwrap();
+    targets
+        .iter()
+        .map(|&t| {
+            if t < first || t > last {
+                return f32::NAN;
+            }
 
-        Ok(Summary {
-            dims,
-            timestamps: vec![],
-            item_ids,
-            items,
-            time_index,
-            start_timestamp: ts.timestamp(),
+            // The number of times not later than the target one, which is at least one.
+            let i = times.partition_point(|&time| time <= t);
+            let (t0, v0) = (times[i - 1], values[i - 1]);
+            if !linear || t0 == t || i == times.len() {
+                return v0;
+            }
+
+            let (t1, v1) = (times[i], values[i]);
+            let weight = (t - t0) as f64 / (t1 - t0) as f64;
+            (v0 as f64 + (v1 - v0) as f64 * weight) as f32
         })
-    }
+        .collect()
 }
 
-/// Implementations of InitializeSummary can build a Summary instance and an object that can be
-/// subsequently used to append more data to it.
-pub trait InitializeSummary {
-    // Updater is meant to be moved to a separate thread.
-    type Updater: UpdateSummary + Send + 'static;
-
-    /// Read enough data to build a valid Summary instance and convert yourself to the Updater
-    /// object.
-    fn init(self) -> Result<(Summary, Self::Updater)>;
+/// A view of a summary over a contiguous range of time iterations.
+#[derive(Debug, Clone)]
+pub struct SummaryView<'a> {
+    summary: &'a Summary,
+    range: Range<usize>,
 }
 
-/// UpdateSummary implementations provide new summary data using the supplied channel.
-pub trait UpdateSummary {
-    fn update(&mut self, data_snd: Sender<Vec<f32>>, term_rcv: Receiver<bool>) -> Result<()>;
+impl<'a> SummaryView<'a> {
+    /// Indices of the time iterations in the view.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    pub fn timestamps(&self) -> &'a [i64] {
+        &self.summary.timestamps[self.range.clone()]
+    }
+
+    /// Values of an item within the view.
+    pub fn values(&self, id: &ItemId) -> Option<ValuesRef<'a>> {
+        self.summary
+            .item_ids
+            .get(id)
+            .map(|&index| self.item_values(index))
+    }
+
+    /// Values of the item at the given index of `items` within the view.
+    pub fn item_values(&self, index: usize) -> ValuesRef<'a> {
+        self.summary.item_values_in(index, self.range.clone())
+    }
 }
 
-/// SummaryFileReader builds Summary data from file-like sources.
-pub struct SummaryFileReader {
-    smspec_file: BufReader<File>,
-    unsmry_file: BufReader<File>,
+/// Values are serialized as a sequence in their stored precision.
+impl Serialize for ItemValues {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match &self.double {
+            Some(double) => double.serialize(serializer),
+            None => self.single.serialize(serializer),
+        }
+    }
 }
 
-/// FileUpdater updates Summary data from a file-like source.
-pub struct SummaryFileUpdater {
-    unsmry_file: BufReader<File>,
+/// Values are deserialized in the precision they have been serialized with, if the format keeps
+/// track of it. Otherwise, e.g. for JSON, they are in double precision. Nulls become NaNs.
+impl<'de> Deserialize<'de> for ItemValues {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        enum Value {
+            Single(f32),
+            Double(f64),
+        }
 
-    n_items: usize,
-    n_steps: usize,
-}
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "a number or null")
+            }
+
+            fn visit_f32<E: de::Error>(self, v: f32) -> std::result::Result<Value, E> {
+                Ok(Value::Single(v))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Value, E> {
+                Ok(Value::Double(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Value, E> {
+                Ok(Value::Double(v as f64))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Value, E> {
+                Ok(Value::Double(v as f64))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> std::result::Result<Value, E> {
+                Ok(Value::Single(f32::NAN))
+            }
+
+            fn visit_none<E: de::Error>(self) -> std::result::Result<Value, E> {
+                Ok(Value::Single(f32::NAN))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Value {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                deserializer.deserialize_any(ValueVisitor)
+            }
+        }
+
+        let mut values = ItemValues::default();
+        for value in Vec::<Value>::deserialize(deserializer)? {
+            match value {
+                Value::Single(v) => values.push_f32(v),
+                Value::Double(v) => values.push_f64(v),
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// An individual summary item. With the `row_major` feature its values are stored by the summary,
+/// see `Summary::item_values`.
+#[derive(Debug, Clone)]
+pub struct SummaryItem {
+    /// Physical unit
+    pub unit: FlexString,
+
+    /// Time series values
+    #[cfg(not(feature = "row_major"))]
+    pub values: ItemValues,
+}
+
+/// Aggregates over the values of a summary item, ignoring NaNs. All of them are None if the item
+/// has no values other than NaNs.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ItemStats {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub mean: Option<f32>,
+    pub last: Option<f32>,
+    /// Index of the first occurrence of the maximum value
+    pub argmax_index: Option<usize>,
+}
+
+impl SummaryItem {
+    /// An item with no values yet.
+    fn new(unit: FlexString) -> Self {
+        SummaryItem {
+            unit,
+            #[cfg(not(feature = "row_major"))]
+            values: ItemValues::default(),
+        }
+    }
+
+    #[cfg(not(feature = "row_major"))]
+    pub fn stats(&self) -> ItemStats {
+        item_stats(self.values.as_f32_slice().iter().copied())
+    }
+}
+
+/// Aggregates over the values of an item, see `ItemStats`.
+fn item_stats<I: IntoIterator<Item = f32>>(values: I) -> ItemStats {
+    let mut stats = ItemStats::default();
+    let mut sum = 0.0;
+    let mut count = 0;
+
+    for (i, value) in values.into_iter().enumerate() {
+        if value.is_nan() {
+            continue;
+        }
+        if stats.max.map_or(true, |max| value > max) {
+            stats.max = Some(value);
+            stats.argmax_index = Some(i);
+        }
+        stats.min = Some(stats.min.map_or(value, |min| min.min(value)));
+        stats.last = Some(value);
+        sum += value as f64;
+        count += 1;
+    }
+
+    if count > 0 {
+        stats.mean = Some((sum / count as f64) as f32);
+    }
+    stats
+}
+
+/// Values of all summary items for a single time iteration, in the precision of the source data.
+#[derive(Debug, PartialEq)]
+pub enum Params {
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+}
+
+impl Params {
+    fn len(&self) -> usize {
+        match self {
+            Params::F32(values) => values.len(),
+            Params::F64(values) => values.len(),
+        }
+    }
+}
+
+/// A union of (a subset of) data from both `SMSPEC` and `UNSMRY` files. The subset may eventually
+/// expand to cover more of the summary data, but right now we ignore data related to run-time
+/// monitoring.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    /// Grid dimensions of a simulation
+    pub dims: [i32; 3],
+
+    /// Unit system of a simulation, if known
+    pub units_system: Option<UnitSystem>,
+
+    /// Identifier of the simulator that produced the data, if known
+    pub simulator_id: Option<i32>,
+
+    /// Name of the case this run was restarted from, if any
+    pub restart_case: Option<String>,
+
+    /// Simulation unix timestamps
+    pub timestamps: Vec<i64>,
+
+    /// ItemId to its index in the items vector
+    pub item_ids: HashMap<ItemId, usize>,
+
+    /// Items listed more than once in the SMSPEC, with the index of every occurrence but the first
+    /// one, which is in `item_ids`
+    pub duplicates: Vec<(ItemId, usize)>,
+
+    /// Simulation data
+    pub items: Vec<SummaryItem>,
+
+    /// Item index to the item's XCOORD and YCOORD, if the SMSPEC provides them
+    pub coords: HashMap<usize, (f32, f32)>,
+
+    /// Measurement descriptions of the items from MEASRMNT, in the items order. Empty if the SMSPEC
+    /// doesn't provide them
+    pub measurements: Vec<FlexString>,
+
+    // Number of values per time iteration. There are more items than that only if the history of
+    // a restarted run contains items absent from the run itself.
+    n_params: usize,
+
+    // Index of the time item.
+    time_index: usize,
+
+    // Number of days in a unit of the time item.
+    days_per_time_unit: f32,
+
+    start_timestamp: i64,
+
+    // Values of all items, one time iteration after another.
+    #[cfg(feature = "row_major")]
+    rows: ItemValues,
+}
+
+impl Summary {
+    /// Read all the summary data currently available for a case in one go. The path may point to
+    /// either of the summary files or omit the extension altogether.
+    pub fn open<P>(input_path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let (summary, _) = SummaryFileReader::from_path(input_path)?.init()?;
+        Ok(summary)
+    }
+
+    /// Total number of summary items.
+    pub fn n_items(&self) -> usize {
+        self.items.len()
+    }
+
+    /// The item qualifier as displayed by `Display`, except that block and completion cells are
+    /// given by their grid coordinates, e.g. `Block (10, 3, 7)`, if they lie within the grid.
+    pub fn display_item(&self, id: &ItemId) -> String {
+        match (&id.qualifier, id.qualifier.block_ijk(self.dims)) {
+            (ItemQualifier::Block { .. }, Some((i, j, k))) => {
+                format!("Block ({}, {}, {})", i, j, k)
+            }
+            (ItemQualifier::Completion { wg_name, .. }, Some((i, j, k))) => {
+                format!("Completion ({}, {}, {}) @ {}", i, j, k, wg_name)
+            }
+            (qualifier, _) => qualifier.to_string(),
+        }
+    }
+
+    /// Ids of all the items with their index in the items vector, including the repeated
+    /// occurrences of duplicate items.
+    pub fn columns(&self) -> impl Iterator<Item = (&ItemId, usize)> {
+        self.item_ids
+            .iter()
+            .chain(self.duplicates.iter().map(|(id, index)| (id, index)))
+            .map(|(id, &index)| (id, index))
+    }
+
+    /// Items listed more than once in the SMSPEC, sorted, with the indices of all their
+    /// occurrences in the items vector.
+    pub fn duplicate_items(&self) -> Vec<(&ItemId, Vec<usize>)> {
+        let mut duplicates: BTreeMap<&ItemId, Vec<usize>> = BTreeMap::new();
+        for (id, index) in &self.duplicates {
+            duplicates
+                .entry(id)
+                .or_insert_with(|| vec![self.item_ids[id]])
+                .push(*index);
+        }
+        duplicates.into_iter().collect()
+    }
+
+    /// Ids of the items with an unrecognized keyword, sorted.
+    pub fn unrecognized(&self) -> Vec<&ItemId> {
+        let mut ids: Vec<&ItemId> = self
+            .item_ids
+            .keys()
+            .filter(|id| !id.qualifier.is_recognized())
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The X and Y coordinates of an item, e.g. of a well completion, if known.
+    pub fn item_coords(&self, id: &ItemId) -> Option<(f32, f32)> {
+        self.coords.get(self.item_ids.get(id)?).copied()
+    }
+
+    /// Datetime of the simulation start.
+    pub fn start_datetime(&self) -> NaiveDateTime {
+        DateTime::from_timestamp(self.start_timestamp, 0)
+            .map(|d| d.naive_utc())
+            .expect("invalid or out-of-range datetime")
+    }
+
+    /// Datetimes of all the stored time iterations.
+    pub fn timestamps_as_datetimes(&self) -> Vec<NaiveDateTime> {
+        self.timestamps
+            .iter()
+            .map(|&ts| {
+                DateTime::from_timestamp(ts, 0)
+                    .map(|d| d.naive_utc())
+                    .expect("invalid or out-of-range datetime")
+            })
+            .collect()
+    }
+
+    /// All items with the given keyword, e.g. WBHP for every well, sorted by `ItemId`.
+    pub fn items_by_name(&self, name: &str) -> Vec<(&ItemId, &SummaryItem)> {
+        let mut items: Vec<(&ItemId, &SummaryItem)> = self
+            .item_ids
+            .iter()
+            .filter(|(id, _)| id.name == name)
+            .map(|(id, &index)| (id, &self.items[index]))
+            .collect();
+        items.sort_unstable_by_key(|(id, _)| *id);
+        items
+    }
+
+    /// Number of time iterations that this Summary stores data for.
+    #[cfg(not(feature = "row_major"))]
+    pub fn n_steps(&self) -> usize {
+        match self.items.first() {
+            Some(items) => items.values.len(),
+            None => 0,
+        }
+    }
+
+    /// Number of time iterations that this Summary stores data for.
+    #[cfg(feature = "row_major")]
+    pub fn n_steps(&self) -> usize {
+        self.rows.len().checked_div(self.items.len()).unwrap_or(0)
+    }
+
+    /// Values of the item at the given index of `items`.
+    pub fn item_values(&self, index: usize) -> ValuesRef<'_> {
+        self.item_values_in(index, 0..self.n_steps())
+    }
+
+    #[cfg(not(feature = "row_major"))]
+    fn item_values_in(&self, index: usize, steps: Range<usize>) -> ValuesRef<'_> {
+        &self.items[index].values.as_f32_slice()[steps]
+    }
+
+    #[cfg(feature = "row_major")]
+    fn item_values_in(&self, index: usize, steps: Range<usize>) -> ValuesRef<'_> {
+        let n_items = self.items.len();
+        let rows = self.rows.as_f32_slice();
+        Cow::Owned(steps.map(|step| rows[step * n_items + index]).collect())
+    }
+
+    /// The values of the item at the given index of `items` one by one, without copying them.
+    #[cfg(not(feature = "row_major"))]
+    pub fn values_for(&self, index: usize) -> impl Iterator<Item = f32> + '_ {
+        self.items[index].values.as_f32_slice().iter().copied()
+    }
+
+    /// The values of the item at the given index of `items` one by one, without copying them.
+    #[cfg(feature = "row_major")]
+    pub fn values_for(&self, index: usize) -> impl Iterator<Item = f32> + '_ {
+        let rows = self.rows.as_f32_slice();
+        rows.iter().skip(index).step_by(self.items.len()).copied()
+    }
+
+    /// Replace the contents of the buffer with the values of the item at the given index of
+    /// `items`, reusing its allocation.
+    pub fn copy_values_into(&self, index: usize, buf: &mut Vec<f32>) {
+        buf.clear();
+        buf.extend(self.values_for(index));
+    }
+
+    /// Values of the item at the given index of `items` in their stored precision.
+    #[cfg(not(feature = "row_major"))]
+    pub(crate) fn item_column(&self, index: usize) -> Cow<'_, ItemValues> {
+        Cow::Borrowed(&self.items[index].values)
+    }
+
+    /// Values of the item at the given index of `items` in their stored precision.
+    #[cfg(feature = "row_major")]
+    pub(crate) fn item_column(&self, index: usize) -> Cow<'_, ItemValues> {
+        Cow::Owned(self.rows.strided(index, self.items.len()))
+    }
+
+    /// Replace every value of the item at the given index of `items` by the result of the
+    /// function, keeping the precision.
+    #[cfg(not(feature = "row_major"))]
+    pub(crate) fn map_item_values<F: Fn(f64) -> f64>(&mut self, index: usize, f: F) {
+        self.items[index].values.map_values(0, 1, f);
+    }
+
+    /// Replace every value of the item at the given index of `items` by the result of the
+    /// function, keeping the precision.
+    #[cfg(feature = "row_major")]
+    pub(crate) fn map_item_values<F: Fn(f64) -> f64>(&mut self, index: usize, f: F) {
+        self.rows.map_values(index, self.items.len(), f);
+    }
+
+    /// Move the values of all items out of the summary, one vector per item.
+    #[cfg(not(feature = "row_major"))]
+    fn take_columns(&mut self) -> Vec<ItemValues> {
+        self.items
+            .iter_mut()
+            .map(|item| std::mem::take(&mut item.values))
+            .collect()
+    }
+
+    /// Move the values of all items out of the summary, one vector per item.
+    #[cfg(feature = "row_major")]
+    fn take_columns(&mut self) -> Vec<ItemValues> {
+        let n_items = self.items.len();
+        let rows = std::mem::take(&mut self.rows);
+        (0..n_items)
+            .map(|index| rows.strided(index, n_items))
+            .collect()
+    }
+
+    /// Store the values of all items, given as one vector per item of the same length.
+    #[cfg(not(feature = "row_major"))]
+    fn set_columns(&mut self, columns: Vec<ItemValues>) {
+        for (item, values) in self.items.iter_mut().zip(columns) {
+            item.values = values;
+        }
+    }
+
+    /// Store the values of all items, given as one vector per item of the same length.
+    #[cfg(feature = "row_major")]
+    fn set_columns(&mut self, columns: Vec<ItemValues>) {
+        let n_steps = columns.first().map_or(0, ItemValues::len);
+        let double = columns.iter().any(ItemValues::is_double_precision);
+
+        let mut rows = ItemValues::default();
+        rows.reserve(n_steps * columns.len());
+        for step in 0..n_steps {
+            for values in &columns {
+                if double {
+                    rows.push_f64(values.get_f64(step));
+                } else {
+                    rows.push_f32(values.as_f32_slice()[step]);
+                }
+            }
+        }
+        self.rows = rows;
+    }
+
+    /// This function expects the size of params to equal the size of items. Items coming only from
+    /// the history of a restarted run are padded with NaNs.
+    pub fn append(&mut self, params: Vec<f32>) -> Result<()> {
+        self.append_params(Params::F32(params))
+    }
+
+    /// Same as `append`, but the values are stored in double precision.
+    pub fn append_f64(&mut self, params: Vec<f64>) -> Result<()> {
+        self.append_params(Params::F64(params))
+    }
+
+    /// Reserve room for at least `n_steps` more time iterations, so that appending them doesn't
+    /// grow the value vectors of every item over and over.
+    pub fn reserve_steps(&mut self, n_steps: usize) {
+        self.timestamps.reserve(n_steps);
+        #[cfg(not(feature = "row_major"))]
+        for item in &mut self.items {
+            item.values.reserve(n_steps);
+        }
+        #[cfg(feature = "row_major")]
+        self.rows.reserve(n_steps * self.items.len());
+    }
+
+    /// Append values of either precision. Once double precision values are appended, all values of
+    /// the summary items are stored in double precision.
+    pub fn append_params(&mut self, params: Params) -> Result<()> {
+        self.append_params_batch(vec![params])
+    }
+
+    /// Append many time iterations at once, which is considerably faster than appending them one
+    /// by one when there are many items. If one of them has the wrong length, the time iterations
+    /// preceding it are appended and the error reports it. The ones following it are dropped.
+    pub fn append_params_batch(&mut self, mut batch: Vec<Params>) -> Result<()> {
+        let invalid = batch
+            .iter()
+            .position(|params| params.len() != self.n_params);
+        let result = match invalid {
+            Some(index) => {
+                let found = batch[index].len();
+                batch.truncate(index);
+                Err(EclairError::UnexpectedRecordDataLength {
+                    name: "PARAMS".to_string(),
+                    expected: self.n_params,
+                    found,
+                })
+            }
+            None => Ok(()),
+        };
+
+        for params in &batch {
+            let new_seconds = match params {
+                Params::F32(params) => {
+                    (params[self.time_index] * self.days_per_time_unit * 86400.0) as f64
+                }
+                Params::F64(params) => {
+                    params[self.time_index] * self.days_per_time_unit as f64 * 86400.0
+                }
+            };
+            let new_ts = self.start_timestamp + Duration::seconds(new_seconds as i64).num_seconds();
+            self.timestamps.push(new_ts);
+        }
+
+        self.append_values(&batch);
+        result
+    }
+
+    /// Append the values of the time iterations to the items. Items of the restart history absent
+    /// from this run are padded with NaNs.
+    #[cfg(not(feature = "row_major"))]
+    fn append_values(&mut self, batch: &[Params]) {
+        // Number of items whose values are appended together. The item values are stored per item,
+        // so this keeps the ends of the item vectors being appended to in the cache.
+        const ITEMS_BLOCK: usize = 64;
+
+        for (block_index, items) in self.items.chunks_mut(ITEMS_BLOCK).enumerate() {
+            let start = block_index * ITEMS_BLOCK;
+            for item in items.iter_mut() {
+                item.values.reserve(batch.len());
+            }
+
+            for params in batch {
+                match params {
+                    Params::F32(params) => {
+                        let params = params.iter().skip(start).chain(iter::repeat(&f32::NAN));
+                        for (item, &param) in items.iter_mut().zip(params) {
+                            item.values.push_f32(param);
+                        }
+                    }
+                    Params::F64(params) => {
+                        let params = params.iter().skip(start).chain(iter::repeat(&f64::NAN));
+                        for (item, &param) in items.iter_mut().zip(params) {
+                            item.values.push_f64(param);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Append the values of the time iterations as rows. Items of the restart history absent from
+    /// this run are padded with NaNs.
+    #[cfg(feature = "row_major")]
+    fn append_values(&mut self, batch: &[Params]) {
+        let n_padded = self.items.len() - self.n_params;
+        self.rows.reserve(batch.len() * self.items.len());
+        for params in batch {
+            match params {
+                Params::F32(params) => {
+                    let padding = iter::repeat(f32::NAN).take(n_padded);
+                    self.rows.extend_f32(params.iter().copied().chain(padding));
+                }
+                Params::F64(params) => {
+                    let padding = iter::repeat(f64::NAN).take(n_padded);
+                    for param in params.iter().copied().chain(padding) {
+                        self.rows.push_f64(param);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Prepend the data of a run this one was restarted from. Only the time iterations preceding
+    /// the first one of this run are taken from the history. Items present only in one of the runs
+    /// are padded with NaNs.
+    fn splice_restart(&mut self, mut history: Summary) {
+        let n_history_steps = match self.timestamps.first() {
+            Some(&first) => history.timestamps.partition_point(|&ts| ts < first),
+            None => history.n_steps(),
+        };
+        let n_steps = self.n_steps();
+
+        let mut columns = self.take_columns();
+        let mut history_columns: Vec<Option<ItemValues>> =
+            history.take_columns().into_iter().map(Some).collect();
+
+        for (id, &index) in &self.item_ids {
+            let mut values = match history.item_ids.get(id) {
+                Some(&history_index) => history_columns[history_index].take().unwrap(),
+                None => ItemValues::default(),
+            };
+            values.resize(n_history_steps);
+            values.append(std::mem::take(&mut columns[index]));
+            columns[index] = values;
+        }
+
+        // The history of a duplicate item goes to its first occurrence only.
+        for &(_, index) in &self.duplicates {
+            let mut values = ItemValues::default();
+            values.resize(n_history_steps);
+            values.append(std::mem::take(&mut columns[index]));
+            columns[index] = values;
+        }
+
+        for (id, history_index) in history.item_ids {
+            if let Some(mut values) = history_columns[history_index].take() {
+                values.resize(n_history_steps);
+                values.resize(n_history_steps + n_steps);
+                if let Some(&coords) = history.coords.get(&history_index) {
+                    self.coords.insert(self.items.len(), coords);
+                }
+                if !self.measurements.is_empty() {
+                    let measurement = history.measurements.get(history_index).cloned();
+                    self.measurements.push(measurement.unwrap_or_default());
+                }
+                self.item_ids.insert(id, self.items.len());
+                self.items.push(history.items[history_index].clone());
+                columns.push(values);
+            }
+        }
+        self.set_columns(columns);
+
+        let mut timestamps = history.timestamps;
+        timestamps.truncate(n_history_steps);
+        timestamps.append(&mut self.timestamps);
+        self.timestamps = timestamps;
+    }
+
+    /// A view of the time iterations within the inclusive range of the unix timestamps. The range
+    /// may extend beyond the stored data, in which case the view covers only the stored part of it.
+    pub fn slice_time(&self, start: i64, end: i64) -> SummaryView<'_> {
+        let first = self.timestamps.partition_point(|&ts| ts < start);
+        let last = self.timestamps.partition_point(|&ts| ts <= end);
+        SummaryView {
+            summary: self,
+            range: first..last.max(first),
+        }
+    }
+
+    /// Value of an item at the unix timestamp, linearly interpolated between the neighbouring time
+    /// iterations. None if the item is unknown or the timestamp is outside of the simulated range.
+    pub fn value_at(&self, id: &ItemId, timestamp: i64) -> Option<f32> {
+        let values = self.item_column(*self.item_ids.get(id)?);
+        let value = resample_values(&self.timestamps, values.as_f32_slice(), &[timestamp], true)[0];
+        if value.is_nan() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Timestamp of the last time iteration together with the last value of every item, sorted by
+    /// item id. None if there are no time iterations yet.
+    pub fn latest(&self) -> Option<(i64, Vec<(&ItemId, f32)>)> {
+        let &timestamp = self.timestamps.last()?;
+        let mut values: Vec<(&ItemId, f32)> = self
+            .item_ids
+            .iter()
+            .map(|(id, &index)| (id, self.values_for(index).last().unwrap()))
+            .collect();
+        values.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        Some((timestamp, values))
+    }
+
+    /// Aggregates over the values of an item. None if the item is unknown.
+    pub fn stats_for(&self, id: &ItemId) -> Option<ItemStats> {
+        self.item_ids
+            .get(id)
+            .map(|&index| item_stats(self.values_for(index)))
+    }
+
+    /// Cumulative integral of an item over time in days, e.g. the total production from a rate
+    /// item, computed with the trapezoidal rule. The result starts with zero at the first time
+    /// iteration. None if the item is unknown.
+    pub fn cumulative(&self, id: &ItemId) -> Option<Vec<f32>> {
+        let values = self.item_values(*self.item_ids.get(id)?);
+
+        let mut total = 0.0;
+        let mut cumulative = Vec::with_capacity(values.len());
+        for (i, &value) in values.iter().enumerate() {
+            if i > 0 {
+                let days = (self.timestamps[i] - self.timestamps[i - 1]) as f64 / 86400.0;
+                total += (values[i - 1] as f64 + value as f64) / 2.0 * days;
+            }
+            cumulative.push(total as f32);
+        }
+        Some(cumulative)
+    }
+
+    /// Resample the values of all items onto the given ascending timestamps. The result holds the
+    /// values in the order of `items`. Timestamps outside of the simulated time range get NaNs.
+    pub fn resample(&self, timestamps: &[i64], method: ResampleMethod) -> Vec<Vec<f32>> {
+        let mut linear = vec![method == ResampleMethod::Linear; self.items.len()];
+        if method == ResampleMethod::Auto {
+            for (id, index) in self.columns() {
+                linear[index] =
+                    id.qualifier == ItemQualifier::Time || id.name.as_bytes().get(3) == Some(&b'R');
+            }
+        }
+
+        linear
+            .into_iter()
+            .enumerate()
+            .map(|(index, linear)| {
+                resample_values(
+                    &self.timestamps,
+                    self.item_column(index).as_f32_slice(),
+                    timestamps,
+                    linear,
+                )
+            })
+            .collect()
+    }
+
+    /// Write the summary metadata as SMSPEC records. Well and group names are written as `NAMES`
+    /// instead of `WGNAMES` if any of them is longer than 8 characters.
+    pub fn write_smspec<W: Write>(&self, out: &mut W) -> Result<()> {
+        let mut ids = vec![None; self.items.len()];
+        for (id, index) in self.columns() {
+            ids[index] = Some(id);
+        }
+
+        let mut keywords = Vec::with_capacity(ids.len());
+        let mut wg_names = Vec::with_capacity(ids.len());
+        let mut nums = Vec::with_capacity(ids.len());
+        let mut lgrs = Vec::with_capacity(ids.len());
+        let mut local_cells = Vec::with_capacity(ids.len());
+        for id in ids.into_iter().flatten() {
+            let (wg_name, num) = id.smspec_fields();
+            let (lgr, ijk) = id.smspec_lgr_fields();
+            keywords.push(id.name.clone());
+            wg_names.push(wg_name);
+            nums.push(num);
+            lgrs.push(lgr);
+            local_cells.push(ijk);
+        }
+        let units = self.items.iter().map(|item| item.unit.clone()).collect();
+
+        if let Some(units_system) = self.units_system {
+            let intehead = vec![units_system.into(), self.simulator_id.unwrap_or(0)];
+            out.write_record(&Record::new("INTEHEAD", RecordData::Int(intehead)))?;
+        }
+
+        let restart_case = self.restart_case.as_deref().unwrap_or_default();
+        let restart = (0..9)
+            .map(|i| FlexString::from(restart_case.get(8 * i..).unwrap_or_default()))
+            .map(|chunk| chunk.chars().take(8).collect())
+            .collect();
+        out.write_record(&Record::new("RESTART", RecordData::Chars(restart)))?;
+
+        let [nx, ny, nz] = self.dims;
+        let dimens = vec![self.items.len() as i32, nx, ny, nz, 0, -1];
+        out.write_record(&Record::new("DIMENS", RecordData::Int(dimens)))?;
+
+        out.write_record(&Record::new("KEYWORDS", RecordData::Chars(keywords)))?;
+        let wg_names_record = if wg_names.iter().any(|name| name.len() > 8) {
+            "NAMES"
+        } else {
+            "WGNAMES"
+        };
+        out.write_record(&Record::new(wg_names_record, RecordData::Chars(wg_names)))?;
+        out.write_record(&Record::new("NUMS", RecordData::Int(nums)))?;
+        // The local grid records are only needed if there are local items.
+        if lgrs.iter().any(|lgr| !lgr.is_empty()) {
+            out.write_record(&Record::new("LGRS", RecordData::Chars(lgrs)))?;
+            for (axis, name) in ["NUMLX", "NUMLY", "NUMLZ"].iter().enumerate() {
+                let coords = local_cells.iter().map(|ijk| ijk[axis]).collect();
+                out.write_record(&Record::new(name, RecordData::Int(coords)))?;
+            }
+        }
+        out.write_record(&Record::new("UNITS", RecordData::Chars(units)))?;
+
+        let start = self.start_datetime();
+        let start_dat = vec![
+            start.day() as i32,
+            start.month() as i32,
+            start.year(),
+            start.hour() as i32,
+            start.minute() as i32,
+            start.second() as i32 * 1_000_000,
+        ];
+        out.write_record(&Record::new("STARTDAT", RecordData::Int(start_dat)))?;
+
+        Ok(())
+    }
+
+    /// Write the time series as SEQHDR/MINISTEP/PARAMS triplets, one per time iteration. PARAMS are
+    /// written in double precision if any of the items is stored as such.
+    pub fn write_unsmry<W: Write>(&self, out: &mut W) -> Result<()> {
+        let columns: Vec<Cow<'_, ItemValues>> = (0..self.n_items())
+            .map(|index| self.item_column(index))
+            .collect();
+        let double = columns.iter().any(|values| values.is_double_precision());
+
+        for step in 0..self.n_steps() {
+            let params = if double {
+                RecordData::F64(columns.iter().map(|values| values.get_f64(step)).collect())
+            } else {
+                RecordData::F32(
+                    columns
+                        .iter()
+                        .map(|values| values.as_f32_slice()[step])
+                        .collect(),
+                )
+            };
+            out.write_record(&Record::new("SEQHDR", RecordData::Int(vec![0])))?;
+            out.write_record(&Record::new("MINISTEP", RecordData::Int(vec![step as i32])))?;
+            out.write_record(&Record::new("PARAMS", params))?;
+        }
+        Ok(())
+    }
+
+    /// The summary as a JSON object with the grid `dims`, the ISO 8601 `start` datetime, the unix
+    /// `timestamps` and the `items` array, sorted by `ItemId`. Each item holds its `name`,
+    /// `qualifier`, `unit` and `values`. Missing values are `null`.
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct JsonItem<'a> {
+            name: &'a str,
+            qualifier: &'a ItemQualifier,
+            unit: &'a str,
+            values: Cow<'a, ItemValues>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonSummary<'a> {
+            dims: [i32; 3],
+            start: String,
+            timestamps: &'a [i64],
+            items: Vec<JsonItem<'a>>,
+        }
+
+        let mut ids: Vec<(&ItemId, usize)> = self.columns().collect();
+        ids.sort_unstable();
+
+        let json = JsonSummary {
+            dims: self.dims,
+            start: self
+                .start_datetime()
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+            timestamps: &self.timestamps,
+            items: ids
+                .into_iter()
+                .map(|(id, index)| JsonItem {
+                    name: &id.name,
+                    qualifier: &id.qualifier,
+                    unit: &self.items[index].unit,
+                    values: self.item_column(index),
+                })
+                .collect(),
+        };
+        Ok(serde_json::to_string(&json)?)
+    }
+
+    /// Write the summary as CSV: a leading ISO 8601 datetime column followed by a column per item,
+    /// sorted by `ItemId`, and a row per time iteration. Item columns are headed by the full item
+    /// name and the unit in brackets, e.g. `WBHP:P1 [PSIA]`. Returns the number of data rows.
+    pub fn write_csv<W: Write>(&self, out: W) -> Result<usize> {
+        let opts = CsvOptions {
+            header: CsvHeader::ItemNames,
+            ..CsvOptions::default()
+        };
+        Ok(to_csv_to_writer(self, out, &opts)?.n_rows)
+    }
+
+    /// Write the summary into an SMSPEC and UNSMRY pair of files. The extension of the path is
+    /// replaced with the appropriate one.
+    pub fn save_to_files<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let mut smspec = BufWriter::new(File::create(path.with_extension("SMSPEC"))?);
+        self.write_smspec(&mut smspec)?;
+        smspec.flush()?;
+
+        let mut unsmry = BufWriter::new(File::create(path.with_extension("UNSMRY"))?);
+        self.write_unsmry(&mut unsmry)?;
+        unsmry.flush()?;
+
+        Ok(())
+    }
+}
+
+/// A summary is serialized with its items grouped by the qualifier kind and sorted by `ItemId`
+/// within a group. Each item holds its id, unit and values. Items listed more than once in the
+/// SMSPEC are serialized once per occurrence.
+impl Serialize for Summary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct SerializedItem<'a> {
+            #[serde(flatten)]
+            id: &'a ItemId,
+            unit: &'a str,
+            values: Cow<'a, ItemValues>,
+        }
+
+        let mut ids: Vec<(&ItemId, usize)> = self.columns().collect();
+        ids.sort_unstable();
+
+        let mut items: BTreeMap<&str, Vec<SerializedItem<'_>>> = BTreeMap::new();
+        for (id, index) in ids {
+            let item = &self.items[index];
+            items
+                .entry(id.qualifier.kind())
+                .or_default()
+                .push(SerializedItem {
+                    id,
+                    unit: &item.unit,
+                    values: self.item_column(index),
+                });
+        }
+
+        let start_date = self
+            .start_datetime()
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
+
+        let mut state = serializer.serialize_struct("Summary", 7)?;
+        state.serialize_field("dims", &self.dims)?;
+        state.serialize_field("start_date", &start_date)?;
+        state.serialize_field("units_system", &self.units_system)?;
+        state.serialize_field("simulator_id", &self.simulator_id)?;
+        state.serialize_field("restart_case", &self.restart_case)?;
+        state.serialize_field("timestamps", &self.timestamps)?;
+        state.serialize_field("items", &items)?;
+        state.end()
+    }
+}
+
+/// Reads back the layout written by `Serialize`. Every item takes part in the time iterations
+/// appended afterwards.
+impl<'de> Deserialize<'de> for Summary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct SerializedItem {
+            #[serde(flatten)]
+            id: ItemId,
+            unit: FlexString,
+            values: ItemValues,
+        }
+
+        #[derive(Deserialize)]
+        struct SerializedSummary {
+            dims: [i32; 3],
+            start_date: String,
+            units_system: Option<UnitSystem>,
+            simulator_id: Option<i32>,
+            restart_case: Option<String>,
+            timestamps: Vec<i64>,
+            items: BTreeMap<String, Vec<SerializedItem>>,
+        }
+
+        let summary = SerializedSummary::deserialize(deserializer)?;
+
+        let start = NaiveDateTime::parse_from_str(&summary.start_date, "%Y-%m-%dT%H:%M:%S")
+            .map_err(de::Error::custom)?;
+
+        let mut item_ids = HashMap::new();
+        let mut duplicates = Vec::new();
+        let mut items = Vec::new();
+        let mut columns = Vec::new();
+        for item in summary.items.into_values().flatten() {
+            if item.values.len() != summary.timestamps.len() {
+                return Err(de::Error::custom(format!(
+                    "item {} has {} values for {} timestamps",
+                    item.id,
+                    item.values.len(),
+                    summary.timestamps.len()
+                )));
+            }
+            // The first occurrence of a duplicate item comes first and is the one looked up.
+            match item_ids.entry(item.id) {
+                Entry::Occupied(entry) => duplicates.push((entry.key().clone(), items.len())),
+                Entry::Vacant(entry) => {
+                    entry.insert(items.len());
+                }
+            }
+            items.push(SummaryItem::new(item.unit));
+            columns.push(item.values);
+        }
+
+        let (time_index, days_per_time_unit) =
+            find_time_item(&item_ids).map_err(de::Error::custom)?;
+
+        let mut summary = Summary {
+            dims: summary.dims,
+            units_system: summary.units_system,
+            simulator_id: summary.simulator_id,
+            restart_case: summary.restart_case,
+            timestamps: summary.timestamps,
+            item_ids,
+            duplicates,
+            n_params: items.len(),
+            items,
+            coords: HashMap::new(),
+            measurements: vec![],
+            time_index,
+            days_per_time_unit,
+            start_timestamp: start.and_utc().timestamp(),
+            #[cfg(feature = "row_major")]
+            rows: ItemValues::default(),
+        };
+        summary.set_columns(columns);
+        Ok(summary)
+    }
+}
+
+/// Intermediate type for Smspec data to facilitate input validation. It contains a subset of
+/// records from which a valid Summary COULD be constructed. At the point of its construction the
+/// only input error we check for is the presence of duplicate records.
+#[derive(Debug)]
+pub struct SmspecRecords {
+    records: HashMap<&'static str, Option<RecordData>>,
+}
+
+impl Default for SmspecRecords {
+    fn default() -> Self {
+        let mut records = HashMap::new();
+        records.insert("INTEHEAD", None);
+        records.insert("RESTART", None);
+        records.insert("DIMENS", None);
+        records.insert("STARTDAT", None);
+        records.insert("KEYWORDS", None);
+        records.insert("WGNAMES", None);
+        records.insert("NUMS", None);
+        records.insert("UNITS", None);
+        records.insert("XCOORD", None);
+        records.insert("YCOORD", None);
+        records.insert("MEASRMNT", None);
+        records.insert("LGRS", None);
+        records.insert("NUMLX", None);
+        records.insert("NUMLY", None);
+        records.insert("NUMLZ", None);
+        SmspecRecords { records }
+    }
+}
+
+impl SmspecRecords {
+    pub(crate) fn new(records: HashMap<&'static str, Option<RecordData>>) -> Self {
+        SmspecRecords { records }
+    }
+
+    /// Collect the SMSPEC records among the given ones, e.g. the records of an SMSPEC file read
+    /// with `RecordReader`. Collecting stops at the first record that doesn't belong in SMSPEC.
+    pub fn from_records<I>(records: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = Record>,
+    {
+        let mut smspec_records = SmspecRecords::default();
+        for record in records {
+            if !smspec_records.add(record)? {
+                break;
+            }
+        }
+        Ok(smspec_records)
+    }
+
+    /// Keep the record if it belongs in SMSPEC. Returns whether more records are needed.
+    fn add(&mut self, record: Record) -> Result<bool> {
+        let Record { name, data } = record;
+        // Stop reading records if we encounter a name that does not belong in SMSPEC.
+        if !SMSPEC_RECORDS.contains(&name.as_str()) {
+            log::debug!(target: "Parsing SMSPEC", "Non-SMSPEC record name encountered: {}.", name);
+            return Ok(false);
+        }
+
+        // If we encounter a record that we wish to consume, first check whether we've already
+        // read it. "NAMES" is looked up as "WGNAMES" because only one of them is allowed in
+        // a given SMSPEC at the same time.
+        let lookup_name = if &name == "NAMES" { "WGNAMES" } else { &name };
+        if let Some(val) = self.records.get_mut(lookup_name) {
+            if val.is_some() {
+                return Err(EclairError::RecordEncounteredTwice(name.to_string()));
+            }
+            *val = Some(data);
+        }
+
+        // If we found all the records we need, stop reading further. This allows us to chain
+        // valid SMSPEC and UNSMRY records in a single stream.
+        if self.is_full() {
+            log::debug!(target: "Parsing SMSPEC", "Found all the neccessary records.");
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn is_full(&self) -> bool {
+        self.records.values().all(|val| val.is_some())
+    }
+}
+
+macro_rules! validate {
+            ($field_data: ident, $field_name: literal, $kind: ident, $($valid_len: expr),+ $(,)?) => {
+                {
+                    // Long strings, e.g. in NAMES, are validated as any other strings.
+                    let $field_data = $field_data.without_width();
+                    let values = if let RecordData::$kind(values) = $field_data {
+                        values
+                    } else {
+                        return Err(EclairError::InvalidRecordDataType {
+                            name: $field_name.to_string(),
+                            expected: RecordDataKind::$kind.to_string(),
+                            found: $field_data.kind_string(),
+                        });
+                    };
+
+                    let len_err = |expected| Err(EclairError::UnexpectedRecordDataLength {
+                        name: $field_name.to_string(),
+                        expected,
+                        found: values.len(),
+                    });
+
+                    // The valid lengths are listed in increasing order.
+                    let valid_lens = [$($valid_len),+];
+                    match valid_lens.iter().find(|&&len| values.len() <= len) {
+                        Some(&len) if len == values.len() => values,
+                        Some(&len) => return len_err(len),
+                        None => return len_err(valid_lens[valid_lens.len() - 1]),
+                    }
+                }
+            };
+        }
+
+impl TryFrom<SmspecRecords> for Summary {
+    type Error = EclairError;
+
+    fn try_from(value: SmspecRecords) -> Result<Self> {
+        Summary::try_from_with_classifier(value, None)
+    }
+}
+
+impl Summary {
+    /// Build an empty Summary from the SMSPEC records. The classifier, if any, is asked for the
+    /// qualifier of every item before the default classification.
+    pub fn try_from_with_classifier(
+        mut value: SmspecRecords,
+        classifier: Option<&ItemClassifier>,
+    ) -> Result<Self> {
+        use EclairError::*;
+
+        macro_rules! extract_and_validate {
+            ($field_name: literal, $kind: ident, $($valid_len: expr),+ $(,)?) => {
+                {
+                   let field_data = value
+                    .records
+                    .remove($field_name)
+                    .unwrap()
+                    .ok_or_else(|| MissingRecord($field_name.to_string()))?;
+
+                   validate!(field_data, $field_name, $kind, $($valid_len),+)
+                }
+            };
+        }
+
+        macro_rules! extract_optional_and_validate {
+            ($field_name: literal, $kind: ident, $($valid_len: expr),+ $(,)?) => {
+                match value.records.remove($field_name).flatten() {
+                    Some(field_data) => Some(validate!(field_data, $field_name, $kind, $($valid_len),+)),
+                    None => None,
+                }
+            };
+        }
+
+        let intehead = extract_optional_and_validate!("INTEHEAD", Int, 2);
+        let restart = extract_optional_and_validate!("RESTART", Chars, 9);
+
+        let dimens = extract_and_validate!("DIMENS", Int, 6);
+        let nlist = dimens[0] as usize;
+
+        // If all the per-item records agree on their length and DIMENS doesn't, then DIMENS is the
+        // one to blame, rather than each of the records.
+        let lengths: HashSet<usize> = NLIST_RECORDS
+            .iter()
+            .filter_map(|name| value.records.get(name)?.as_ref())
+            .map(|data| data.n_elements())
+            .collect();
+        if lengths.len() == 1 {
+            let found = lengths.into_iter().next().unwrap();
+            if found != nlist {
+                return Err(InconsistentNlist {
+                    dimens: nlist,
+                    found,
+                });
+            }
+        }
+
+        let start_dat = extract_and_validate!("STARTDAT", Int, 3, 6);
+        let keywords = extract_and_validate!("KEYWORDS", Chars, nlist);
+        let wg_names = extract_and_validate!("WGNAMES", Chars, nlist);
+        let nums = extract_and_validate!("NUMS", Int, nlist);
+        let units = extract_and_validate!("UNITS", Chars, nlist);
+
+        // Coordinates are optional, so we ignore them rather than fail if they are invalid.
+        let x_coords = value.records.remove("XCOORD").flatten();
+        let y_coords = value.records.remove("YCOORD").flatten();
+        let coords = match (x_coords, y_coords) {
+            (Some(RecordData::F32(x)), Some(RecordData::F32(y)))
+                if x.len() == nlist && y.len() == nlist =>
+            {
+                x.into_iter().zip(y).enumerate().collect()
+            }
+            (None, None) => HashMap::new(),
+            _ => {
+                log::warn!(target: "Parsing SMSPEC", "Ignoring the invalid XCOORD and YCOORD records.");
+                HashMap::new()
+            }
+        };
+
+        // Local grid names and cells are optional as well. Items with a blank name are global.
+        let lgr_records = (
+            value.records.remove("LGRS").flatten(),
+            value.records.remove("NUMLX").flatten(),
+            value.records.remove("NUMLY").flatten(),
+            value.records.remove("NUMLZ").flatten(),
+        );
+        let lgr_cells: Vec<(FlexString, [i32; 3])> = match lgr_records {
+            (
+                Some(lgrs),
+                Some(RecordData::Int(x)),
+                Some(RecordData::Int(y)),
+                Some(RecordData::Int(z)),
+            ) if lgrs.as_chars().map(|lgrs| lgrs.len()) == Some(nlist)
+                && x.len() == nlist
+                && y.len() == nlist
+                && z.len() == nlist =>
+            {
+                let lgrs = lgrs.as_chars().unwrap().to_vec();
+                multizip((lgrs, x, y, z))
+                    .map(|(lgr, i, j, k)| (lgr, [i, j, k]))
+                    .collect()
+            }
+            (None, None, None, None) => vec![],
+            _ => {
+                log::warn!(target: "Parsing SMSPEC", "Ignoring the invalid LGRS and NUMLX/Y/Z records.");
+                vec![]
+            }
+        };
+
+        // Every item is described by the same number of strings, which have to be padded back to
+        // their width to join them. Invalid descriptions are ignored as the coordinates are.
+        let measurements = match value.records.remove("MEASRMNT").flatten() {
+            None => vec![],
+            Some(data) => {
+                let width = match &data {
+                    RecordData::LongChars { width, .. } => *width,
+                    _ => FIXED_STRING_LENGTH,
+                };
+                match data.as_chars() {
+                    Some(chunks)
+                        if nlist > 0 && !chunks.is_empty() && chunks.len() % nlist == 0 =>
+                    {
+                        chunks
+                            .chunks(chunks.len() / nlist)
+                            .map(|item_chunks| {
+                                let description: String = item_chunks
+                                    .iter()
+                                    .map(|chunk| format!("{:<1$}", chunk, width))
+                                    .collect();
+                                FlexString::from(description.trim_end())
+                            })
+                            .collect()
+                    }
+                    _ => {
+                        log::warn!(target: "Parsing SMSPEC", "Ignoring the invalid MEASRMNT record.");
+                        vec![]
+                    }
+                }
+            }
+        };
+
+        // Now we prepare to construct the Summary object.
+        let dims = dimens[1..4].try_into().unwrap();
+
+        let (units_system, simulator_id) = match intehead {
+            Some(intehead) => (Some(UnitSystem::try_from(intehead[0])?), Some(intehead[1])),
+            None => (None, None),
+        };
+
+        let restart_case = restart
+            .map(|restart| restart.concat())
+            .filter(|name| !name.is_empty());
+
+        let d = NaiveDate::from_ymd(start_dat[2], start_dat[1] as u32, start_dat[0] as u32);
+
+        let ts = if start_dat.len() == 3 {
+            d.and_hms(0, 0, 0)
+        } else {
+            d.and_hms_milli(
+                start_dat[3] as u32,
+                start_dat[4] as u32,
+                (start_dat[5] / 1_000_000) as u32,
+                (start_dat[5] % 1_000_000) as u32,
+            )
+        };
+
+        let mut item_ids = HashMap::new();
+        let mut duplicates = Vec::new();
+        let mut items = Vec::with_capacity(nlist);
+
+        for (n, vals) in multizip((keywords, wg_names, nums, units)).enumerate() {
+            let (name, wg_name, index, unit) = vals;
+            let lgr_cell = lgr_cells.get(n).filter(|(lgr, _)| !lgr.is_empty());
+            let item_id = match classifier.and_then(|classify| classify(&name, &wg_name, index)) {
+                Some(qualifier) => ItemId { name, qualifier },
+                None => match lgr_cell {
+                    Some((lgr, ijk)) => ItemId::new_local(name, wg_name, index, lgr.clone(), *ijk),
+                    None => ItemId::new(name, wg_name, index),
+                },
+            };
+
+            // Items stay in the PARAMS order. Only the first of the duplicates can be looked up by
+            // the id, the others are kept aside.
+            match item_ids.entry(item_id) {
+                Entry::Occupied(entry) => {
+                    log::warn!(target: "Reading summary", "Duplicate summary item {}, looking up the first occurrence.", entry.key());
+                    duplicates.push((entry.key().clone(), items.len()));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(items.len());
+                }
+            }
+            items.push(SummaryItem::new(unit));
+        }
+
+        // Report the unrecognized keywords at once rather than item by item.
+        let unrecognized: Vec<&ItemId> = item_ids
+            .keys()
+            .filter(|id| !id.qualifier.is_recognized())
+            .collect();
+        if !unrecognized.is_empty() {
+            let keywords: BTreeSet<&str> = unrecognized.iter().map(|id| id.name.as_str()).collect();
+            let keywords: Vec<&str> = keywords.into_iter().collect();
+            log::info!(target: "Building SummaryItem", "{} unrecognized items: {}", unrecognized.len(), keywords.join(", "));
+        }
+
+        let (time_index, days_per_time_unit) = find_time_item(&item_ids)?;
+
+        Ok(Summary {
+            dims,
+            units_system,
+            simulator_id,
+            restart_case,
+            timestamps: vec![],
+            item_ids,
+            duplicates,
+            items,
+            coords,
+            measurements,
+            n_params: nlist,
+            time_index,
+            days_per_time_unit,
+            start_timestamp: ts.timestamp(),
+            #[cfg(feature = "row_major")]
+            rows: ItemValues::default(),
+        })
+    }
+}
+
+/// Index of the item measuring the simulation time and the number of days in its unit. The time is
+/// measured by TIME (in days) or, failing that, by YEARS.
+fn find_time_item(item_ids: &HashMap<ItemId, usize>) -> Result<(usize, f32)> {
+    let find = |name| {
+        item_ids
+            .get(&ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::Time,
+            })
+            .copied()
+    };
+
+    if let Some(index) = find("TIME") {
+        Ok((index, 1.0))
+    } else if let Some(index) = find("YEARS") {
+        Ok((index, 365.25))
+    } else {
+        Err(EclairError::MissingTimeItem)
+    }
+}
+
+/// Implementations of InitializeSummary can build a Summary instance and an object that can be
+/// subsequently used to append more data to it.
+pub trait InitializeSummary {
+    // Updater is meant to be moved to a separate thread.
+    type Updater: UpdateSummary + Send + 'static;
+
+    /// Read enough data to build a valid Summary instance and convert yourself to the Updater
+    /// object.
+    fn init(self) -> Result<(Summary, Self::Updater)>;
+}
+
+/// UpdateSummary implementations provide new summary data using the supplied channel.
+pub trait UpdateSummary {
+    fn update(&mut self, data_snd: Sender<Params>, term_rcv: Receiver<bool>) -> Result<()>;
+
+    /// Flag telling whether the data source is currently connected, for sources that can lose
+    /// their connection and get it back.
+    fn connection_flag(&self) -> Option<Arc<AtomicBool>> {
+        None
+    }
+
+    /// Flag telling whether the data source has gone quiet for longer than expected, for sources
+    /// that are supposed to send data regularly.
+    fn stale_flag(&self) -> Option<Arc<AtomicBool>> {
+        None
+    }
+}
+
+/// A seekable source of Eclipse records, either binary or formatted.
+trait RecordSource: ReadRecord + Seek + Send {}
+
+impl<T: ReadRecord + Seek + Send> RecordSource for T {}
+
+/// Open a file as a source of Eclipse records.
+fn open_records(path: &Path, formatted: bool) -> Result<Box<dyn RecordSource>> {
+    #[allow(unused_mut)]
+    let mut file = BufReader::new(File::open(path)?);
+
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::BufRead;
+
+        if file.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            let file = GzFile::new(file);
+            return Ok(if formatted {
+                Box::new(FormattedReader::new(BufReader::new(file)))
+            } else {
+                Box::new(RecordReader::new(file))
+            });
+        }
+    }
+
+    Ok(if formatted {
+        Box::new(FormattedReader::new(file))
+    } else {
+        Box::new(RecordReader::new(file))
+    })
+}
+
+/// Check whether the file extension denotes a non-unified summary file, i.e. it is a letter (`S`
+/// for binary and `A` for formatted files) followed by four digits.
+fn is_separate_summary_ext(ext: &str, letter: char) -> bool {
+    ext.len() == 5 && ext.starts_with(letter) && ext[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// List all non-unified summary files of a case, sorted by their report step number.
+fn list_separate_files(case_path: &Path, letter: char) -> std::io::Result<Vec<PathBuf>> {
+    let stem = case_path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let dir = match case_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let matches = path.file_stem().and_then(|s| s.to_str()) == Some(stem)
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| is_separate_summary_ext(ext, letter));
+        if matches {
+            files.push(path);
+        }
+    }
+
+    // The extensions have the same length, so the lexicographical order is the numerical one.
+    files.sort();
+    Ok(files)
+}
+
+/// Non-unified summary files read in order as if they were a single continuous stream. New files
+/// are picked up as they appear.
+struct SeparateFiles {
+    case_path: PathBuf,
+    letter: char,
+    files: Vec<PathBuf>,
+    current: usize,
+    file: Option<BufReader<File>>,
+    pos: u64,
+}
+
+impl SeparateFiles {
+    fn new(case_path: &Path, letter: char) -> Result<Self> {
+        let files = list_separate_files(case_path, letter)?;
+        let file = match files.first() {
+            Some(path) => Some(BufReader::new(File::open(path)?)),
+            None => None,
+        };
+
+        Ok(Self {
+            case_path: case_path.to_path_buf(),
+            letter,
+            files,
+            current: 0,
+            file,
+            pos: 0,
+        })
+    }
+
+    /// Open the file with the given index, if it exists.
+    fn open(&mut self, index: usize) -> std::io::Result<()> {
+        self.current = index;
+        self.file = match self.files.get(index) {
+            Some(path) => Some(BufReader::new(File::open(path)?)),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Refresh the list of files from the disk.
+    fn refresh(&mut self) -> std::io::Result<()> {
+        self.files = list_separate_files(&self.case_path, self.letter)?;
+        Ok(())
+    }
+}
+
+impl Read for SeparateFiles {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let n_bytes = match &mut self.file {
+                Some(file) => file.read(buf)?,
+                None => 0,
+            };
+
+            if n_bytes > 0 || buf.is_empty() {
+                self.pos += n_bytes as u64;
+                return Ok(n_bytes);
+            }
+
+            // The current file is exhausted, move on to the next one if it has appeared.
+            self.refresh()?;
+            let next = if self.file.is_some() {
+                self.current + 1
+            } else {
+                self.current
+            };
+            if next >= self.files.len() {
+                return Ok(0);
+            }
+            self.open(next)?;
+        }
+    }
+}
+
+impl Seek for SeparateFiles {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.refresh()?;
+        let sizes = self
+            .files
+            .iter()
+            .map(|path| Ok(std::fs::metadata(path)?.len()))
+            .collect::<std::io::Result<Vec<u64>>>()?;
+        let total: u64 = sizes.iter().sum();
+
+        let target = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::End(offset) => total as i64 + offset,
+            SeekFrom::Current(offset) => self.pos as i64 + offset,
+        };
+        if target < 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid seek to a negative position",
+            ));
+        }
+
+        // Find the file containing the target position. Positions past the end land in the last
+        // file.
+        let mut offset = target as u64;
+        let mut index = 0;
+        while index + 1 < sizes.len() && offset >= sizes[index] {
+            offset -= sizes[index];
+            index += 1;
+        }
+
+        self.open(index)?;
+        if let Some(file) = &mut self.file {
+            file.seek(SeekFrom::Start(offset))?;
+        }
+        self.pos = target as u64;
+        Ok(self.pos)
+    }
+}
+
+/// The first bytes of a gzip stream.
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// A gzip-compressed file, decompressed on the fly. Decompression can't go back, so seeking only
+/// succeeds to the current position. Gzipped sources must therefore be complete files: a read
+/// that stops in the middle of a record can't be resumed, so they are not watched for new data.
+#[cfg(feature = "gzip")]
+struct GzFile {
+    decoder: flate2::bufread::MultiGzDecoder<BufReader<File>>,
+    pos: u64,
+}
+
+#[cfg(feature = "gzip")]
+impl GzFile {
+    fn new(file: BufReader<File>) -> Self {
+        Self {
+            decoder: flate2::bufread::MultiGzDecoder::new(file),
+            pos: 0,
+        }
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl Read for GzFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n_bytes = self.decoder.read(buf)?;
+        self.pos += n_bytes as u64;
+        Ok(n_bytes)
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl Seek for GzFile {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match pos {
+            SeekFrom::Start(offset) if offset == self.pos => Ok(self.pos),
+            SeekFrom::Current(0) => Ok(self.pos),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Compressed files only support seeking to the current position",
+            )),
+        }
+    }
+}
+
+/// The path of the file, or of its gzipped copy if only that one exists.
+fn with_gz_fallback(path: PathBuf) -> PathBuf {
+    #[cfg(feature = "gzip")]
+    if !path.exists() {
+        let mut gz_path = path.clone().into_os_string();
+        gz_path.push(".gz");
+        let gz_path = PathBuf::from(gz_path);
+        if gz_path.exists() {
+            return gz_path;
+        }
+    }
+    path
+}
+
+/// Location of the files holding the summary time series.
+enum SummaryDataFiles {
+    Unified(PathBuf),
+    Separate { case_path: PathBuf, letter: char },
+}
+
+impl SummaryDataFiles {
+    /// The total size of the summary data files.
+    fn len(&self) -> Result<u64> {
+        match self {
+            SummaryDataFiles::Unified(path) => Ok(std::fs::metadata(path)?.len()),
+            SummaryDataFiles::Separate { case_path, letter } => {
+                let mut len = 0;
+                for path in list_separate_files(case_path, *letter)? {
+                    len += std::fs::metadata(path)?.len();
+                }
+                Ok(len)
+            }
+        }
+    }
+
+    /// The latest modification time among the summary data files.
+    fn modified(&self) -> Result<SystemTime> {
+        match self {
+            SummaryDataFiles::Unified(path) => Ok(std::fs::metadata(path)?.modified()?),
+            SummaryDataFiles::Separate { case_path, letter } => {
+                let mut modified = SystemTime::UNIX_EPOCH;
+                for path in list_separate_files(case_path, *letter)? {
+                    modified = modified.max(std::fs::metadata(path)?.modified()?);
+                }
+                Ok(modified)
+            }
+        }
+    }
+}
+
+/// How long to wait for a file change notification before checking the files anyway.
+#[cfg(feature = "watch_files")]
+const WATCH_TIMEOUT: time::Duration = time::Duration::from_secs(1);
+
+/// Waits for the summary data files to change, either by polling them or, with the `watch_files`
+/// feature, by blocking on file system notifications.
+enum ChangeWaiter {
+    Polling(time::Duration),
+    #[cfg(feature = "watch_files")]
+    Watching {
+        _watcher: notify::RecommendedWatcher,
+        events: Receiver<()>,
+    },
+}
+
+impl ChangeWaiter {
+    #[cfg(not(feature = "watch_files"))]
+    fn new(_data_files: &SummaryDataFiles, poll_interval: time::Duration) -> Self {
+        ChangeWaiter::Polling(poll_interval)
+    }
+
+    /// Watch the unified summary file or the directory with separate summary files. Falls back to
+    /// polling if the platform does not support file system notifications.
+    #[cfg(feature = "watch_files")]
+    fn new(data_files: &SummaryDataFiles, poll_interval: time::Duration) -> Self {
+        use notify::Watcher;
+
+        let (event_snd, events) = crossbeam_channel::unbounded();
+        let path = match data_files {
+            SummaryDataFiles::Unified(path) => path.clone(),
+            SummaryDataFiles::Separate { case_path, .. } => match case_path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => PathBuf::from("."),
+            },
+        };
+
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if event.is_ok() {
+                let _ = event_snd.send(());
+            }
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(&path, notify::RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+
+        match watcher {
+            Ok(watcher) => ChangeWaiter::Watching {
+                _watcher: watcher,
+                events,
+            },
+            Err(err) => {
+                log::warn!(
+                    target: "SummaryFileUpdater::update",
+                    "Failed to watch {:?}, falling back to polling: {}", path, err
+                );
+                ChangeWaiter::Polling(poll_interval)
+            }
+        }
+    }
+
+    /// Wait until the files may have changed. Returns None if termination was requested while
+    /// waiting, otherwise whether a change has been observed.
+    fn wait(&self, term_rcv: &Receiver<bool>) -> Option<bool> {
+        match self {
+            ChangeWaiter::Polling(poll_interval) => match term_rcv.recv_timeout(*poll_interval) {
+                Ok(_) => None,
+                Err(RecvTimeoutError::Timeout) => Some(false),
+                Err(RecvTimeoutError::Disconnected) => {
+                    sleep(*poll_interval);
+                    Some(false)
+                }
+            },
+            #[cfg(feature = "watch_files")]
+            ChangeWaiter::Watching { events, .. } => {
+                crossbeam_channel::select! {
+                    recv(term_rcv) -> _ => return None,
+                    recv(events) -> _ => {},
+                    default(WATCH_TIMEOUT) => return Some(false),
+                }
+                while events.try_recv().is_ok() {}
+                Some(true)
+            }
+        }
+    }
+}
+
+/// SummaryFileReader builds Summary data from file-like sources.
+pub struct SummaryFileReader {
+    smspec_file: Box<dyn RecordSource>,
+    unsmry_file: Box<dyn RecordSource>,
+    data_files: SummaryDataFiles,
+
+    // Path to the case files without the extension.
+    case_path: PathBuf,
+
+    // How many levels of restarts to follow.
+    max_restart_depth: usize,
+
+    // The largest number of elements accepted in a UNSMRY record, NLIST if not set.
+    max_record_elements: Option<usize>,
+
+    // Custom classification of the summary items, shared with the readers of the restart history.
+    classifier: Option<Arc<ItemClassifier>>,
+
+    // How many of the last time iterations to read initially, all of them if not set.
+    tail_steps: Option<usize>,
+
+    // How often the updater checks for new data.
+    poll_interval: time::Duration,
+}
+
+/// FileUpdater updates Summary data from a file-like source.
+pub struct SummaryFileUpdater {
+    unsmry_file: Box<dyn RecordSource>,
+    data_files: SummaryDataFiles,
+
+    n_items: usize,
+    next_ministep: Option<usize>,
+    poll_interval: time::Duration,
+
+    // Whether to watch the files for new data. Compressed files are read once, see `GzFile`.
+    follow: bool,
+}
 
 /// Scan the next two or three UNSMRY records and attempt to extract data for the next time
-/// iteration.
-fn get_next_params<T: ReadRecord>(
+/// iteration. If the expected MINISTEP value is not provided, any value is accepted, which is the
+/// case for the first time iteration of a restarted run. Returns the number of bytes read, the
+/// MINISTEP value and the PARAMS values.
+fn get_next_params<T: ReadRecord + ?Sized>(
     reader: &mut T,
-    step: usize,
+    ministep: Option<usize>,
+    n_items: usize,
+) -> Result<Option<(usize, usize, Params)>> {
+    use EclairError::*;
+
+    macro_rules! unwrap_and_validate {
+        ($record: expr, $field_name: literal, $kind: ident, $valid_len: expr) => {
+            match $record {
+                Some(Record { name, data }) if name == $field_name => {
+                    validate!(data, $field_name, $kind, $valid_len)
+                }
+                _ => {
+                    return Err(MissingRecord($field_name.to_string()));
+                }
+            }
+        };
+    }
+
+    let mut n_bytes_read = 0;
+
+    let (n_bytes, mut record) = reader.read_record()?;
+
+    // This could be a SEQHDR.
+    let read_next = match &record {
+        None => return Ok(None),
+        Some(Record { name, .. }) => {
+            n_bytes_read += n_bytes;
+            name == "SEQHDR"
+        }
+    };
+
+    if read_next {
+        let (n_bytes, next_record) = reader.read_record()?;
+        record = next_record;
+        n_bytes_read += n_bytes;
+    }
+
+    // Next one should be MINISTEP. The wrapped counter inside starts at 0, unless the run has been
+    // restarted. Some simulators also reset it at the start of every non-unified file, which begins
+    // with a SEQHDR.
+    let step_index = unwrap_and_validate!(record, "MINISTEP", Int, 1)[0] as usize;
+    let counter_reset = read_next && step_index == 0;
+
+    match ministep {
+        Some(expected) if expected != step_index && !counter_reset => {
+            return Err(EclairError::InvalidMinistepValue {
+                expected,
+                found: step_index,
+            });
+        }
+        _ => (),
+    }
+
+    let (n_bytes, record) = reader.read_record()?;
+    n_bytes_read += n_bytes;
+
+    // Next is PARAMS with as many values as we have items, in either single or double precision.
+    let params = match record {
+        Some(Record {
+            name,
+            data: data @ RecordData::F64(_),
+        }) if name == "PARAMS" => Params::F64(validate!(data, "PARAMS", F64, n_items)),
+        record => Params::F32(unwrap_and_validate!(record, "PARAMS", F32, n_items)),
+    };
+    Ok(Some((n_bytes_read, step_index, params)))
+}
+
+impl UpdateSummary for SummaryFileUpdater {
+    fn update(&mut self, data_snd: Sender<Params>, term_rcv: Receiver<bool>) -> Result<()> {
+        // Continuously tries to read from the UNSMRY file and sends new values over the provided
+        // channel.
+        if !self.follow {
+            log::info!(
+                target: "SummaryFileUpdater::update",
+                "Compressed summary files are not watched for new data."
+            );
+            return Ok(());
+        }
+        let mut file_pos = self.unsmry_file.seek(SeekFrom::Current(0)).unwrap();
+        let mut last_read_successful = true;
+        let mut modified_time = std::time::SystemTime::now();
+        let mut changed = false;
+        let waiter = ChangeWaiter::new(&self.data_files, self.poll_interval);
+
+        // Size of the files when the last read failed. A partially written record is read again
+        // only once more data has been appended.
+        let mut failed_len = None;
+
+        loop {
+            // First check if we were instructed to stop.
+            if let Ok(_) = term_rcv.try_recv() {
+                log::info!(
+                    target: "SummaryFileUpdater::update",
+                    "Received termination request."
+                );
+                return Ok(());
+            }
+
+            // Try to read from the file if necessary.
+            let new_modified_time = self.data_files.modified()?;
+            let grown = match failed_len {
+                Some(len) => self.data_files.len()? > len,
+                None => true,
+            };
+
+            if grown && (last_read_successful || changed || new_modified_time > modified_time) {
+                modified_time = new_modified_time;
+                let len = self.data_files.len()?;
+                let params =
+                    get_next_params(&mut *self.unsmry_file, self.next_ministep, self.n_items);
+
+                failed_len = None;
+                last_read_successful = match params {
+                    Ok(params) => {
+                        if let Some((n_bytes, ministep, params)) = params {
+                            file_pos += n_bytes as u64;
+                            self.next_ministep = Some(ministep + 1);
+
+                            if data_snd.send(params).is_err() {
+                                log::info!(target: "SummaryFileUpdater::update", "Error while sending params over a channel");
+                                return Ok(());
+                            }
+                            true
+                        } else {
+                            false
+                        }
+                    }
+                    Err(_) => {
+                        if self.unsmry_file.seek(SeekFrom::Start(file_pos)).is_err() {
+                            log::warn!(target: "SummaryFileUpdater::update", "Failed to rewind the summary data file after an incomplete read");
+                        }
+                        failed_len = Some(len);
+                        false
+                    }
+                };
+            }
+
+            // More data may be available right away after a successful read.
+            changed = false;
+            if !last_read_successful {
+                match waiter.wait(&term_rcv) {
+                    Some(files_changed) => changed = files_changed,
+                    None => {
+                        log::info!(
+                            target: "SummaryFileUpdater::update",
+                            "Received termination request."
+                        );
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl SummaryFileReader {
+    /// Create a reader for the case at the given path. The history of restarted runs is followed
+    /// up to `DEFAULT_MAX_RESTART_DEPTH` levels deep. With the `gzip` feature, gzipped files such
+    /// as `SPE10.UNSMRY.gz` are decompressed on the fly. They are expected to be complete and are
+    /// not watched for new data, as their reading can't resume in the middle of a record.
+    pub fn from_path<P>(input_path: P) -> Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        // If there is no stem, bail early.
+        let input_path = input_path.as_ref();
+
+        // Gzipped files are opened transparently, so we look past their extension.
+        #[cfg(feature = "gzip")]
+        let input_path = &if input_path.extension() == Some(std::ffi::OsStr::new("gz")) {
+            input_path.with_extension("")
+        } else {
+            input_path.to_owned()
+        };
+
+        let stem = input_path.file_stem();
+        if stem.is_none() || stem.unwrap().to_str().is_none() {
+            return Err(EclairError::InvalidFilePath(
+                input_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        // We allow binary (SMSPEC, UNSMRY and Snnnn) and formatted (FSMSPEC, FUNSMRY and Annnn)
+        // extensions or no extension at all, in which case we fall back to formatted files only if
+        // the binary ones are absent.
+        let formatted = match input_path.extension().map(|ext| ext.to_str()) {
+            None => {
+                !with_gz_fallback(input_path.with_extension("SMSPEC")).exists()
+                    && with_gz_fallback(input_path.with_extension("FSMSPEC")).exists()
+            }
+            Some(Some("SMSPEC")) | Some(Some("UNSMRY")) => false,
+            Some(Some("FSMSPEC")) | Some(Some("FUNSMRY")) => true,
+            Some(Some(ext)) if is_separate_summary_ext(ext, 'S') => false,
+            Some(Some(ext)) if is_separate_summary_ext(ext, 'A') => true,
+            _ => {
+                return Err(EclairError::InvalidFilePath(
+                    input_path.to_string_lossy().to_string(),
+                ))
+            }
+        };
+
+        let (smspec_ext, unsmry_ext, separate_letter) = if formatted {
+            ("FSMSPEC", "FUNSMRY", 'A')
+        } else {
+            ("SMSPEC", "UNSMRY", 'S')
+        };
+
+        let missing_file = |path: &Path| {
+            Err(EclairError::MissingSummaryFile(
+                path.to_string_lossy().to_string(),
+            ))
+        };
+
+        let smspec_path = with_gz_fallback(input_path.with_extension(smspec_ext));
+        if !smspec_path.exists() {
+            return missing_file(&smspec_path);
+        }
+        let smspec_file = open_records(&smspec_path, formatted)?;
+
+        // Fall back to the non-unified summary files if there is no unified one.
+        let unsmry_path = with_gz_fallback(input_path.with_extension(unsmry_ext));
+        let case_path = input_path.with_extension("");
+        let separate_files = if unsmry_path.exists() {
+            Vec::new()
+        } else {
+            list_separate_files(&case_path, separate_letter)?
+        };
+
+        if separate_files.is_empty() && !unsmry_path.exists() {
+            return missing_file(&unsmry_path);
+        }
+
+        let (unsmry_file, data_files) = if separate_files.is_empty() {
+            (
+                open_records(&unsmry_path, formatted)?,
+                SummaryDataFiles::Unified(unsmry_path),
+            )
+        } else {
+            let stream = SeparateFiles::new(&case_path, separate_letter)?;
+            let unsmry_file: Box<dyn RecordSource> = if formatted {
+                Box::new(FormattedReader::new(BufReader::new(stream)))
+            } else {
+                Box::new(RecordReader::new(stream))
+            };
+            (
+                unsmry_file,
+                SummaryDataFiles::Separate {
+                    case_path,
+                    letter: separate_letter,
+                },
+            )
+        };
+
+        Ok(Self {
+            smspec_file,
+            unsmry_file,
+            data_files,
+            case_path: input_path.with_extension(""),
+            max_restart_depth: DEFAULT_MAX_RESTART_DEPTH,
+            max_record_elements: None,
+            classifier: None,
+            tail_steps: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        })
+    }
+
+    /// Set the largest number of elements accepted in a UNSMRY record. Larger records are
+    /// rejected without reading them, which keeps a garbled header of a partially written record
+    /// from allocating a huge buffer. Defaults to the number of summary items (NLIST), the length of
+    /// the PARAMS records.
+    pub fn with_max_record_elements(self, max_record_elements: usize) -> Self {
+        Self {
+            max_record_elements: Some(max_record_elements),
+            ..self
+        }
+    }
+
+    /// Classify the summary items with the function first. It takes the keyword, the well or group
+    /// name and the NUMS value of an item, and the items it returns None for are classified as
+    /// usual. This allows recognizing site-specific keywords.
+    pub fn with_classifier<F>(self, classifier: F) -> Self
+    where
+        F: Fn(&str, &str, i32) -> Option<ItemQualifier> + Send + Sync + 'static,
+    {
+        Self {
+            classifier: Some(Arc::new(classifier)),
+            ..self
+        }
+    }
+
+    /// Set how often the updater checks the files for new data. Defaults to
+    /// `DEFAULT_POLL_INTERVAL`. With the `watch_files` feature, the files are checked as soon as
+    /// they change and the interval applies only if they cannot be watched.
+    pub fn with_poll_interval(self, poll_interval: time::Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Same as `from_path`, but a unified binary UNSMRY file is memory-mapped with `MmapReader`
+    /// instead of being read through a buffer. Formatted and non-unified summary files are read as
+    /// usual.
+    ///
+    /// # Safety
+    ///
+    /// Same as for `MmapReader::new`: the UNSMRY file must not be truncated or modified in place
+    /// while the summary is being read or updated, only appended to.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn from_path_mmap<P>(input_path: P) -> Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let reader = Self::from_path(input_path)?;
+        let unsmry_file: Box<dyn RecordSource> = match &reader.data_files {
+            SummaryDataFiles::Unified(path)
+                if path.extension().and_then(|ext| ext.to_str()) == Some("UNSMRY") =>
+            {
+                Box::new(MmapReader::new(File::open(path)?)?)
+            }
+            _ => return Ok(reader),
+        };
+
+        Ok(Self {
+            unsmry_file,
+            ..reader
+        })
+    }
+
+    /// Same as `from_path`, but the history of the run this one was restarted from is prepended to
+    /// the summary data. Restarts of restarted runs are followed up to `max_depth` levels deep.
+    pub fn from_path_with_restarts<P>(input_path: P, max_depth: usize) -> Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        Ok(Self {
+            max_restart_depth: max_depth,
+            ..Self::from_path(input_path)?
+        })
+    }
+
+    /// Same as `from_path`, but only the last `n_steps` time iterations are read initially. The
+    /// updater follows the new ones as usual. The history of restarted runs is not read.
+    ///
+    /// The earlier time iterations of a unified binary UNSMRY file are skipped without reading
+    /// them. The size of the first time iteration gives an estimate of where the last `n_steps`
+    /// begin, and the reading starts at the first SEQHDR or MINISTEP record after a position a
+    /// couple of time iterations earlier. Not every time iteration carries a SEQHDR, so the
+    /// estimate is only approximate and the extra time iterations read are dropped. MINISTEP
+    /// values are validated from the first time iteration read on. Other summary files are read in
+    /// full.
+    pub fn from_path_tail<P>(input_path: P, n_steps: usize) -> Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        Ok(Self {
+            max_restart_depth: 0,
+            tail_steps: Some(n_steps),
+            ..Self::from_path(input_path)?
+        })
+    }
+
+    /// Move the UNSMRY file close to the start of the last `n_steps` time iterations, see
+    /// `from_path_tail`. Returns the new position.
+    fn seek_tail(&mut self, n_steps: usize, n_items: usize, unsmry_size: u64) -> Result<u64> {
+        let path = match &self.data_files {
+            SummaryDataFiles::Unified(path)
+                if path.extension().and_then(|ext| ext.to_str()) == Some("UNSMRY") =>
+            {
+                path.clone()
+            }
+            _ => return Ok(0),
+        };
+
+        // The first time iteration always starts with a SEQHDR, so it is at least as large as any
+        // other. A file without a complete one is read as usual.
+        let step_bytes = match get_next_params(&mut *self.unsmry_file, None, n_items) {
+            Ok(Some((n_bytes, ..))) => n_bytes as u64,
+            _ => return Ok(self.unsmry_file.seek(SeekFrom::Start(0))?),
+        };
+
+        // One more time iteration may be partially written and another one is cut by the estimate.
+        let start = unsmry_size.saturating_sub((n_steps as u64 + 2) * step_bytes);
+        let pos = if start == 0 {
+            0
+        } else {
+            find_step_start(&path, start, step_bytes + 24)?.unwrap_or(0)
+        };
+        Ok(self.unsmry_file.seek(SeekFrom::Start(pos))?)
+    }
+
+    /// Whether no other time iteration starts after the one at `pos` in a binary UNSMRY file of
+    /// the given size. Other files are not checked.
+    fn is_last_step(&self, pos: u64, n_items: usize, unsmry_size: u64) -> Result<bool> {
+        // The remaining bytes are searched in windows overlapping by a header.
+        const WINDOW_BYTES: u64 = 1 << 20;
+
+        let path = match &self.data_files {
+            SummaryDataFiles::Unified(path)
+                if path.extension().and_then(|ext| ext.to_str()) == Some("UNSMRY")
+                    && unsmry_size != u64::MAX =>
+            {
+                path
+            }
+            _ => return Ok(false),
+        };
+
+        // The next time iteration starts after at least the MINISTEP and PARAMS records of this one.
+        let mut start = pos + 36 + 32 + 4 * n_items as u64;
+        while start < unsmry_size {
+            if find_step_start(path, start, WINDOW_BYTES + 24)?.is_some() {
+                return Ok(false);
+            }
+            start += WINDOW_BYTES;
+        }
+        Ok(true)
+    }
+
+    /// Read the summary data of the run this one was restarted from, including its own history.
+    /// Paths of the runs visited so far are used to detect cycles.
+    fn read_restart_history(
+        &self,
+        restart_case: &str,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<Summary> {
+        // The restart case is located relative to the directory of the current one.
+        let dir = match self.case_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let restart_path = dir.join(restart_case);
+
+        let mut reader = Self::from_path_with_restarts(&restart_path, self.max_restart_depth - 1)?;
+        reader.classifier = self.classifier.clone();
+
+        let canonical_path = reader.canonical_case_path()?;
+        if !visited.insert(canonical_path) {
+            return Err(EclairError::RestartCycle(
+                restart_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        Ok(reader.read(visited)?.0)
+    }
+
+    /// Case path that is unique for a given case, used to detect restart cycles.
+    fn canonical_case_path(&self) -> Result<PathBuf> {
+        let dir = match self.case_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        Ok(std::fs::canonicalize(dir)?.join(self.case_path.file_name().unwrap_or_default()))
+    }
+}
+
+/// Build an empty Summary from the SMSPEC records.
+fn read_smspec<T: ReadRecord + ?Sized>(
+    smspec_file: &mut T,
+    classifier: Option<&ItemClassifier>,
+) -> Result<Summary> {
+    // First build the SmspecRecords object from the Smspec source.
+    let mut smspec_records = SmspecRecords::default();
+
+    while let (_, Some(record)) = smspec_file.read_record()? {
+        if !smspec_records.add(record)? {
+            break;
+        }
+    }
+
+    Summary::try_from_with_classifier(smspec_records, classifier)
+}
+
+/// ChunkedSummaryReader builds Summary data from in-memory SMSPEC data and UNSMRY data that arrives
+/// in chunks, e.g. from a network stream or a file upload. Incomplete records at the end of a chunk
+/// are buffered until the rest of them arrives.
+pub struct ChunkedSummaryReader {
+    summary: Summary,
+    buffer: Vec<u8>,
+    next_ministep: Option<usize>,
+    progress: Option<(usize, ProgressCallback)>,
+}
+
+type ProgressCallback = Box<dyn FnMut(usize)>;
+
+impl ChunkedSummaryReader {
+    /// Create a reader from the contents of an SMSPEC file.
+    pub fn from_smspec_bytes(smspec: &[u8]) -> Result<Self> {
+        Ok(Self {
+            summary: read_smspec(&mut RecordReader::new(Cursor::new(smspec)), None)?,
+            buffer: Vec::new(),
+            next_ministep: None,
+            progress: None,
+        })
+    }
+
+    /// Call the callback with the total number of time iterations every `n_steps` iterations.
+    pub fn with_progress_callback<F>(mut self, n_steps: usize, callback: F) -> Self
+    where
+        F: FnMut(usize) + 'static,
+    {
+        self.progress = Some((n_steps.max(1), Box::new(callback)));
+        self
+    }
+
+    /// Ids of all summary items, sorted.
+    pub fn item_ids(&self) -> Vec<&ItemId> {
+        let mut ids: Vec<&ItemId> = self.summary.item_ids.keys().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub fn summary(&self) -> &Summary {
+        &self.summary
+    }
+
+    pub fn into_summary(self) -> Summary {
+        self.summary
+    }
+
+    /// Consume the next chunk of the UNSMRY file. Returns the number of time iterations added.
+    pub fn push_unsmry_chunk(&mut self, chunk: &[u8]) -> Result<usize> {
+        self.buffer.extend_from_slice(chunk);
+
+        // Only parse the data up to the end of the last complete PARAMS record. A record cut short
+        // by the end of the buffer waits for the next chunk, any other error is for good.
+        let mut scanner = RecordReader::new(Cursor::new(&self.buffer));
+        let mut scanned = 0;
+        let mut complete = 0;
+        loop {
+            match scanner.scan_record() {
+                Ok((n_bytes, Some(meta))) => {
+                    scanned += n_bytes;
+                    if meta.name == "PARAMS" {
+                        complete = scanned;
+                    }
+                }
+                Ok((_, None)) => break,
+                Err(e) if e.is_truncation() => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let n_items = self.summary.items.len();
+        let mut reader = RecordReader::new(Cursor::new(&self.buffer[..complete]));
+        let mut n_steps = 0;
+        while let Some((_, ministep, params)) =
+            get_next_params(&mut reader, self.next_ministep, n_items)?
+        {
+            self.summary.append_params(params)?;
+            self.next_ministep = Some(ministep + 1);
+            n_steps += 1;
+
+            if let Some((every, callback)) = &mut self.progress {
+                let total = self.summary.n_steps();
+                if total % *every == 0 {
+                    callback(total);
+                }
+            }
+        }
+
+        self.buffer.drain(..complete);
+        Ok(n_steps)
+    }
+}
+
+/// Find the first SEQHDR or MINISTEP record header in `len` bytes of a binary UNSMRY file starting
+/// at `start`. Returns the position of the header.
+fn find_step_start(path: &Path, start: u64, len: u64) -> Result<Option<u64>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut window = Vec::new();
+    file.take(len).read_to_end(&mut window)?;
+
+    // The header starts with the 16-byte head marker in either byte order.
+    let offset = window.windows(12).position(|bytes| {
+        matches!(bytes[..4], [0, 0, 0, 16] | [16, 0, 0, 0])
+            && matches!(&bytes[4..], b"SEQHDR  " | b"MINISTEP")
+    });
+    Ok(offset.map(|offset| start + offset as u64))
+}
+
+/// Positions of the time iterations in a binary UNSMRY file, found by seeking past the record
+/// bodies instead of decoding them. The PARAMS of a time iteration are only read when asked for,
+/// which makes indexing a large file cheap.
+pub struct UnsmryIndex {
+    reader: RecordReader<BufReader<File>>,
+    ministep_pos: Vec<u64>,
     n_items: usize,
-) -> Result<Option<(usize, Vec<f32>)>> {
-    use EclairError::*;
+}
+
+impl UnsmryIndex {
+    /// Index the time iterations of the UNSMRY file. A time iteration that is still being written
+    /// is left out.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        use EclairError::*;
+
+        let mut reader = RecordReader::new(BufReader::new(File::open(path)?));
+        let mut ministep_pos = Vec::new();
+        let mut n_items = None;
+
+        let mut pos = 0;
+        let mut last_ministep = None;
+        loop {
+            let (n_bytes, meta) = match reader.skip_record() {
+                Ok((n_bytes, Some(meta))) => (n_bytes, meta),
+                Ok((_, None)) | Err(TruncatedRecord { .. }) => break,
+                Err(e) => return Err(e),
+            };
+
+            if meta.name == "MINISTEP" {
+                last_ministep = Some(pos);
+            } else if meta.name == "PARAMS" {
+                let ministep = last_ministep
+                    .take()
+                    .ok_or_else(|| MissingRecord("MINISTEP".to_string()))?;
+
+                match n_items {
+                    Some(expected) if expected != meta.n_elements => {
+                        return Err(UnexpectedRecordDataLength {
+                            name: "PARAMS".to_string(),
+                            expected,
+                            found: meta.n_elements,
+                        });
+                    }
+                    _ => n_items = Some(meta.n_elements),
+                }
+                ministep_pos.push(ministep);
+            }
+            pos += n_bytes as u64;
+        }
+
+        Ok(Self {
+            reader,
+            ministep_pos,
+            n_items: n_items.unwrap_or(0),
+        })
+    }
+
+    /// Number of the indexed time iterations.
+    pub fn n_steps(&self) -> usize {
+        self.ministep_pos.len()
+    }
+
+    /// Number of summary items in each time iteration, 0 if the file holds none.
+    pub fn n_items(&self) -> usize {
+        self.n_items
+    }
+
+    /// Read the values of all summary items for the time iteration. Returns None past the last
+    /// indexed one.
+    pub fn read_params(&mut self, step: usize) -> Result<Option<Params>> {
+        let pos = match self.ministep_pos.get(step) {
+            Some(&pos) => pos,
+            None => return Ok(None),
+        };
+
+        self.reader.seek(SeekFrom::Start(pos))?;
+        Ok(get_next_params(&mut self.reader, None, self.n_items)?.map(|(_, _, params)| params))
+    }
+}
+
+impl InitializeSummary for SummaryFileReader {
+    type Updater = SummaryFileUpdater;
+
+    fn init(self) -> Result<(Summary, Self::Updater)> {
+        let mut visited = HashSet::new();
+        visited.insert(self.canonical_case_path()?);
+        self.read(&mut visited)
+    }
+}
+
+impl SummaryFileReader {
+    fn read(mut self, visited: &mut HashSet<PathBuf>) -> Result<(Summary, SummaryFileUpdater)> {
+        use EclairError::*;
+
+        let mut summary = read_smspec(&mut *self.smspec_file, self.classifier.as_deref())?;
+
+        let n_items = summary.items.len();
+        let mut next_ministep = None;
+
+        // SEQHDR and MINISTEP records hold a single element.
+        self.unsmry_file
+            .set_max_elements(Some(self.max_record_elements.unwrap_or(n_items).max(1)));
+
+        // Get the current size and don't read data past it (strictly speaking, we can go past by a
+        // fraction of a single UNSMRY triplet length). The size of compressed files is unknown, so
+        // we read them to the end.
+        let unsmry_size = self.unsmry_file.seek(SeekFrom::End(0)).unwrap_or(u64::MAX);
+        let mut unsmry_pos = self.unsmry_file.seek(SeekFrom::Start(0)).unwrap();
+        if let (Some(n_steps), true) = (self.tail_steps, unsmry_size != u64::MAX) {
+            unsmry_pos = self.seek_tail(n_steps, n_items, unsmry_size)?;
+        }
+
+        // Time iterations are appended in batches of about `BATCH_VALUES` values. When reading the
+        // tail, they are all kept until the end to drop the extra ones.
+        const BATCH_VALUES: usize = 1 << 24;
+        // In tail mode the batch is a ring of the last time iterations read so far, which is
+        // appended at the end.
+        let batch_steps = match self.tail_steps {
+            Some(_) => usize::MAX,
+            None => (BATCH_VALUES / n_items.max(1)).max(1),
+        };
+        let mut batch = VecDeque::new();
+
+        // We store the current file position before the read and try to read as many timestep data
+        // as we can.
+        loop {
+            if batch.len() >= batch_steps {
+                summary.append_params_batch(Vec::from(std::mem::take(&mut batch)))?;
+            }
+
+            let params = get_next_params(&mut *self.unsmry_file, next_ministep, n_items);
+
+            match params {
+                Ok(params) => {
+                    match params {
+                        None => break,
+                        Some((n_bytes, ministep, params)) => {
+                            // Time iterations usually take the same number of bytes, so the first
+                            // one tells how many there are. The size of compressed files is unknown.
+                            if next_ministep.is_none() && unsmry_size != u64::MAX {
+                                let remaining = unsmry_size.saturating_sub(unsmry_pos);
+                                let n_steps = remaining.div_ceil(n_bytes as u64) as usize;
+                                summary
+                                    .reserve_steps(self.tail_steps.unwrap_or(n_steps).min(n_steps));
+                            }
+
+                            batch.push_back(params);
+                            if self.tail_steps.is_some_and(|n_steps| batch.len() > n_steps) {
+                                batch.pop_front();
+                            }
+                            next_ministep = Some(ministep + 1);
+                            unsmry_pos += n_bytes as u64;
+                            // In case we're reading from a file that's still being written to, we stop here
+                            // and continue reading during subsequent updates.
+                            if unsmry_pos >= unsmry_size {
+                                break;
+                            }
+                        }
+                    }
+                }
+                // The last time iteration may still be being written, or be preallocated space
+                // that is zero-filled for now. The updater picks it up once it is complete. An
+                // error followed by more time iterations means the file is corrupt.
+                Err(e)
+                    if e.is_truncation()
+                        || self.is_last_step(unsmry_pos, n_items, unsmry_size)? =>
+                {
+                    if self.unsmry_file.seek(SeekFrom::Start(unsmry_pos)).is_err() {
+                        log::warn!(target: "Reading summary", "Failed to rewind the summary data file after an incomplete read");
+                    }
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        summary.append_params_batch(Vec::from(batch))?;
+
+        if self.max_restart_depth > 0 {
+            if let Some(restart_case) = &summary.restart_case {
+                match self.read_restart_history(restart_case, visited) {
+                    Ok(history) => summary.splice_restart(history),
+                    // A missing history is not fatal, we still have the data of the run itself.
+                    Err(MissingSummaryFile(file)) => {
+                        log::warn!(target: "Reading restart history", "Restart file {} does not exist, skipping the history.", file);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok((
+            summary,
+            SummaryFileUpdater {
+                unsmry_file: self.unsmry_file,
+                data_files: self.data_files,
+                n_items,
+                next_ministep,
+                poll_interval: self.poll_interval,
+                follow: unsmry_size != u64::MAX,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::WriteRecord;
+
+    /// Read the summary data for a case, ignoring the updater.
+    fn read_summary(path: &Path) -> Summary {
+        SummaryFileReader::from_path(path)
+            .unwrap()
+            .init()
+            .unwrap()
+            .0
+    }
+
+    fn assert_same_data(lhs: &Summary, rhs: &Summary) {
+        assert_eq!(lhs.timestamps, rhs.timestamps);
+        assert_eq!(lhs.n_items(), rhs.n_items());
+        for (id, &index) in &lhs.item_ids {
+            assert_eq!(lhs.item_values(index), rhs.item_values(rhs.item_ids[id]));
+        }
+    }
+
+    /// Build minimal SMSPEC records for the given keywords, well/group names and numbers.
+    fn smspec_records(items: &[(&str, &str, i32)]) -> SmspecRecords {
+        use RecordData::*;
+
+        let chars = |values: Vec<&str>| Chars(values.into_iter().map(FlexString::from).collect());
+
+        let mut records = HashMap::new();
+        records.insert(
+            "DIMENS",
+            Some(Int(vec![items.len() as i32, 10, 10, 3, 0, -1])),
+        );
+        records.insert("STARTDAT", Some(Int(vec![1, 3, 2005])));
+        records.insert(
+            "KEYWORDS",
+            Some(chars(items.iter().map(|item| item.0).collect())),
+        );
+        records.insert(
+            "WGNAMES",
+            Some(chars(items.iter().map(|item| item.1).collect())),
+        );
+        records.insert("NUMS", Some(Int(items.iter().map(|item| item.2).collect())));
+        records.insert("UNITS", Some(chars(vec![""; items.len()])));
+        SmspecRecords::new(records)
+    }
+
+    #[test]
+    fn classify_vendor_keywords() {
+        let items = [
+            ("TIME", "", 0),
+            ("XWELQ", "PROD", 0),
+            ("XFOO", "", 0),
+            ("WBHP", "PROD", 0),
+        ];
+        let classifier = |name: &str, wg_name: &str, _| match name {
+            "XWELQ" => Some(ItemQualifier::Well {
+                wg_name: FlexString::from(wg_name),
+            }),
+            _ => None,
+        };
+
+        let summary =
+            Summary::try_from_with_classifier(smspec_records(&items), Some(&classifier)).unwrap();
+        let xwelq = ItemId {
+            name: FlexString::from("XWELQ"),
+            qualifier: ItemQualifier::Well {
+                wg_name: FlexString::from("PROD"),
+            },
+        };
+        assert_eq!(summary.item_ids.get(&xwelq), Some(&1));
+        assert!(matches!(
+            summary.unrecognized()[..],
+            [ItemId { ref name, .. }] if name == "XFOO"
+        ));
+
+        // The default classification is unchanged.
+        let summary = Summary::try_from(smspec_records(&items)).unwrap();
+        assert_eq!(summary.unrecognized().len(), 2);
+        assert_eq!(summary.item_ids.get(&xwelq), None);
+    }
+
+    #[test]
+    fn smspec_from_records() {
+        let mut reader = RecordReader::new(File::open("../assets/SPE10.SMSPEC").unwrap());
+        let records = std::iter::from_fn(|| reader.read_record().unwrap().1);
+        let classifier = |name: &str, _: &str, index| match name {
+            "BPR" => Some(ItemQualifier::Region {
+                wg_name: None,
+                index,
+            }),
+            _ => None,
+        };
+        let summary = Summary::try_from_with_classifier(
+            SmspecRecords::from_records(records).unwrap(),
+            Some(&classifier),
+        )
+        .unwrap();
+
+        let expected = read_summary(Path::new("../assets/SPE10"));
+        assert_eq!(summary.n_items(), expected.n_items());
+        let bpr = ItemId {
+            name: FlexString::from("BPR"),
+            qualifier: ItemQualifier::Region {
+                wg_name: None,
+                index: 120910,
+            },
+        };
+        assert!(summary.item_ids.contains_key(&bpr));
+    }
+
+    #[test]
+    fn inconsistent_nlist() {
+        let items = [("TIME", "", 0), ("FOPR", "", 0)];
+        let mut records = smspec_records(&items);
+        records
+            .records
+            .insert("DIMENS", Some(RecordData::Int(vec![3, 10, 10, 3, 0, -1])));
+        assert!(matches!(
+            Summary::try_from(records),
+            Err(EclairError::InconsistentNlist {
+                dimens: 3,
+                found: 2
+            })
+        ));
+
+        // A single record of the wrong length is reported as such.
+        let mut records = smspec_records(&items);
+        records
+            .records
+            .insert("UNITS", Some(RecordData::Chars(vec![FlexString::new()])));
+        assert!(matches!(
+            Summary::try_from(records),
+            Err(EclairError::UnexpectedRecordDataLength { name, expected: 2, found: 1 }) if name == "UNITS"
+        ));
+    }
+
+    #[test]
+    fn time_from_years() {
+        let records = smspec_records(&[("YEARS", UNKNOWN_WG_NAME, 0), ("FOPR", "", 0)]);
+        let mut summary = Summary::try_from(records).unwrap();
+
+        summary.append(vec![2.0, 100.0]).unwrap();
+        let start = NaiveDate::from_ymd_opt(2005, 3, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .unwrap();
+        assert_eq!(
+            summary.timestamps,
+            vec![start.and_utc().timestamp() + (2.0 * 365.25 * 86400.0) as i64]
+        );
+
+        // Appending the wrong number of values is an error.
+        assert!(summary.append(vec![3.0]).is_err());
+        assert_eq!(summary.n_steps(), 1);
+    }
+
+    #[test]
+    fn missing_time_item() {
+        let records = smspec_records(&[("FOPR", "", 0), ("WBHP", "PROD", 0)]);
+        assert!(matches!(
+            Summary::try_from(records),
+            Err(EclairError::MissingTimeItem)
+        ));
+    }
+
+    #[test]
+    fn segment_items() {
+        let items = [
+            ("TIME", "", 0),
+            ("SOFR", "PROD", 3),
+            ("SPR", "PROD", 0),
+            ("STEPTYPE", UNKNOWN_WG_NAME, 0),
+        ];
+        let summary = Summary::try_from(smspec_records(&items)).unwrap();
+
+        let sofr = ItemId {
+            name: FlexString::from("SOFR"),
+            qualifier: ItemQualifier::Segment {
+                wg_name: FlexString::from("PROD"),
+                index: 3,
+            },
+        };
+        assert_eq!(summary.item_ids.get(&sofr), Some(&1));
+        assert_eq!(sofr.to_string(), "SOFR:PROD:3");
+        assert_eq!(sofr.qualifier.to_string(), "Segment #3 @ PROD");
+
+        // Segment items need the well name and the segment number, which other S* keywords lack.
+        let unrecognized: Vec<&str> = summary
+            .unrecognized()
+            .into_iter()
+            .map(|id| id.name.as_str())
+            .collect();
+        assert_eq!(unrecognized, ["SPR", "STEPTYPE"]);
+    }
+
+    #[test]
+    fn unrecognized_items() {
+        let items = [
+            ("TIME", "", 0),
+            ("XOPR", "PROD", 2),
+            ("WBHP", "PROD", 0),
+            ("XOPR", "PROD", 1),
+            ("ZZZ", "", 0),
+        ];
+        let summary = Summary::try_from(smspec_records(&items)).unwrap();
+        let unrecognized: Vec<String> = summary
+            .unrecognized()
+            .into_iter()
+            .map(|id| id.to_string())
+            .collect();
+        assert_eq!(unrecognized, ["XOPR:PROD:1", "XOPR:PROD:2", "ZZZ::0"]);
+    }
+
+    #[test]
+    fn node_items() {
+        let items = [
+            ("TIME", "", 0),
+            ("NPR", "NODE1", 0),
+            ("GPR", "GRP1", 0),
+            ("NEWTON", "", 0),
+        ];
+        let summary = Summary::try_from(smspec_records(&items)).unwrap();
+        let qualifiers: Vec<_> = items
+            .iter()
+            .map(|&(name, wg_name, index)| {
+                ItemId::new(FlexString::from(name), FlexString::from(wg_name), index).qualifier
+            })
+            .collect();
+
+        let node = ItemQualifier::Node {
+            wg_name: FlexString::from("NODE1"),
+        };
+        assert_eq!(qualifiers[1], node);
+        assert_eq!(node.to_string(), "Node NODE1");
+        assert!(matches!(qualifiers[2], ItemQualifier::Group { .. }));
+        assert_eq!(qualifiers[3], ItemQualifier::Performance);
+
+        let npr = ItemId {
+            name: FlexString::from("NPR"),
+            qualifier: node,
+        };
+        assert_eq!(summary.item_ids.get(&npr), Some(&1));
+        assert_eq!(npr.to_string(), "NPR:NODE1");
+    }
+
+    #[test]
+    fn block_ijk() {
+        let summary = Summary::open("../assets/SPE10").unwrap();
+        assert_eq!(summary.dims, [100, 100, 30]);
+
+        let bpr = ItemId {
+            name: FlexString::from("BPR"),
+            qualifier: ItemQualifier::Block { index: 120910 },
+        };
+        assert_eq!(bpr.qualifier.block_ijk(summary.dims), Some((10, 10, 13)));
+        assert_eq!(summary.display_item(&bpr), "Block (10, 10, 13)");
+
+        let cell = |index| ItemQualifier::Completion {
+            wg_name: FlexString::from("PROD"),
+            index,
+        };
+        assert_eq!(cell(1).block_ijk([10, 5, 2]), Some((1, 1, 1)));
+        assert_eq!(cell(23).block_ijk([10, 5, 2]), Some((3, 3, 1)));
+        assert_eq!(cell(100).block_ijk([10, 5, 2]), Some((10, 5, 2)));
+
+        // Cells outside the grid have no coordinates.
+        assert_eq!(cell(0).block_ijk([10, 5, 2]), None);
+        assert_eq!(cell(101).block_ijk([10, 5, 2]), None);
+        assert_eq!(cell(1).block_ijk([0, 0, 0]), None);
+        assert_eq!(ItemQualifier::Field.block_ijk([10, 5, 2]), None);
+
+        let outside = ItemId {
+            name: FlexString::from("COPR"),
+            qualifier: cell(300_001),
+        };
+        assert_eq!(summary.display_item(&outside), "Completion #300001 @ PROD");
+    }
+
+    #[test]
+    fn lgr_items() {
+        let summary = read_summary(Path::new("../assets/SPE10_LGR"));
+        let spe_10 = read_summary(Path::new("../assets/SPE10"));
+        let lgr = || FlexString::from("LGR1");
+
+        let lbpr = ItemId {
+            name: FlexString::from("LBPR"),
+            qualifier: ItemQualifier::LocalBlock {
+                lgr: lgr(),
+                i: 2,
+                j: 2,
+                k: 1,
+            },
+        };
+        let lwbhp = ItemId {
+            name: FlexString::from("LWBHP"),
+            qualifier: ItemQualifier::LocalWell {
+                lgr: lgr(),
+                wg_name: FlexString::from("P1"),
+            },
+        };
+        let lcopr = ItemId {
+            name: FlexString::from("LCOPR"),
+            qualifier: ItemQualifier::LocalCompletion {
+                lgr: lgr(),
+                wg_name: FlexString::from("P1"),
+                i: 1,
+                j: 1,
+                k: 1,
+            },
+        };
+        assert_eq!(summary.item_ids.get(&lbpr), Some(&3));
+        assert_eq!(summary.item_ids.get(&lwbhp), Some(&6));
+        assert_eq!(summary.item_ids.get(&lcopr), Some(&11));
+        assert!(summary.unrecognized().is_empty());
+
+        // The global items are unaffected.
+        let wbhp = ItemId {
+            name: FlexString::from("WBHP"),
+            qualifier: ItemQualifier::Well {
+                wg_name: FlexString::from("P2"),
+            },
+        };
+        assert_eq!(summary.item_ids.get(&wbhp), Some(&7));
+        assert_eq!(summary.item_values(3), spe_10.item_values(3));
+
+        assert_eq!(lbpr.to_string(), "LBPR:LGR1:2,2,1");
+        assert_eq!(lwbhp.to_string(), "LWBHP:LGR1:P1");
+        assert_eq!(lcopr.to_string(), "LCOPR:LGR1:P1:1,1,1");
+        assert_eq!(
+            summary.display_item(&lcopr),
+            "Completion (1, 1, 1) @ P1 in LGR1"
+        );
+        assert!(crate::query::matches_pattern(&lcopr, "LC*:LGR?:P1:1,1,1"));
+        assert!(crate::query::matches_pattern(&lwbhp, "LWBHP:LGR1"));
+        assert!(!crate::query::matches_pattern(&lbpr, "LBPR:LGR1:1,1,1"));
+
+        // The local grid records are written back.
+        let dir = tempfile::tempdir().unwrap();
+        summary.save_to_files(dir.path().join("LGR")).unwrap();
+        let saved = read_summary(&dir.path().join("LGR"));
+        assert_eq!(saved.item_ids, summary.item_ids);
+        assert_same_data(&saved, &summary);
+    }
+
+    #[test]
+    fn item_coords() {
+        let items = [("TIME", "", 0), ("WBHP", "PROD", 0), ("COPR", "PROD", 7)];
+        let copr = ItemId {
+            name: FlexString::from("COPR"),
+            qualifier: ItemQualifier::Completion {
+                wg_name: FlexString::from("PROD"),
+                index: 7,
+            },
+        };
+
+        let summary = Summary::try_from(smspec_records(&items)).unwrap();
+        assert!(summary.coords.is_empty());
+        assert_eq!(summary.item_coords(&copr), None);
+
+        let mut records = smspec_records(&items);
+        records
+            .records
+            .insert("XCOORD", Some(RecordData::F32(vec![0.0, 10.0, 12.5])));
+        records
+            .records
+            .insert("YCOORD", Some(RecordData::F32(vec![0.0, 20.0, 22.5])));
+        let summary = Summary::try_from(records).unwrap();
+        assert_eq!(summary.item_coords(&copr), Some((12.5, 22.5)));
+
+        // Coordinates of the wrong length are ignored.
+        let mut records = smspec_records(&items);
+        records
+            .records
+            .insert("XCOORD", Some(RecordData::F32(vec![0.0, 10.0])));
+        records
+            .records
+            .insert("YCOORD", Some(RecordData::F32(vec![0.0, 20.0])));
+        let summary = Summary::try_from(records).unwrap();
+        assert!(summary.coords.is_empty());
+    }
+
+    #[test]
+    fn measurements() {
+        let summary = Summary::open("../assets/SPE10").unwrap();
+        assert_eq!(summary.measurements.len(), summary.n_items());
+        assert!(summary
+            .measurements
+            .iter()
+            .all(|measurement| measurement == "O:Simulation_Time"));
+
+        // Strings are padded to their width before joining them.
+        let items = [("TIME", "", 0), ("WOPR", "PROD", 0)];
+        let chunks = ["O:Time", "", "", "O:Oil_Pr", "oduction", "_Rate"];
+        let mut records = smspec_records(&items);
+        records.records.insert(
+            "MEASRMNT",
+            Some(RecordData::Chars(
+                chunks.iter().copied().map(FlexString::from).collect(),
+            )),
+        );
+        let summary = Summary::try_from(records).unwrap();
+        assert_eq!(summary.measurements, ["O:Time", "O:Oil_Production_Rate"]);
+
+        // Descriptions that can't be split between the items are ignored.
+        let mut records = smspec_records(&items);
+        records.records.insert(
+            "MEASRMNT",
+            Some(RecordData::Chars(vec![FlexString::from("Time")])),
+        );
+        assert!(Summary::try_from(records).unwrap().measurements.is_empty());
+    }
+
+    #[test]
+    fn duplicate_items() {
+        let records = smspec_records(&[
+            ("TIME", "", 0),
+            ("WBHP", "PROD", 0),
+            ("FOPR", "", 0),
+            ("WBHP", "PROD", 0),
+        ]);
+        let mut summary = Summary::try_from(records).unwrap();
+        summary.append(vec![1.0, 200.0, 10.0, 300.0]).unwrap();
+
+        assert_eq!(summary.item_ids.len(), 3);
+        assert_eq!(summary.n_items(), 4);
+        let wbhp = ItemId {
+            name: FlexString::from("WBHP"),
+            qualifier: ItemQualifier::Well {
+                wg_name: FlexString::from("PROD"),
+            },
+        };
+        assert_eq!(*summary.item_values(summary.item_ids[&wbhp]), [200.0]);
+
+        // The other occurrence is kept as well.
+        assert_eq!(summary.duplicate_items(), [(&wbhp, vec![1, 3])]);
+        assert_eq!(*summary.item_values(3), [300.0]);
+        assert_eq!(summary.columns().count(), 4);
+
+        let mut csv = Vec::new();
+        crate::to_csv::to_csv_to_writer(&summary, &mut csv, &Default::default()).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "KEYWORD,FOPR,TIME,WBHP,WBHP");
+        assert!(lines[4].ends_with(",10,1,200,300"));
+
+        let dir = tempfile::tempdir().unwrap();
+        summary.save_to_files(dir.path().join("DUPLICATE")).unwrap();
+        let saved = read_summary(&dir.path().join("DUPLICATE"));
+        assert_eq!(saved.duplicate_items(), [(&wbhp, vec![1, 3])]);
+        assert_same_data(&saved, &summary);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        let deserialized: Summary = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.columns().count(), 4);
+        let (_, indices) = &deserialized.duplicate_items()[0];
+        let values: Vec<Vec<f32>> = indices
+            .iter()
+            .map(|&index| deserialized.values_for(index).collect())
+            .collect();
+        assert_eq!(values, [[200.0], [300.0]]);
+
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json().unwrap()).unwrap();
+        let wbhp_values: Vec<&serde_json::Value> = json["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|item| item["name"] == "WBHP")
+            .map(|item| &item["values"])
+            .collect();
+        assert_eq!(
+            wbhp_values,
+            [&serde_json::json!([200.0]), &serde_json::json!([300.0])]
+        );
+
+        #[cfg(feature = "arrow")]
+        {
+            let batch = summary.to_record_batch().unwrap();
+            let schema = batch.schema();
+            let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+            assert_eq!(
+                names,
+                ["TIMESTAMP", "FOPR", "TIME", "WBHP:PROD", "WBHP:PROD_2"]
+            );
+        }
+    }
+
+    #[test]
+    fn datetimes() {
+        let summary = read_summary(Path::new("../assets/SPE10"));
+        let start = NaiveDate::from_ymd_opt(2005, 3, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .unwrap();
+        assert_eq!(summary.start_datetime(), start);
+
+        let datetimes = summary.timestamps_as_datetimes();
+        assert_eq!(datetimes.len(), 58);
+        assert_eq!(datetimes[0], start);
+        assert_eq!(datetimes[1], start + Duration::days(1));
+    }
+
+    #[test]
+    fn write_csv_spe_10() {
+        let summary = read_summary(Path::new("../assets/SPE10"));
+        let mut csv = Vec::new();
+        assert_eq!(summary.write_csv(&mut csv).unwrap(), 58);
+        let csv = String::from_utf8(csv).unwrap();
+
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header.len(), summary.n_items() + 1);
+        assert_eq!(
+            header[..10],
+            [
+                "DATE",
+                "BPR:120910 [PSIA]",
+                "BPR:197450 [PSIA]",
+                "BRS:120910 [MSCF/STB]",
+                "BRS:197450 [MSCF/STB]",
+                "FGIP [MSCF]",
+                "FOIP [STB]",
+                "FWIP [STB]",
+                "TIME [DAYS]",
+                "WBHP:I1 [PSIA]",
+            ]
+        );
+        assert_eq!(header[24], "WWCT:I1");
+
+        let row: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(
+            row[..9],
+            [
+                "2005-03-01T00:00:00",
+                "2167.7368",
+                "2179.2222",
+                "0.35",
+                "0.35",
+                "10550736",
+                "24268124",
+                "50010240",
+                "0",
+            ]
+        );
+        assert!(row[9..].iter().all(|&value| value == "0"));
+        assert_eq!(lines.count(), 57);
+    }
+
+    #[test]
+    fn to_json_spe_10() {
+        let summary = read_summary(Path::new("../assets/SPE10"));
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json().unwrap()).unwrap();
+
+        let keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str())
+            .collect();
+        assert_eq!(keys, ["dims", "items", "start", "timestamps"]);
+        assert_eq!(json["start"], "2005-03-01T00:00:00");
+        assert_eq!(json["timestamps"][1], summary.timestamps[1]);
+
+        let items = json["items"].as_array().unwrap();
+        assert_eq!(items.len(), 34);
+        assert_eq!(items[0]["name"], "BPR");
+        assert_eq!(
+            items[0]["qualifier"],
+            serde_json::json!({"kind": "Block", "index": 120910})
+        );
+        assert_eq!(items[0]["unit"], "PSIA");
+        assert!(items[0]["values"][0].is_number());
+    }
+
+    #[test]
+    fn msgpack_round_trip() {
+        for path in &["../assets/SPE10", "../assets/SPE10_DOUB"] {
+            let summary = read_summary(Path::new(path));
+            for msgpack in &[
+                rmp_serde::to_vec(&summary).unwrap(),
+                rmp_serde::to_vec_named(&summary).unwrap(),
+            ] {
+                let read: Summary = rmp_serde::from_slice(msgpack).unwrap();
+                assert_eq!(read.dims, summary.dims);
+                assert_eq!(read.start_timestamp, summary.start_timestamp);
+                assert_eq!(read.timestamps, summary.timestamps);
+                assert_eq!(read.n_items(), summary.n_items());
+                for (id, &index) in &summary.item_ids {
+                    let read_item = &read.items[read.item_ids[id]];
+                    assert_eq!(read_item.unit, summary.items[index].unit);
+                    assert_eq!(
+                        read.item_column(read.item_ids[id]),
+                        summary.item_column(index)
+                    );
+                }
+            }
+        }
+
+        let json = serde_json::to_string(&read_summary(Path::new("../assets/SPE10"))).unwrap();
+        let read: Summary = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.timestamps.len(), 58);
+    }
+
+    #[test]
+    fn serialize_spe_10() {
+        let summary = read_summary(Path::new("../assets/SPE10"));
+        let json = serde_json::to_value(&summary).unwrap();
+
+        assert_eq!(json["dims"], serde_json::json!(summary.dims));
+        assert_eq!(json["start_date"], "2005-03-01T00:00:00");
+        assert_eq!(json["units_system"], serde_json::Value::Null);
+        assert_eq!(json["timestamps"].as_array().unwrap().len(), 58);
+
+        let items = json["items"].as_object().unwrap();
+        let kinds: Vec<&str> = items.keys().map(|k| k.as_str()).collect();
+        assert_eq!(kinds, ["Block", "Field", "Time", "Well"]);
+        assert_eq!(items["Well"].as_array().unwrap().len(), 25);
+
+        let wbhp = &items["Well"][0];
+        assert_eq!(wbhp["name"], "WBHP");
+        assert_eq!(
+            wbhp["qualifier"],
+            serde_json::json!({"kind": "Well", "wg_name": "I1"})
+        );
+        assert_eq!(wbhp["unit"], "PSIA");
+        assert_eq!(wbhp["values"].as_array().unwrap().len(), 58);
+
+        let bpr = &items["Block"][0];
+        assert_eq!(
+            bpr["qualifier"],
+            serde_json::json!({"kind": "Block", "index": 120910})
+        );
+    }
+
+    #[test]
+    fn items_by_name() {
+        let summary = read_summary(Path::new("../assets/SPE10"));
+
+        let wells: Vec<String> = summary
+            .items_by_name("WOPR")
+            .into_iter()
+            .map(|(id, item)| {
+                assert_eq!(item.unit, "STB/DAY");
+                id.qualifier.to_string()
+            })
+            .collect();
+        assert_eq!(
+            wells,
+            ["Well I1", "Well P1", "Well P2", "Well P3", "Well P4"]
+        );
+
+        assert_eq!(summary.items_by_name("BPR").len(), 2);
+        assert!(summary.items_by_name("GOPR").is_empty());
+    }
+
+    #[test]
+    fn slice_time() {
+        let records = smspec_records(&[("TIME", "", 0), ("FOPR", "", 0)]);
+        let mut summary = Summary::try_from(records).unwrap();
+        for day in 1..=4 {
+            summary.append(vec![day as f32, 10.0 * day as f32]).unwrap();
+        }
+
+        let start = summary.start_timestamp;
+        let day = 86400;
+        let fopr = ItemId {
+            name: FlexString::from("FOPR"),
+            qualifier: ItemQualifier::Field,
+        };
+
+        let view = summary.slice_time(start + 2 * day, start + 3 * day);
+        assert_eq!(view.range(), 1..3);
+        assert_eq!(view.timestamps(), [start + 2 * day, start + 3 * day]);
+        assert_eq!(view.values(&fopr).unwrap()[..], [20.0, 30.0]);
+
+        // Bounds between time iterations and beyond the data.
+        let view = summary.slice_time(start + day / 2, start + 100 * day);
+        assert_eq!(view.range(), 0..4);
+        assert_eq!(view.values(&fopr).unwrap().len(), 4);
+
+        assert!(summary.slice_time(start, start + day / 2).is_empty());
+        assert!(summary
+            .slice_time(start + 3 * day, start + 2 * day)
+            .is_empty());
+        assert!(summary
+            .slice_time(start + 10 * day, start + 20 * day)
+            .is_empty());
+    }
+
+    #[test]
+    fn value_at() {
+        let summary = read_summary(Path::new("../assets/SPE10"));
+        let foip = ItemId {
+            name: FlexString::from("FOIP"),
+            qualifier: ItemQualifier::Field,
+        };
+        let values = summary.item_values(summary.item_ids[&foip]);
+        let (t1, t2) = (summary.timestamps[1], summary.timestamps[2]);
+
+        assert_eq!(summary.value_at(&foip, t1), Some(values[1]));
+        let midpoint = summary.value_at(&foip, (t1 + t2) / 2).unwrap();
+        let expected = (values[1] as f64 + values[2] as f64) / 2.0;
+        assert!((midpoint as f64 - expected).abs() <= expected.abs() * 1e-6);
+
+        assert_eq!(summary.value_at(&foip, summary.timestamps[0] - 1), None);
+        assert_eq!(
+            summary.value_at(&foip, *summary.timestamps.last().unwrap() + 1),
+            None
+        );
+        let fopr = ItemId {
+            name: FlexString::from("FOPR"),
+            qualifier: ItemQualifier::Field,
+        };
+        assert_eq!(summary.value_at(&fopr, t1), None);
+    }
+
+    #[test]
+    fn latest() {
+        let records = smspec_records(&[("TIME", "", 0), ("FOPR", "", 0)]);
+        let mut summary = Summary::try_from(records).unwrap();
+        assert_eq!(summary.latest(), None);
+
+        summary.append(vec![1.0, 10.0]).unwrap();
+        summary.append(vec![2.0, 20.0]).unwrap();
+        let (timestamp, values) = summary.latest().unwrap();
+        assert_eq!(timestamp, summary.timestamps[1]);
+        let names: Vec<String> = values.iter().map(|(id, _)| id.to_string()).collect();
+        assert_eq!(names, ["FOPR", "TIME"]);
+        assert_eq!(values[0].1, 20.0);
+        assert_eq!(values[1].1, 2.0);
+    }
+
+    #[test]
+    fn cumulative() {
+        let records = smspec_records(&[("TIME", "", 0), ("FOPR", "", 0)]);
+        let mut summary = Summary::try_from(records).unwrap();
+        let fopr = ItemId {
+            name: FlexString::from("FOPR"),
+            qualifier: ItemQualifier::Field,
+        };
+        let fwpr = ItemId {
+            name: FlexString::from("FWPR"),
+            qualifier: ItemQualifier::Field,
+        };
+
+        summary.append(vec![1.0, 10.0]).unwrap();
+        assert_eq!(summary.cumulative(&fopr), Some(vec![0.0]));
+        assert_eq!(summary.cumulative(&fwpr), None);
+
+        // Irregular time steps of 2 and 0.5 days.
+        summary.append(vec![3.0, 20.0]).unwrap();
+        summary.append(vec![3.5, 40.0]).unwrap();
+        assert_eq!(summary.cumulative(&fopr), Some(vec![0.0, 30.0, 45.0]));
+    }
+
+    #[test]
+    fn stats() {
+        let item = |values: &[f32]| item_stats(values.iter().copied());
+
+        assert_eq!(
+            item(&[2.0, f32::NAN, 5.0, 1.0, 5.0, f32::NAN]),
+            ItemStats {
+                min: Some(1.0),
+                max: Some(5.0),
+                mean: Some(3.25),
+                last: Some(5.0),
+                argmax_index: Some(2),
+            }
+        );
+        assert_eq!(item(&[]), ItemStats::default());
+        assert_eq!(item(&[f32::NAN, f32::NAN]), ItemStats::default());
+
+        let summary = read_summary(Path::new("../assets/SPE10"));
+        let time = ItemId {
+            name: FlexString::from("TIME"),
+            qualifier: ItemQualifier::Time,
+        };
+        let stats = summary.stats_for(&time).unwrap();
+        assert_eq!(stats.min, Some(0.0));
+        assert_eq!(stats.argmax_index, Some(57));
+        assert_eq!(stats.last, stats.max);
+        assert_eq!(
+            summary.stats_for(&ItemId {
+                name: FlexString::from("FOPR"),
+                qualifier: ItemQualifier::Field,
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn resample() {
+        let records = smspec_records(&[
+            ("TIME", "", 0),
+            ("FOPR", "", 0),
+            ("FOPT", "", 0),
+            ("FOPR", "", 0),
+        ]);
+        let mut summary = Summary::try_from(records).unwrap();
+        summary.append(vec![1.0, 10.0, 10.0, 10.0]).unwrap();
+        summary.append(vec![3.0, 30.0, 50.0, 30.0]).unwrap();
+
+        let start = summary.start_timestamp;
+        let day = 86400;
+        let targets = [
+            start,
+            start + day,
+            start + 2 * day,
+            start + 3 * day,
+            start + 4 * day,
+        ];
+
+        let linear = summary.resample(&targets, ResampleMethod::Linear);
+        assert!(linear[1][0].is_nan());
+        assert_eq!(linear[1][1..4], [10.0, 20.0, 30.0]);
+        assert!(linear[1][4].is_nan());
+
+        let previous = summary.resample(&targets, ResampleMethod::Previous);
+        assert_eq!(previous[2][1..4], [10.0, 10.0, 50.0]);
+
+        let auto = summary.resample(&targets, ResampleMethod::Auto);
+        assert_eq!(auto[0][1..4], [1.0, 2.0, 3.0]);
+        assert_eq!(auto[1][1..4], [10.0, 20.0, 30.0]);
+        assert_eq!(auto[2][1..4], [10.0, 10.0, 50.0]);
+        // Both occurrences of a duplicate rate are interpolated linearly.
+        assert_eq!(auto[3][1..4], [10.0, 20.0, 30.0]);
+
+        // Resampling onto the own timestamps does not change anything.
+        let spe_10 = read_summary(Path::new("../assets/SPE10"));
+        let resampled = spe_10.resample(&spe_10.timestamps, ResampleMethod::Auto);
+        for (index, values) in resampled.iter().enumerate() {
+            assert_eq!(*spe_10.item_values(index), values[..]);
+        }
+    }
+
+    #[test]
+    fn units_system_from_intehead() {
+        let mut records = smspec_records(&[("TIME", "", 0)]);
+        let summary = Summary::try_from(records).unwrap();
+        assert_eq!(summary.units_system, None);
+        assert_eq!(summary.simulator_id, None);
+
+        records = smspec_records(&[("TIME", "", 0)]);
+        records
+            .records
+            .insert("INTEHEAD", Some(RecordData::Int(vec![2, 100])));
+        let summary = Summary::try_from(records).unwrap();
+        assert_eq!(summary.units_system, Some(UnitSystem::Field));
+        assert_eq!(summary.simulator_id, Some(100));
+
+        records = smspec_records(&[("TIME", "", 0)]);
+        records
+            .records
+            .insert("INTEHEAD", Some(RecordData::Int(vec![7, 100])));
+        assert!(matches!(
+            Summary::try_from(records),
+            Err(EclairError::InvalidUnitSystemId(7))
+        ));
+    }
+
+    #[test]
+    fn append_params_batch() {
+        // Enough items to span several blocks.
+        let mut items = vec![("TIME", "", 0)];
+        items.extend((1..600).map(|i| ("BPR", "", i)));
+        let row = |step: usize| {
+            (0..items.len())
+                .map(|i| (step * i) as f32)
+                .collect::<Vec<_>>()
+        };
+
+        let mut expected = Summary::try_from(smspec_records(&items)).unwrap();
+        let mut summary = Summary::try_from(smspec_records(&items)).unwrap();
+        let batch = (1..5)
+            .map(|step| match step {
+                3 => Params::F64(row(step).into_iter().map(f64::from).collect()),
+                _ => Params::F32(row(step)),
+            })
+            .collect::<Vec<_>>();
+
+        for params in &batch {
+            let params = match params {
+                Params::F32(values) => Params::F32(values.clone()),
+                Params::F64(values) => Params::F64(values.clone()),
+            };
+            expected.append_params(params).unwrap();
+        }
+        summary.append_params_batch(batch).unwrap();
+        assert_eq!(summary.timestamps, expected.timestamps);
+        for index in 0..summary.n_items() {
+            assert_eq!(summary.item_column(index), expected.item_column(index));
+        }
+
+        // The time iterations preceding one of the wrong length are appended.
+        let batch = vec![
+            Params::F32(row(5)),
+            Params::F32(vec![0.0]),
+            Params::F32(row(6)),
+        ];
+        assert!(matches!(
+            summary.append_params_batch(batch),
+            Err(EclairError::UnexpectedRecordDataLength { found: 1, .. })
+        ));
+        assert_eq!(summary.n_steps(), 5);
+        assert_eq!(summary.item_values(1)[4], 5.0);
+    }
+
+    #[test]
+    fn double_precision_params() {
+        let records = vec![
+            Record {
+                name: FlexString::from("SEQHDR"),
+                data: RecordData::Int(vec![0]),
+            },
+            Record {
+                name: FlexString::from("MINISTEP"),
+                data: RecordData::Int(vec![0]),
+            },
+            Record {
+                name: FlexString::from("PARAMS"),
+                data: RecordData::F64(vec![1.0, 2.5, -1.0e10]),
+            },
+        ];
+
+        let mut buf = Vec::new();
+        for record in &records {
+            buf.write_record(record).unwrap();
+        }
 
-    macro_rules! unwrap_and_validate {
-        ($record: expr, $field_name: literal, $kind: ident, $valid_len: expr) => {
-            match $record {
-                Some(Record { name, data }) if name == $field_name => {
-                    validate!(data, $field_name, $kind, $valid_len)
+        let mut cursor = std::io::Cursor::new(buf);
+        let (n_bytes, _, params) = get_next_params(&mut cursor, Some(0), 3).unwrap().unwrap();
+        assert_eq!(n_bytes as u64, cursor.position());
+        assert_eq!(params, Params::F64(vec![1.0, 2.5, -1.0e10]));
+
+        // The number of values is still validated.
+        cursor.set_position(0);
+        assert!(matches!(
+            get_next_params(&mut cursor, Some(0), 4),
+            Err(EclairError::UnexpectedRecordDataLength { .. })
+        ));
+    }
+
+    #[test]
+    fn open_summary() {
+        for path in &["../assets/SPE10", "../assets/SPE10.UNSMRY"] {
+            let summary = Summary::open(path).unwrap();
+            assert_eq!(summary.n_items(), 34);
+            assert_eq!(summary.n_steps(), 58);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let smspec_path = dir.path().join("SPE10.SMSPEC");
+        std::fs::copy("../assets/SPE10.SMSPEC", &smspec_path).unwrap();
+
+        match Summary::open(&smspec_path) {
+            Err(EclairError::MissingSummaryFile(path)) => assert!(path.ends_with("SPE10.UNSMRY")),
+            _ => panic!("Expected a missing UNSMRY file error"),
+        }
+    }
+
+    #[test]
+    fn presized_item_values() {
+        // The values are allocated once, for as many time iterations as the file holds.
+        for path in &[
+            "../assets/SPE10",
+            "../assets/SPE10_DOUB",
+            "../assets/SPE10.FSMSPEC",
+        ] {
+            let (summary, _) = SummaryFileReader::from_path(path).unwrap().init().unwrap();
+            let n_steps = summary.n_steps();
+            assert_eq!(summary.timestamps.capacity(), n_steps);
+            #[cfg(not(feature = "row_major"))]
+            for item in &summary.items {
+                assert_eq!(item.values.single.capacity(), n_steps);
+                if let Some(double) = &item.values.double {
+                    assert_eq!(double.capacity(), n_steps);
                 }
-                _ => {
-                    return Err(MissingRecord($field_name.to_string()));
+            }
+            #[cfg(feature = "row_major")]
+            {
+                let n_values = n_steps * summary.n_items();
+                assert_eq!(summary.rows.single.capacity(), n_values);
+                if let Some(double) = &summary.rows.double {
+                    assert_eq!(double.capacity(), n_values);
                 }
             }
-        };
+        }
     }
 
-    let mut n_bytes_read = 0;
+    #[test]
+    fn read_double_precision_unsmry() {
+        let double = read_summary(Path::new("../assets/SPE10_DOUB"));
+        let single = read_summary(Path::new("../assets/SPE10"));
 
-    let (n_bytes, mut record) = reader.read_record()?;
+        assert_eq!(double.n_steps(), 58);
+        assert_same_data(&double, &single);
+    }
 
-    // This could be a SEQHDR.
-    let read_next = match &record {
-        None => return Ok(None),
-        Some(Record { name, .. }) => {
-            n_bytes_read += n_bytes;
-            name == "SEQHDR"
-        }
-    };
+    #[test]
+    fn double_precision_values() {
+        let records = smspec_records(&[("TIME", "", 0), ("FOPT", "", 0)]);
+        let mut summary = Summary::try_from(records).unwrap();
 
-    if read_next {
-        let (n_bytes, next_record) = reader.read_record()?;
-        record = next_record;
-        n_bytes_read += n_bytes;
-    }
+        summary.append(vec![1.0, 100.0]).unwrap();
+        summary.append_f64(vec![2.0, 16_777_217.1]).unwrap();
+        summary.append(vec![3.0, 0.5]).unwrap();
 
-    // Next one should be MINISTEP. The wrapped counter inside starts at 0.
-    let step_index = unwrap_and_validate!(record, "MINISTEP", Int, 1)[0] as usize;
+        let fopt = summary.item_column(1);
+        assert!(fopt.is_double_precision());
+        assert_eq!(fopt.as_f64_vec(), vec![100.0, 16_777_217.1, 0.5]);
+        assert_eq!(fopt.as_f32_slice(), &[100.0, 16_777_218.0, 0.5]);
 
-    if step_index != step {
-        return Err(EclairError::InvalidMinistepValue {
-            expected: step,
-            found: step_index,
-        });
+        // The values survive a round trip through the files without a loss of precision.
+        let dir = tempfile::tempdir().unwrap();
+        summary.save_to_files(dir.path().join("DOUBLE")).unwrap();
+        let saved = read_summary(&dir.path().join("DOUBLE"));
+        assert_eq!(saved.timestamps, summary.timestamps);
+        assert_eq!(saved.item_column(1).as_f64_vec(), fopt.as_f64_vec());
     }
 
-    let (n_bytes, record) = reader.read_record()?;
-    n_bytes_read += n_bytes;
+    #[test]
+    fn read_separate_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::copy("../assets/SPE10.SMSPEC", dir.path().join("SPE10.SMSPEC")).unwrap();
 
-    // Next is PARAMS with as many values as we have items.
-    let params = unwrap_and_validate!(record, "PARAMS", F32, n_items);
-    Ok(Some((n_bytes_read, params)))
-}
+        // Split the unified file into three non-unified ones at SEQHDR boundaries.
+        let unsmry = File::open("../assets/SPE10.UNSMRY").unwrap();
+        let records: Vec<Record> = BufReader::new(unsmry)
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+        let steps: Vec<&[Record]> = records.chunks(3).collect();
+        for (i, chunk) in steps.chunks(20).enumerate() {
+            let path = dir.path().join(format!("SPE10.S{:04}", i + 1));
+            let mut file = File::create(path).unwrap();
+            for record in chunk.iter().flat_map(|step| step.iter()) {
+                file.write_record(record).unwrap();
+            }
+        }
 
-impl UpdateSummary for SummaryFileUpdater {
-    fn update(&mut self, data_snd: Sender<Vec<f32>>, term_rcv: Receiver<bool>) -> Result<()> {
-        // Continuously tries to read from the UNSMRY file and sends new values over the provided
-        // channel.
-        let mut file_pos = self.unsmry_file.seek(SeekFrom::Current(0)).unwrap();
-        let mut last_read_successful = true;
-        let mut modified_time = std::time::SystemTime::now();
+        let unified = read_summary(Path::new("../assets/SPE10"));
+        let separate = read_summary(&dir.path().join("SPE10"));
 
-        loop {
-            // First check if we were instructed to stop.
-            if let Ok(_) = term_rcv.try_recv() {
-                log::info!(
-                    target: "SummaryFileUpdater::update",
-                    "Received termination request."
-                );
-                return Ok(());
+        assert_eq!(separate.n_steps(), 58);
+        assert_same_data(&separate, &unified);
+    }
+
+    #[test]
+    fn ministep_reset_between_separate_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::copy("../assets/SPE10.SMSPEC", dir.path().join("SPE10.SMSPEC")).unwrap();
+
+        // Split the unified file into two, restarting the MINISTEP counter in each of them.
+        let unsmry = File::open("../assets/SPE10.UNSMRY").unwrap();
+        let mut records: Vec<Record> = BufReader::new(unsmry)
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+        for (i, chunk) in records.chunks_mut(3 * 29).enumerate() {
+            let path = dir.path().join(format!("SPE10.S{:04}", i + 1));
+            let mut file = File::create(path).unwrap();
+            for (step, records) in chunk.chunks_mut(3).enumerate() {
+                records[1].data = RecordData::Int(vec![step as i32]);
+                for record in records.iter() {
+                    file.write_record(record).unwrap();
+                }
             }
+        }
 
-            // Try to read from the file if necessary.
-            let metadata = self.unsmry_file.get_ref().metadata()?;
-            let new_modified_time = metadata.modified()?;
+        let unified = read_summary(Path::new("../assets/SPE10"));
+        let separate = read_summary(&dir.path().join("SPE10"));
 
-            if last_read_successful || new_modified_time > modified_time {
-                modified_time = new_modified_time;
-                let params = get_next_params(&mut self.unsmry_file, self.n_steps, self.n_items);
+        assert_eq!(separate.n_steps(), 58);
+        assert_same_data(&separate, &unified);
+    }
 
-                last_read_successful = match params {
-                    Ok(params) => {
-                        if let Some((n_bytes, params)) = params {
-                            file_pos += n_bytes as u64;
-                            self.n_steps += 1;
+    /// Write a case into `dir` from the SPE10 records, restarted from `restart` and containing
+    /// the given range of time steps. The FGIP keyword is renamed to `gas_keyword`.
+    fn write_spe_10_case(
+        dir: &Path,
+        name: &str,
+        restart: &str,
+        gas_keyword: &str,
+        steps: std::ops::Range<usize>,
+    ) {
+        let read_records = |path: &str| -> Vec<Record> {
+            BufReader::new(File::open(path).unwrap())
+                .records()
+                .map(|r| r.unwrap())
+                .collect()
+        };
 
-                            if data_snd.send(params).is_err() {
-                                log::info!(target: "SummaryFileUpdater::update", "Error while sending params over a channel");
-                                return Ok(());
-                            }
-                            true
-                        } else {
-                            false
-                        }
-                    }
-                    Err(_) => {
-                        self.unsmry_file.seek(SeekFrom::Start(file_pos)).unwrap();
-                        false
+        let mut smspec = File::create(dir.join(format!("{}.SMSPEC", name))).unwrap();
+        for mut record in read_records("../assets/SPE10.SMSPEC") {
+            match (record.name.as_str(), &mut record.data) {
+                ("RESTART", RecordData::Chars(chars)) => {
+                    chars[0] = restart.into();
+                }
+                ("KEYWORDS", RecordData::Chars(chars)) => {
+                    for keyword in chars.iter_mut().filter(|kw| kw.as_str() == "FGIP") {
+                        *keyword = gas_keyword.into();
                     }
-                };
+                }
+                _ => {}
             }
-            sleep(time::Duration::from_millis(100));
+            smspec.write_record(&record).unwrap();
+        }
+
+        let mut unsmry = File::create(dir.join(format!("{}.UNSMRY", name))).unwrap();
+        let records = read_records("../assets/SPE10.UNSMRY");
+        for record in records
+            .chunks(3)
+            .skip(steps.start)
+            .take(steps.len())
+            .flatten()
+        {
+            unsmry.write_record(record).unwrap();
         }
     }
-}
 
-impl SummaryFileReader {
-    pub fn from_path<P>(input_path: P) -> Result<Self>
-    where
-        P: AsRef<std::path::Path>,
-    {
-        // If there is no stem, bail early.
-        let input_path = input_path.as_ref();
+    #[test]
+    fn updater_reads_appended_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        write_spe_10_case(dir.path(), "LIVE", "", "FGIP", 0..30);
 
-        let stem = input_path.file_stem();
-        if stem.is_none() || stem.unwrap().to_str().is_none() {
-            return Err(EclairError::InvalidFilePath(
-                input_path.to_string_lossy().to_string(),
-            ));
-        }
+        let (summary, mut updater) = SummaryFileReader::from_path(dir.path().join("LIVE"))
+            .unwrap()
+            .with_poll_interval(time::Duration::from_millis(10))
+            .init()
+            .unwrap();
+        assert_eq!(summary.n_steps(), 30);
 
-        // We allow SMSPEC and UNSMRY extensions or no extension at all.
-        if let Some(ext) = input_path.extension() {
-            let ext = ext.to_str();
-            if ext != Some("SMSPEC") && ext != Some("UNSMRY") {
-                return Err(EclairError::InvalidFilePath(
-                    input_path.to_string_lossy().to_string(),
-                ));
-            }
+        let (data_snd, data_rcv) = crossbeam_channel::unbounded();
+        let (term_snd, term_rcv) = crossbeam_channel::bounded(1);
+        let updater_thread = std::thread::spawn(move || updater.update(data_snd, term_rcv));
+
+        // Simulate the simulator writing out the next time step.
+        let records: Vec<Record> = BufReader::new(File::open("../assets/SPE10.UNSMRY").unwrap())
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+        let mut unsmry = std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join("LIVE.UNSMRY"))
+            .unwrap();
+        for record in &records[90..93] {
+            unsmry.write_record(record).unwrap();
         }
+        unsmry.flush().unwrap();
 
-        let open_file = |path| -> Result<_> { Ok(BufReader::new(File::open(path)?)) };
-        Ok(Self {
-            smspec_file: open_file(input_path.with_extension("SMSPEC"))?,
-            unsmry_file: open_file(input_path.with_extension("UNSMRY"))?,
-        })
+        let params = data_rcv
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(
+            params,
+            Params::F32(records[92].data.as_f32().unwrap().to_vec())
+        );
+
+        term_snd.send(true).unwrap();
+        updater_thread.join().unwrap().unwrap();
     }
-}
 
-impl InitializeSummary for SummaryFileReader {
-    type Updater = SummaryFileUpdater;
+    #[test]
+    fn updater_completes_partial_record() {
+        let dir = tempfile::tempdir().unwrap();
+        write_spe_10_case(dir.path(), "LIVE", "", "FGIP", 0..30);
 
-    fn init(mut self) -> Result<(Summary, Self::Updater)> {
-        use EclairError::*;
+        let (_, mut updater) = SummaryFileReader::from_path(dir.path().join("LIVE"))
+            .unwrap()
+            .with_poll_interval(time::Duration::from_millis(10))
+            .init()
+            .unwrap();
 
-        // First build the SmspecRecords object from the Smspec source.
-        let mut smspec_records = SmspecRecords::default();
+        let (data_snd, data_rcv) = crossbeam_channel::unbounded();
+        let (term_snd, term_rcv) = crossbeam_channel::bounded(1);
+        let updater_thread = std::thread::spawn(move || updater.update(data_snd, term_rcv));
 
-        loop {
-            let (_, record) = self.smspec_file.read_record()?;
-            if record.is_none() {
-                break;
-            }
+        // The simulator is interrupted in the middle of the PARAMS record.
+        let records: Vec<Record> = BufReader::new(File::open("../assets/SPE10.UNSMRY").unwrap())
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+        let mut step = Vec::new();
+        for record in &records[90..93] {
+            step.write_record(record).unwrap();
+        }
+        let mut unsmry = std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join("LIVE.UNSMRY"))
+            .unwrap();
+        let (head, tail) = step.split_at(step.len() - 50);
+        unsmry.write_all(head).unwrap();
+        unsmry.flush().unwrap();
 
-            let Record { name, data } = record.unwrap();
-            // Stop reading records if we encounter a name that does not belong in SMSPEC.
-            if !SMSPEC_RECORDS.contains(&name.as_str()) {
-                log::debug!(target: "Parsing SMSPEC", "Non-SMSPEC record name encountered: {}.", name);
-                break;
+        std::thread::sleep(time::Duration::from_millis(100));
+        assert!(data_rcv.try_recv().is_err());
+
+        unsmry.write_all(tail).unwrap();
+        unsmry.flush().unwrap();
+        let params = data_rcv
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(
+            params,
+            Params::F32(records[92].data.as_f32().unwrap().to_vec())
+        );
+
+        term_snd.send(true).unwrap();
+        updater_thread.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn read_formatted_spe_10() {
+        let binary = read_summary(Path::new("../assets/SPE10"));
+        let formatted = read_summary(Path::new("../assets/SPE10.FSMSPEC"));
+        assert_eq!(formatted.timestamps, binary.timestamps);
+        assert_eq!(formatted.item_ids, binary.item_ids);
+
+        // Formatted REAL values have 8 significant digits, which is not always enough for an f32.
+        for (index, (lhs, rhs)) in formatted.items.iter().zip(&binary.items).enumerate() {
+            assert_eq!(lhs.unit, rhs.unit);
+            for (a, b) in formatted.values_for(index).zip(binary.values_for(index)) {
+                assert!((a - b).abs() <= 1e-7 * b.abs(), "{} != {}", a, b);
             }
+        }
+    }
+
+    #[test]
+    fn read_tail() {
+        let expected = read_summary(Path::new("../assets/SPE10"));
+        let n_total = expected.n_steps();
+        let tail = |path: &Path, n_steps| {
+            SummaryFileReader::from_path_tail(path, n_steps)
+                .unwrap()
+                .init()
+                .map(|(summary, _)| summary)
+        };
+
+        let summary = tail(Path::new("../assets/SPE10"), 10).unwrap();
+        assert_eq!(summary.timestamps, expected.timestamps[n_total - 10..]);
+        for (id, &index) in &summary.item_ids {
+            assert_eq!(
+                summary.item_values(index)[..],
+                expected.item_values(expected.item_ids[id])[n_total - 10..]
+            );
+        }
+        assert_eq!(
+            tail(Path::new("../assets/SPE10"), n_total + 5)
+                .unwrap()
+                .n_steps(),
+            n_total
+        );
+
+        // Formatted data is read in full, but only room for the tail is taken.
+        let summary = tail(Path::new("../assets/SPE10.FSMSPEC"), 10).unwrap();
+        assert_eq!(summary.timestamps, expected.timestamps[n_total - 10..]);
+        assert_eq!(summary.timestamps.capacity(), 10);
 
-            // If we encounter a record that we wish to consume, first check whether we've already
-            // read it. "NAMES" is looked up as "WGNAMES" because only one of them is allowed in
-            // a given SMSPEC at the same time.
-            let lookup_name = if &name == "NAMES" { "WGNAMES" } else { &name };
-            if let Some(val) = smspec_records.records.get_mut(lookup_name) {
-                if val.is_some() {
-                    return Err(RecordEncounteredTwice(name.to_string()));
+        // Only some of the time iterations start with a SEQHDR.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::copy("../assets/SPE10.SMSPEC", dir.path().join("SPARSE.SMSPEC")).unwrap();
+        let records: Vec<Record> = BufReader::new(File::open("../assets/SPE10.UNSMRY").unwrap())
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+        let write_unsmry = |records: &[Record]| {
+            let mut unsmry = File::create(dir.path().join("SPARSE.UNSMRY")).unwrap();
+            for (step, triplet) in records.chunks(3).enumerate() {
+                let skip = if step % 5 == 0 { 0 } else { 1 };
+                for record in &triplet[skip..] {
+                    unsmry.write_record(record).unwrap();
                 }
-                *val = Some(data);
             }
+        };
+        write_unsmry(&records);
+        let summary = tail(&dir.path().join("SPARSE"), 7).unwrap();
+        assert_eq!(summary.timestamps, expected.timestamps[n_total - 7..]);
 
-            // If we found all the records we need, stop reading further. This allows us to chain
-            // valid SMSPEC and UNSMRY records in a single stream.
-            if smspec_records.is_full() {
-                log::debug!(target: "Parsing SMSPEC", "Found all the neccessary records.");
-                break;
+        // MINISTEP values of the tail still have to follow each other.
+        let mut records = records;
+        let ministep = &mut records[3 * (n_total - 2) + 1].data;
+        *ministep = RecordData::Int(vec![0]);
+        write_unsmry(&records);
+        assert!(matches!(
+            tail(&dir.path().join("SPARSE"), 7),
+            Err(EclairError::InvalidMinistepValue { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_unsmry() {
+        let dir = tempfile::tempdir().unwrap();
+        let expected = read_summary(Path::new("../assets/SPE10"));
+        let unsmry = std::fs::read("../assets/SPE10.UNSMRY").unwrap();
+        std::fs::copy("../assets/SPE10.SMSPEC", dir.path().join("SPE10.SMSPEC")).unwrap();
+
+        // A cut in the last PARAMS body is a time iteration still being written, we stop before it.
+        let cut = unsmry.len() - 10;
+        std::fs::write(dir.path().join("SPE10.UNSMRY"), &unsmry[..cut]).unwrap();
+        let summary = read_summary(&dir.path().join("SPE10"));
+        assert_eq!(summary.n_steps(), expected.n_steps() - 1);
+
+        let mut reader = RecordReader::new(Cursor::new(&unsmry[..cut]));
+        let err = loop {
+            if let Err(err) = reader.read_record() {
+                break err;
             }
+        };
+        assert!(err.is_truncation());
+        assert!(matches!(
+            err,
+            EclairError::TruncatedRecord { record_name, expected, got }
+                if record_name == "PARAMS" && expected - got == 10
+        ));
+
+        // So is preallocated space at the end of the file.
+        let mut zero_filled = unsmry.clone();
+        zero_filled.resize(unsmry.len() + 4096, 0);
+        std::fs::write(dir.path().join("SPE10.UNSMRY"), zero_filled).unwrap();
+        let summary = read_summary(&dir.path().join("SPE10"));
+        assert_eq!(summary.n_steps(), expected.n_steps());
+
+        // And a last time iteration with a garbled PARAMS header.
+        let mut garbled = unsmry.clone();
+        let params = garbled
+            .windows(8)
+            .rposition(|name| name == b"PARAMS  ")
+            .unwrap();
+        garbled[params + 8..params + 12].copy_from_slice(&i32::MAX.to_be_bytes());
+        std::fs::write(dir.path().join("SPE10.UNSMRY"), garbled).unwrap();
+        let summary = read_summary(&dir.path().join("SPE10"));
+        assert_eq!(summary.n_steps(), expected.n_steps() - 1);
+
+        // The same cut followed by more data is a corrupt file.
+        let mut corrupt = unsmry[..cut].to_vec();
+        corrupt.extend_from_slice(&unsmry[cut - 1000..]);
+        std::fs::write(dir.path().join("SPE10.UNSMRY"), corrupt).unwrap();
+        let err = SummaryFileReader::from_path(dir.path().join("SPE10"))
+            .unwrap()
+            .init()
+            .err()
+            .unwrap();
+        assert!(!err.is_truncation());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn read_gzipped_spe_10() {
+        use flate2::{write::GzEncoder, Compression};
+
+        let dir = tempfile::tempdir().unwrap();
+        let gzip = |ext: &str, len: Option<usize>| {
+            let data = std::fs::read(format!("../assets/SPE10.{}", ext)).unwrap();
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&data).unwrap();
+            let mut compressed = encoder.finish().unwrap();
+            compressed.truncate(len.unwrap_or(compressed.len()));
+            std::fs::write(dir.path().join(format!("SPE10.{}.gz", ext)), compressed).unwrap();
+        };
+        gzip("SMSPEC", None);
+        gzip("UNSMRY", None);
+
+        let expected = read_summary(Path::new("../assets/SPE10"));
+        assert_same_data(&read_summary(&dir.path().join("SPE10")), &expected);
+        assert_same_data(
+            &read_summary(&dir.path().join("SPE10.UNSMRY.gz")),
+            &expected,
+        );
+
+        // A truncated stream can't be rewound, we keep the steps read so far. The updater can't
+        // resume in the middle of a record, so it stops right away.
+        let len = std::fs::metadata(dir.path().join("SPE10.UNSMRY.gz"))
+            .unwrap()
+            .len();
+        gzip("UNSMRY", Some(len as usize / 2));
+        let (summary, mut updater) = SummaryFileReader::from_path(dir.path().join("SPE10"))
+            .unwrap()
+            .init()
+            .unwrap();
+        assert!(summary.n_steps() > 0 && summary.n_steps() < expected.n_steps());
+
+        let (data_snd, data_rcv) = crossbeam_channel::unbounded();
+        let (_term_snd, term_rcv) = crossbeam_channel::bounded(1);
+        assert!(updater.update(data_snd, term_rcv).is_ok());
+        assert!(data_rcv.try_recv().is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn read_mmap() {
+        for path in &["../assets/SPE10", "../assets/SPE10_DOUB"] {
+            let expected = read_summary(Path::new(path));
+            let (summary, _) = unsafe { SummaryFileReader::from_path_mmap(path) }
+                .unwrap()
+                .init()
+                .unwrap();
+            assert_same_data(&summary, &expected);
         }
 
-        let mut summary = Summary::try_from(smspec_records)?;
+        // The mapping follows the file as the time steps are appended, one record at a time.
+        let dir = tempfile::tempdir().unwrap();
+        write_spe_10_case(dir.path(), "LIVE", "", "FGIP", 0..30);
+
+        let (summary, mut updater) =
+            unsafe { SummaryFileReader::from_path_mmap(dir.path().join("LIVE")) }
+                .unwrap()
+                .with_poll_interval(time::Duration::from_millis(10))
+                .init()
+                .unwrap();
+        assert_eq!(summary.n_steps(), 30);
+
+        let (data_snd, data_rcv) = crossbeam_channel::unbounded();
+        let (term_snd, term_rcv) = crossbeam_channel::bounded(1);
+        let updater_thread = std::thread::spawn(move || updater.update(data_snd, term_rcv));
+
+        let records: Vec<Record> = BufReader::new(File::open("../assets/SPE10.UNSMRY").unwrap())
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+        let mut unsmry = std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join("LIVE.UNSMRY"))
+            .unwrap();
+        for record in &records[90..96] {
+            unsmry.write_record(record).unwrap();
+            unsmry.flush().unwrap();
+            std::thread::sleep(time::Duration::from_millis(20));
+        }
+
+        for step in [92, 95] {
+            let params = data_rcv
+                .recv_timeout(std::time::Duration::from_secs(10))
+                .unwrap();
+            assert_eq!(
+                params,
+                Params::F32(records[step].data.as_f32().unwrap().to_vec())
+            );
+        }
+
+        term_snd.send(true).unwrap();
+        updater_thread.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn read_in_chunks() {
+        let smspec = std::fs::read("../assets/SPE10.SMSPEC").unwrap();
+        let unsmry = std::fs::read("../assets/SPE10.UNSMRY").unwrap();
+
+        let progress = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let progress_log = progress.clone();
+        let mut reader = ChunkedSummaryReader::from_smspec_bytes(&smspec)
+            .unwrap()
+            .with_progress_callback(20, move |n_steps| progress_log.borrow_mut().push(n_steps));
+        assert_eq!(reader.item_ids().len(), 34);
 
-        let n_items = summary.items.len();
         let mut n_steps = 0;
+        for chunk in unsmry.chunks(1000) {
+            n_steps += reader.push_unsmry_chunk(chunk).unwrap();
+            assert_eq!(reader.summary().n_steps(), n_steps);
+        }
+        assert_eq!(n_steps, 58);
+        assert_eq!(*progress.borrow(), [20, 40]);
 
-        // Get the current size and don't read data past it (strictly speaking, we can go past by a
-        // fraction of a single UNSMRY triplet length).
-        let unsmry_size = self.unsmry_file.seek(SeekFrom::End(0)).unwrap();
-        let mut unsmry_pos = self.unsmry_file.seek(SeekFrom::Start(0)).unwrap();
+        let spe_10 = read_summary(Path::new("../assets/SPE10"));
+        assert_same_data(&reader.into_summary(), &spe_10);
+    }
 
-        // We store the current file position before the read and try to read as many timestep data
-        // as we can.
-        loop {
-            let params = get_next_params(&mut self.unsmry_file, n_steps, n_items);
+    #[test]
+    fn read_corrupt_chunk() {
+        let smspec = std::fs::read("../assets/SPE10.SMSPEC").unwrap();
+        let mut unsmry = std::fs::read("../assets/SPE10.UNSMRY").unwrap();
+        // Garble the data type of the first record.
+        unsmry[16..20].copy_from_slice(b"XXXX");
 
-            match params {
-                Ok(params) => {
-                    match params {
-                        None => break,
-                        Some((n_bytes, params)) => {
-                            summary.append(params);
-                            n_steps += 1;
-                            unsmry_pos += n_bytes as u64;
-                            // In case we're reading from a file that's still being written to, we stop here
-                            // and continue reading during subsequent updates.
-                            if unsmry_pos >= unsmry_size {
-                                break;
-                            }
-                        }
-                    }
-                }
-                Err(_) => {
-                    self.unsmry_file.seek(SeekFrom::Start(unsmry_pos)).unwrap();
-                    break;
-                }
-            }
+        let mut reader = ChunkedSummaryReader::from_smspec_bytes(&smspec).unwrap();
+        assert!(reader.push_unsmry_chunk(&unsmry[..10]).is_ok());
+        assert!(reader.push_unsmry_chunk(&unsmry[10..1000]).is_err());
+    }
+
+    fn read_summary_with_restarts(path: &Path, max_depth: usize) -> Result<Summary> {
+        Ok(SummaryFileReader::from_path_with_restarts(path, max_depth)?
+            .init()?
+            .0)
+    }
+
+    #[test]
+    fn read_restarted_run() {
+        let dir = tempfile::tempdir().unwrap();
+        write_spe_10_case(dir.path(), "BASE", "", "FGIP", 0..30);
+        write_spe_10_case(dir.path(), "RESTART", "BASE", "FGIP", 25..58);
+
+        let spe_10 = read_summary(Path::new("../assets/SPE10"));
+
+        // Without following restarts only the steps of the restarted run are available.
+        let restarted = read_summary_with_restarts(&dir.path().join("RESTART"), 0).unwrap();
+        assert_eq!(restarted.restart_case.as_deref(), Some("BASE"));
+        assert_eq!(restarted.n_steps(), 33);
+
+        // Overlapping steps of the history are replaced with the ones from the restarted run.
+        let restarted = read_summary_with_restarts(&dir.path().join("RESTART"), 1).unwrap();
+        assert_eq!(restarted.n_steps(), 58);
+        assert_same_data(&restarted, &spe_10);
+    }
+
+    #[test]
+    fn read_restarted_run_with_different_items() {
+        let dir = tempfile::tempdir().unwrap();
+        write_spe_10_case(dir.path(), "BASE", "", "FGIP", 0..30);
+        write_spe_10_case(dir.path(), "RESTART", "BASE", "FGPT", 30..58);
+
+        let summary = read_summary_with_restarts(&dir.path().join("RESTART"), 1).unwrap();
+        assert_eq!(summary.n_steps(), 58);
+        assert_eq!(summary.n_items(), 35);
+
+        let field_item = |name: &str| {
+            let id = ItemId {
+                name: name.into(),
+                qualifier: ItemQualifier::Field,
+            };
+            summary.item_values(summary.item_ids[&id])
+        };
+
+        let fgip = field_item("FGIP");
+        assert!(fgip[..30].iter().all(|v| !v.is_nan()));
+        assert!(fgip[30..].iter().all(|v| v.is_nan()));
+
+        let fgpt = field_item("FGPT");
+        assert!(fgpt[..30].iter().all(|v| v.is_nan()));
+        assert!(fgpt[30..].iter().all(|v| !v.is_nan()));
+    }
+
+    #[test]
+    fn missing_restart_history() {
+        let dir = tempfile::tempdir().unwrap();
+        write_spe_10_case(dir.path(), "RESTART", "BASE", "FGIP", 30..58);
+
+        let summary = read_summary(&dir.path().join("RESTART"));
+        assert_eq!(summary.restart_case.as_deref(), Some("BASE"));
+        assert_eq!(summary.n_steps(), 28);
+    }
+
+    #[test]
+    fn restart_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        write_spe_10_case(dir.path(), "FIRST", "SECOND", "FGIP", 0..30);
+        write_spe_10_case(dir.path(), "SECOND", "FIRST", "FGIP", 30..58);
+
+        assert!(matches!(
+            read_summary_with_restarts(&dir.path().join("SECOND"), 10),
+            Err(EclairError::RestartCycle(_))
+        ));
+    }
+
+    #[test]
+    fn save_spe_10() {
+        let dir = tempfile::tempdir().unwrap();
+        let spe_10 = read_summary(Path::new("../assets/SPE10"));
+        spe_10.save_to_files(dir.path().join("SPE10")).unwrap();
+
+        let saved = read_summary(&dir.path().join("SPE10"));
+        assert_eq!(saved.dims, spe_10.dims);
+        assert_eq!(saved.start_timestamp, spe_10.start_timestamp);
+        assert_eq!(saved.restart_case, None);
+        assert_same_data(&saved, &spe_10);
+        for (id, &index) in &saved.item_ids {
+            assert_eq!(
+                saved.items[index].unit,
+                spe_10.items[spe_10.item_ids[id]].unit
+            );
         }
+    }
 
-        Ok((
-            summary,
-            SummaryFileUpdater {
-                unsmry_file: self.unsmry_file,
-                n_items,
-                n_steps,
-            },
-        ))
+    #[test]
+    fn save_long_well_names() {
+        let records = smspec_records(&[("TIME", "", 0), ("WBHP", "PRODUCER_NORTH", 0)]);
+        let mut summary = Summary::try_from(records).unwrap();
+        summary.units_system = Some(UnitSystem::Metric);
+        summary.append(vec![1.0, 250.0]).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        summary.save_to_files(dir.path().join("LONG")).unwrap();
+
+        let smspec = File::open(dir.path().join("LONG.SMSPEC")).unwrap();
+        let records: Vec<Record> = BufReader::new(smspec)
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+        assert!(records.iter().any(|r| r.name() == "NAMES"));
+        assert!(records.iter().all(|r| r.name() != "WGNAMES"));
+
+        let saved = read_summary(&dir.path().join("LONG"));
+        assert_eq!(saved.units_system, Some(UnitSystem::Metric));
+        assert_same_data(&saved, &summary);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::io::Read;
+    #[test]
+    fn index_spe_10() {
+        let mut index = UnsmryIndex::new("../assets/SPE10.UNSMRY").unwrap();
+        let mut reader = RecordReader::new(BufReader::new(
+            File::open("../assets/SPE10.UNSMRY").unwrap(),
+        ));
 
-    use super::*;
+        let mut all_params = Vec::new();
+        while let Some((_, _, params)) =
+            get_next_params(&mut reader, None, index.n_items()).unwrap()
+        {
+            all_params.push(params);
+        }
+        assert_eq!(index.n_steps(), all_params.len());
+
+        // Time iterations can be read in any order.
+        for step in (0..index.n_steps()).rev() {
+            assert_eq!(
+                index.read_params(step).unwrap().as_ref(),
+                Some(&all_params[step])
+            );
+        }
+        assert_eq!(index.read_params(index.n_steps()).unwrap(), None);
+    }
 
     #[test]
     fn read_spe_10() {
@@ -1,13 +1,25 @@
-use std::{borrow::Cow, collections::HashSet, thread};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread, time,
+};
 
 use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, Select, Sender, TryRecvError};
 
 #[cfg(feature = "read_zmq")]
-use crate::zmq::ZmqConnection;
+use crate::zmq::{ReconnectPolicy, ZmqAuth, ZmqConnection, ZmqSocketKind};
 use crate::{
+    error::EclairError,
+    net::{TcpConnection, DEFAULT_INIT_TIMEOUT},
+    query,
     summary::{
-        InitializeSummary, ItemId, ItemQualifier, Summary, SummaryFileReader, UpdateSummary,
+        InitializeSummary, ItemId, ItemQualifier, Params, Summary, SummaryFileReader, UnitSystem,
+        UpdateSummary, ValuesRef, DEFAULT_POLL_INTERVAL,
     },
     FlexString, Result,
 };
@@ -18,40 +30,224 @@ struct UpdatableSummary {
     updater_thread: thread::JoinHandle<()>,
 
     // To receive data from the updater threads
-    data_rcv: Receiver<Vec<f32>>,
+    data_rcv: Receiver<Params>,
 
     // To signal the threads that they need to terminate.
     term_snd: Sender<bool>,
+
+    // To receive the error an updater thread has stopped with.
+    error_rcv: Receiver<EclairError>,
+
+    // Message of the error the updater thread has stopped with, if any.
+    failure: Option<String>,
+
+    // Whether the updater thread has stopped without an error, e.g. at the end of its source.
+    finished: bool,
+
+    // Whether the data source is connected, for sources that can lose their connection.
+    connected: Option<Arc<AtomicBool>>,
+
+    // Whether the data source has gone quiet, for sources expected to send data regularly.
+    stale: Option<Arc<AtomicBool>>,
+}
+
+/// A view of a single summary item together with its metadata.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(feature = "row_major"), derive(Copy))]
+pub struct ItemView<'a> {
+    /// Name of the summary the item belongs to
+    pub summary_name: &'a str,
+
+    /// Physical unit
+    pub unit: &'a str,
+
+    /// Time series values
+    pub values: ValuesRef<'a>,
+}
+
+/// New time iterations received by a summary during a refresh.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RefreshDelta {
+    pub summary_index: usize,
+    pub new_steps: usize,
+}
+
+/// Outcome of a refresh for a single summary.
+#[derive(Debug)]
+pub enum RefreshStatus {
+    /// The given number of new time iterations has been received.
+    NewData(usize),
+
+    /// Nothing new has been received.
+    NoData,
+
+    /// The updater of the named summary has stopped with an error, or has sent a time iteration of
+    /// the wrong length. Data received before the failure has still been appended, `new_steps`
+    /// counts the time iterations appended by this refresh. The failure is reported once,
+    /// `SummaryManager::health` keeps it, and the summary is not refreshed anymore.
+    SourceFailed {
+        name: String,
+        error: EclairError,
+        new_steps: usize,
+    },
+}
+
+/// Health of the data source behind a summary.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SummarySourceStatus {
+    /// The source is being watched for new data.
+    Active,
+
+    /// The source has lost its connection and is trying to get it back.
+    Disconnected,
+
+    /// The source is connected, but nothing has been received for longer than
+    /// `SummaryManagerConfig::zmq_stale_after`. It becomes active again once data resumes.
+    Stale,
+
+    /// The updater has stopped with the given error message.
+    Failed(String),
+
+    /// The updater has stopped without an error, e.g. at the end of its source. No more data is
+    /// coming.
+    Finished,
+}
+
+/// SummaryManager settings.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SummaryManagerConfig {
+    /// How many time iterations an updater can send ahead of a refresh before it blocks
+    pub channel_capacity: usize,
+
+    /// How many time iterations a refresh takes from a summary at most, so that a source sending
+    /// lots of data doesn't hold up the others. Unlimited if None.
+    pub refresh_batch_size: Option<usize>,
+
+    /// How often file-based summaries are checked for new data
+    pub file_poll_interval: time::Duration,
+
+    /// How long network-based summaries wait for new data before checking whether they should stop
+    pub zmq_poll_interval: time::Duration,
+
+    /// How long TCP-based summaries wait for new data before checking whether they should stop
+    pub tcp_poll_interval: time::Duration,
+
+    /// How long TCP-based summaries wait for the SMSPEC when they are added
+    pub tcp_init_timeout: time::Duration,
+
+    /// How network-based summaries recover from a dropped connection
+    #[cfg(feature = "read_zmq")]
+    pub zmq_reconnect: ReconnectPolicy,
+
+    /// How long a network-based summary can go without receiving anything before it is reported
+    /// as stale. Never if None.
+    #[cfg(feature = "read_zmq")]
+    pub zmq_stale_after: Option<time::Duration>,
+}
+
+impl Default for SummaryManagerConfig {
+    fn default() -> Self {
+        Self {
+            channel_capacity: 10,
+            refresh_batch_size: None,
+            file_poll_interval: DEFAULT_POLL_INTERVAL,
+            zmq_poll_interval: DEFAULT_POLL_INTERVAL,
+            tcp_poll_interval: DEFAULT_POLL_INTERVAL,
+            tcp_init_timeout: DEFAULT_INIT_TIMEOUT,
+            #[cfg(feature = "read_zmq")]
+            zmq_reconnect: ReconnectPolicy::default(),
+            #[cfg(feature = "read_zmq")]
+            zmq_stale_after: None,
+        }
+    }
 }
 
 /// SummaryManager owns all summary data from multiple sources. It can update the data and accept
 /// queries for individual summary item values.
 pub struct SummaryManager {
     summaries: Vec<UpdatableSummary>,
+    config: SummaryManagerConfig,
+}
+
+/// How long to wait for an updater thread to stop before leaving it behind.
+const UPDATER_STOP_TIMEOUT: time::Duration = time::Duration::from_secs(1);
+
+/// Ask the updater threads of the summaries to stop and wait for them, up to
+/// `UPDATER_STOP_TIMEOUT` in total.
+fn stop_updaters(summaries: Vec<UpdatableSummary>) {
+    let threads: Vec<(String, thread::JoinHandle<()>)> = summaries
+        .into_iter()
+        .map(|s| {
+            // This only fails if the updater thread has already stopped with an error.
+            let _ = s.term_snd.send(true);
+            // The data receiver is dropped with the rest of the summary, which also stops an
+            // updater waiting for room in a full channel.
+            (s.name, s.updater_thread)
+        })
+        .collect();
+
+    let deadline = time::Instant::now() + UPDATER_STOP_TIMEOUT;
+    for (name, updater_thread) in threads {
+        while !updater_thread.is_finished() && time::Instant::now() < deadline {
+            thread::sleep(time::Duration::from_millis(1));
+        }
+        if !updater_thread.is_finished() {
+            log::warn!(target: "Summary Manager", "Updater of {} did not stop in time", name);
+        } else if updater_thread.join().is_err() {
+            log::error!(target: "Summary Manager", "Updater of {} has panicked", name);
+        }
+    }
 }
 
 impl SummaryManager {
     pub fn new() -> Self {
+        Self::with_config(SummaryManagerConfig::default())
+    }
+
+    /// Create a manager that applies the given settings to all summaries added to it.
+    pub fn with_config(config: SummaryManagerConfig) -> Self {
         SummaryManager {
             summaries: Vec::new(),
+            config,
         }
     }
 
+    pub fn config(&self) -> &SummaryManagerConfig {
+        &self.config
+    }
+
     pub fn name(&self, index: usize) -> &str {
         self.summaries.get(index).map_or("", |s| s.name.as_str())
     }
 
-    fn add<R: InitializeSummary>(&mut self, name: &str, reader: R) -> Result<()> {
+    /// Index of the summary with the given name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.summaries.iter().position(|s| s.name == name)
+    }
+
+    /// Add a summary data source initialized by the reader, e.g. a `SummaryFileReader` with custom
+    /// settings.
+    pub fn add<R: InitializeSummary>(&mut self, name: &str, reader: R) -> Result<()> {
+        if self.index_of(name).is_some() {
+            return Err(EclairError::DuplicateSummaryName(name.to_string()));
+        }
+
         let (data, mut updater) = reader.init()?;
 
-        // TODO: Once I'm done experimenting, make the channel size a SummaryManager config option.
-        let (data_snd, data_rcv) = crossbeam_channel::bounded(10);
+        let (data_snd, data_rcv) = crossbeam_channel::bounded(self.config.channel_capacity);
 
         let (term_snd, term_rcv) = crossbeam_channel::bounded(1);
 
+        let (error_snd, error_rcv) = crossbeam_channel::bounded(1);
+
+        let connected = updater.connection_flag();
+        let stale = updater.stale_flag();
+
         let updater_thread = thread::spawn(move || {
             if let Err(err) = updater.update(data_snd, term_rcv) {
-                println!("Error during updating: {}", err);
+                log::error!(target: "Summary Manager", "Error during updating: {}", err);
+                // The receiver is gone if the summary has been removed meanwhile.
+                let _ = error_snd.send(err);
             }
         });
 
@@ -61,6 +257,11 @@ impl SummaryManager {
             updater_thread,
             data_rcv,
             term_snd,
+            error_rcv,
+            failure: None,
+            finished: false,
+            connected,
+            stale,
         });
 
         log::info!(target: "Summary Manager", "Added new summary object: {}", name);
@@ -69,23 +270,30 @@ impl SummaryManager {
     }
 
     pub fn remove(&mut self, index: usize) -> Result<()> {
-        // This should not fail unless there's a bug.
-        self.summaries[index]
-            .term_snd
-            .send(true)
-            .expect("Error sending a term request to the summary thread.");
+        if index >= self.summaries.len() {
+            return Err(EclairError::SummaryIndexOutOfRange {
+                index,
+                length: self.summaries.len(),
+            });
+        }
 
         let s = self.summaries.remove(index);
 
         log::info!(target: "Summary Manager", "Removed summary object: {}", s.name);
 
-        s.updater_thread
-            .join()
-            .expect("Error when waiting for the summary thread to join");
+        stop_updaters(vec![s]);
 
         Ok(())
     }
 
+    /// Remove the summary with the given name.
+    pub fn remove_by_name(&mut self, name: &str) -> Result<()> {
+        match self.index_of(name) {
+            Some(index) => self.remove(index),
+            None => Err(EclairError::SummaryNotFound(name.to_string())),
+        }
+    }
+
     pub fn length(&self) -> usize {
         self.summaries.len()
     }
@@ -96,49 +304,225 @@ impl SummaryManager {
         P: AsRef<std::path::Path>,
     {
         let reader = SummaryFileReader::from_path(&input_path)?;
+        self.add_file_reader(input_path.as_ref(), name, reader)
+    }
+
+    /// Same as `add_from_files`, but the summary items are classified with the function first,
+    /// see `SummaryFileReader::with_classifier`.
+    pub fn add_from_files_with_classifier<P, F>(
+        &mut self,
+        input_path: P,
+        name: Option<&str>,
+        classifier: F,
+    ) -> Result<()>
+    where
+        P: AsRef<std::path::Path>,
+        F: Fn(&str, &str, i32) -> Option<ItemQualifier> + Send + Sync + 'static,
+    {
+        let reader = SummaryFileReader::from_path(&input_path)?.with_classifier(classifier);
+        self.add_file_reader(input_path.as_ref(), name, reader)
+    }
+
+    /// Add the reader of the files at the path, named after the file stem by default.
+    fn add_file_reader(
+        &mut self,
+        input_path: &std::path::Path,
+        name: Option<&str>,
+        reader: SummaryFileReader,
+    ) -> Result<()> {
+        let reader = reader.with_poll_interval(self.config.file_poll_interval);
         let name = if let Some(n) = name {
             Cow::Borrowed(n)
         } else {
             // If we get here the file stem exists, so unwrapping if fine.
-            input_path.as_ref().file_stem().unwrap().to_string_lossy()
+            input_path.file_stem().unwrap().to_string_lossy()
         };
 
         self.add(&name, reader)
     }
 
-    /// Add a new ZeroMQ-based summary data source.
+    /// Add a new ZeroMQ-based summary data source. Pass the CURVE keys for a server that requires
+    /// authentication.
     #[cfg(feature = "read_zmq")]
     pub fn add_from_network(
         &mut self,
         server: &str,
         port: i32,
         identity: &str,
+        kind: ZmqSocketKind,
+        auth: Option<&ZmqAuth>,
         name: Option<&str>,
     ) -> Result<()> {
-        let reader = ZmqConnection::new(server, port, identity)?;
-        let name = if let Some(name) = name {
-            name.to_owned()
-        } else {
-            format!("{}:{}", server, port)
+        let name = match (name, &kind) {
+            (Some(name), _) => name.to_owned(),
+            (None, ZmqSocketKind::Dealer) => format!("{}:{}", server, port),
+            (None, ZmqSocketKind::Sub { topic }) => format!("{}:{}/{}", server, port, topic),
+        };
+        let reader = ZmqConnection::new(server, port, identity, kind, auth)?
+            .with_poll_interval(self.config.zmq_poll_interval)
+            .with_stale_after(self.config.zmq_stale_after)
+            .with_reconnect_policy(self.config.zmq_reconnect)?;
+
+        self.add(&name, reader)
+    }
+
+    /// Add a new summary data source receiving the data over a plain TCP connection.
+    pub fn add_from_tcp(&mut self, host: &str, port: u16, name: Option<&str>) -> Result<()> {
+        let name = match name {
+            Some(name) => name.to_owned(),
+            None => format!("{}:{}", host, port),
         };
+        let reader = TcpConnection::new(host, port)?
+            .with_poll_interval(self.config.tcp_poll_interval)
+            .with_init_timeout(self.config.tcp_init_timeout);
 
         self.add(&name, reader)
     }
 
-    /// For each summary it tries to pull values from the corresponding receiver channel.
+    /// For each summary it tries to pull values from the corresponding receiver channel, at most
+    /// `SummaryManagerConfig::refresh_batch_size` time iterations per summary. Returns whether any
+    /// new values have been received.
     pub fn refresh(&mut self) -> Result<bool> {
-        let mut new_values = false;
-        for summary in &mut self.summaries {
-            loop {
-                if let Ok(params) = summary.data_rcv.try_recv() {
-                    new_values = true;
-                    summary.data.append(params);
-                } else {
-                    break;
+        Ok(!self.refresh_deltas()?.is_empty())
+    }
+
+    /// Same as `refresh`, but if nothing new is available yet, it sleeps until any summary
+    /// receives data or the timeout elapses.
+    pub fn refresh_timeout(&mut self, timeout: time::Duration) -> Result<bool> {
+        if self.refresh()? {
+            return Ok(true);
+        }
+        self.wait_for_data(Some(timeout));
+        self.refresh()
+    }
+
+    /// Same as `refresh`, but if nothing new is available yet, it sleeps until any summary
+    /// receives data. Returns false only if no summary is watched for new data anymore.
+    pub fn refresh_blocking(&mut self) -> Result<bool> {
+        loop {
+            if self.refresh()? {
+                return Ok(true);
+            }
+            if !self.wait_for_data(None) {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Sleep until any summary with a running updater receives data or an error, or the timeout
+    /// elapses. The selection is built anew on each call, so it covers all current summaries.
+    /// Returns false if there is no running updater to wait for or the timeout has elapsed.
+    fn wait_for_data(&self, timeout: Option<time::Duration>) -> bool {
+        let mut select = Select::new();
+        let mut n_active = 0;
+        // Channels of stopped updaters are disconnected and would always be ready.
+        for summary in self
+            .summaries
+            .iter()
+            .filter(|s| s.failure.is_none() && !s.finished)
+        {
+            select.recv(&summary.data_rcv);
+            select.recv(&summary.error_rcv);
+            n_active += 1;
+        }
+
+        match timeout {
+            Some(timeout) => select.ready_timeout(timeout).is_ok(),
+            None if n_active > 0 => {
+                select.ready();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Same as `refresh`, but reports which summaries received new time iterations and how many.
+    pub fn refresh_deltas(&mut self) -> Result<Vec<RefreshDelta>> {
+        Ok(self
+            .refresh_status()?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(summary_index, status)| match status {
+                RefreshStatus::NewData(new_steps)
+                | RefreshStatus::SourceFailed { new_steps, .. }
+                    if new_steps > 0 =>
+                {
+                    Some(RefreshDelta {
+                        summary_index,
+                        new_steps,
+                    })
                 }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Same as `refresh`, but reports the outcome for every summary, in the summary order.
+    pub fn refresh_status(&mut self) -> Result<Vec<RefreshStatus>> {
+        let batch_size = self.config.refresh_batch_size.unwrap_or(usize::MAX);
+        let mut statuses = Vec::with_capacity(self.summaries.len());
+        for summary in &mut self.summaries {
+            // The data of a failed source is not trusted anymore.
+            if summary.failure.is_some() {
+                statuses.push(RefreshStatus::NoData);
+                continue;
             }
+
+            let batch: Vec<Params> = summary.data_rcv.try_iter().take(batch_size).collect();
+            let n_received = batch.len();
+            let n_steps = summary.data.n_steps();
+            let appended = summary.data.append_params_batch(batch);
+            let new_steps = summary.data.n_steps() - n_steps;
+
+            // An updater sends its error after all of its data, so it waits while data remains. The
+            // error channel is disconnected without a message once the updater has stopped cleanly.
+            let failure = match appended {
+                Err(error) => Some(error),
+                Ok(()) if n_received < batch_size => match summary.error_rcv.try_recv() {
+                    Ok(error) => Some(error),
+                    Err(TryRecvError::Disconnected) => {
+                        summary.finished = true;
+                        None
+                    }
+                    Err(TryRecvError::Empty) => None,
+                },
+                Ok(()) => None,
+            };
+            statuses.push(if let Some(error) = failure {
+                summary.failure = Some(error.to_string());
+                RefreshStatus::SourceFailed {
+                    name: summary.name.clone(),
+                    error,
+                    new_steps,
+                }
+            } else if new_steps > 0 {
+                RefreshStatus::NewData(new_steps)
+            } else {
+                RefreshStatus::NoData
+            });
         }
-        Ok(new_values)
+        Ok(statuses)
+    }
+
+    /// Health of the data source behind a summary, as of the last refresh. None if there is no
+    /// summary at the index.
+    pub fn health(&self, summary_idx: usize) -> Option<SummarySourceStatus> {
+        let summary = self.summaries.get(summary_idx)?;
+        let load =
+            |flag: &Option<Arc<AtomicBool>>| flag.as_ref().map(|f| f.load(Ordering::Relaxed));
+        Some(
+            match (
+                &summary.failure,
+                load(&summary.connected),
+                load(&summary.stale),
+            ) {
+                (Some(message), _, _) => SummarySourceStatus::Failed(message.clone()),
+                (None, ..) if summary.finished => SummarySourceStatus::Finished,
+                (None, Some(false), _) => SummarySourceStatus::Disconnected,
+                (None, _, Some(true)) => SummarySourceStatus::Stale,
+                _ => SummarySourceStatus::Active,
+            },
+        )
     }
 
     pub fn all_item_ids(&self) -> HashSet<&ItemId> {
@@ -150,24 +534,121 @@ impl SummaryManager {
         ids
     }
 
-    /// Get optional values for an item id from all summary sources.
-    fn get_items_for_id(&self, summary_idx: usize, id: ItemId) -> Option<&[f32]> {
-        self.summaries[summary_idx]
-            .data
+    /// Get an item along with its metadata, if the summary contains it.
+    pub fn get(&self, summary_idx: usize, id: &ItemId) -> Option<ItemView<'_>> {
+        let summary = &self.summaries[summary_idx];
+        summary.data.item_ids.get(id).map(|index| {
+            let item = &summary.data.items[*index];
+            ItemView {
+                summary_name: &summary.name,
+                unit: &item.unit,
+                values: summary.data.item_values(*index),
+            }
+        })
+    }
+
+    /// Get optional values for an item id from a summary source.
+    fn get_items_for_id(&self, summary_idx: usize, id: ItemId) -> Option<ValuesRef<'_>> {
+        self.get(summary_idx, &id).map(|view| view.values)
+    }
+
+    /// Get optional values for an item id from all summary sources, keyed by the summary name.
+    pub fn item_from_all(&self, id: &ItemId) -> HashMap<&str, Option<ValuesRef<'_>>> {
+        (0..self.summaries.len())
+            .map(|idx| (self.name(idx), self.get(idx, id).map(|view| view.values)))
+            .collect()
+    }
+
+    /// Find all items of a summary matching the keyword and qualifier wildcard patterns. See the
+    /// `query` module for the pattern syntax. The items are returned in their SMSPEC order.
+    pub fn query(
+        &self,
+        summary_idx: usize,
+        keyword_glob: &str,
+        qualifier_glob: &str,
+    ) -> Vec<(&ItemId, ValuesRef<'_>)> {
+        let data = &self.summaries[summary_idx].data;
+        let mut matched: Vec<(usize, &ItemId)> = data
             .item_ids
-            .get(&id)
-            .map(|index| {
-                self.summaries[summary_idx].data.items[*index]
-                    .values
-                    .as_slice()
-            })
+            .iter()
+            .filter(|(id, _)| query::matches(id, keyword_glob, qualifier_glob))
+            .map(|(id, &index)| (index, id))
+            .collect();
+        matched.sort_unstable_by_key(|&(index, _)| index);
+
+        matched
+            .into_iter()
+            .map(|(index, id)| (id, data.item_values(index)))
+            .collect()
+    }
+
+    /// Get the physical unit of an item, if the summary contains it.
+    pub fn item_unit(&self, summary_idx: usize, id: &ItemId) -> Option<&str> {
+        self.get(summary_idx, id).map(|view| view.unit)
+    }
+
+    pub fn field_item_unit(&self, summary_idx: usize, name: &str) -> Option<&str> {
+        self.item_unit(
+            summary_idx,
+            &ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::Field,
+            },
+        )
+    }
+
+    pub fn well_item_unit(&self, summary_idx: usize, name: &str, well_name: &str) -> Option<&str> {
+        self.item_unit(
+            summary_idx,
+            &ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::Well {
+                    wg_name: FlexString::from_str(well_name),
+                },
+            },
+        )
+    }
+
+    pub fn group_item_unit(
+        &self,
+        summary_idx: usize,
+        name: &str,
+        group_name: &str,
+    ) -> Option<&str> {
+        self.item_unit(
+            summary_idx,
+            &ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::Group {
+                    wg_name: FlexString::from_str(group_name),
+                },
+            },
+        )
+    }
+
+    pub fn timestamps(&self, summary_idx: usize) -> Option<&[i64]> {
+        Some(self.summaries.get(summary_idx)?.data.timestamps.as_slice())
+    }
+
+    /// Last timestamp and last item values of a summary, see `Summary::latest`.
+    pub fn latest(&self, summary_idx: usize) -> Option<(i64, Vec<(&ItemId, f32)>)> {
+        self.summaries.get(summary_idx)?.data.latest()
+    }
+
+    pub fn units_system(&self, summary_idx: usize) -> Option<UnitSystem> {
+        self.summaries.get(summary_idx)?.data.units_system
     }
 
-    pub fn timestamps(&self, summary_idx: usize) -> &[i64] {
-        self.summaries[summary_idx].data.timestamps.as_slice()
+    pub fn simulator_id(&self, summary_idx: usize) -> Option<i32> {
+        self.summaries.get(summary_idx)?.data.simulator_id
     }
 
-    pub fn time_item<'a>(&'a self, summary_idx: usize, name: &'_ str) -> Option<&[f32]> {
+    /// Grid dimensions of the simulation.
+    pub fn dims(&self, summary_idx: usize) -> Option<[i32; 3]> {
+        Some(self.summaries.get(summary_idx)?.data.dims)
+    }
+
+    pub fn time_item<'a>(&'a self, summary_idx: usize, name: &'_ str) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -177,7 +658,11 @@ impl SummaryManager {
         )
     }
 
-    pub fn performance_item<'a>(&'a self, summary_idx: usize, name: &'_ str) -> Option<&[f32]> {
+    pub fn performance_item<'a>(
+        &'a self,
+        summary_idx: usize,
+        name: &'_ str,
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -187,7 +672,7 @@ impl SummaryManager {
         )
     }
 
-    pub fn field_item<'a>(&'a self, summary_idx: usize, name: &'_ str) -> Option<&[f32]> {
+    pub fn field_item<'a>(&'a self, summary_idx: usize, name: &'_ str) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -202,7 +687,7 @@ impl SummaryManager {
         summary_idx: usize,
         name: &'_ str,
         index: i32,
-    ) -> Option<&[f32]> {
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -217,7 +702,7 @@ impl SummaryManager {
         summary_idx: usize,
         name: &'_ str,
         index: i32,
-    ) -> Option<&[f32]> {
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -232,7 +717,7 @@ impl SummaryManager {
         summary_idx: usize,
         name: &'_ str,
         well_name: &'_ str,
-    ) -> Option<&[f32]> {
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -249,7 +734,7 @@ impl SummaryManager {
         summary_idx: usize,
         name: &'_ str,
         group_name: &'_ str,
-    ) -> Option<&[f32]> {
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -261,12 +746,29 @@ impl SummaryManager {
         )
     }
 
+    pub fn node_item(
+        &self,
+        summary_idx: usize,
+        name: &str,
+        node_name: &str,
+    ) -> Option<ValuesRef<'_>> {
+        self.get_items_for_id(
+            summary_idx,
+            ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::Node {
+                    wg_name: FlexString::from_str(node_name),
+                },
+            },
+        )
+    }
+
     pub fn region_item<'a>(
         &'a self,
         summary_idx: usize,
         name: &'_ str,
         index: i32,
-    ) -> Option<&[f32]> {
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -285,7 +787,7 @@ impl SummaryManager {
         name: &'_ str,
         from: i32,
         to: i32,
-    ) -> Option<&[f32]> {
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -301,7 +803,7 @@ impl SummaryManager {
         name: &'_ str,
         well_name: &'_ str,
         index: i32,
-    ) -> Option<&[f32]> {
+    ) -> Option<ValuesRef<'a>> {
         self.get_items_for_id(
             summary_idx,
             ItemId {
@@ -313,4 +815,703 @@ impl SummaryManager {
             },
         )
     }
+
+    pub fn segment_item(
+        &self,
+        summary_idx: usize,
+        name: &str,
+        well_name: &str,
+        segment: i32,
+    ) -> Option<ValuesRef<'_>> {
+        self.get_items_for_id(
+            summary_idx,
+            ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::Segment {
+                    wg_name: FlexString::from_str(well_name),
+                    index: segment,
+                },
+            },
+        )
+    }
+
+    pub fn local_well_item(
+        &self,
+        summary_idx: usize,
+        name: &str,
+        lgr_name: &str,
+        well_name: &str,
+    ) -> Option<ValuesRef<'_>> {
+        self.get_items_for_id(
+            summary_idx,
+            ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::LocalWell {
+                    lgr: FlexString::from_str(lgr_name),
+                    wg_name: FlexString::from_str(well_name),
+                },
+            },
+        )
+    }
+
+    pub fn local_completion_item(
+        &self,
+        summary_idx: usize,
+        name: &str,
+        lgr_name: &str,
+        well_name: &str,
+        (i, j, k): (i32, i32, i32),
+    ) -> Option<ValuesRef<'_>> {
+        self.get_items_for_id(
+            summary_idx,
+            ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::LocalCompletion {
+                    lgr: FlexString::from_str(lgr_name),
+                    wg_name: FlexString::from_str(well_name),
+                    i,
+                    j,
+                    k,
+                },
+            },
+        )
+    }
+
+    pub fn local_block_item(
+        &self,
+        summary_idx: usize,
+        name: &str,
+        lgr_name: &str,
+        (i, j, k): (i32, i32, i32),
+    ) -> Option<ValuesRef<'_>> {
+        self.get_items_for_id(
+            summary_idx,
+            ItemId {
+                name: FlexString::from_str(name),
+                qualifier: ItemQualifier::LocalBlock {
+                    lgr: FlexString::from_str(lgr_name),
+                    i,
+                    j,
+                    k,
+                },
+            },
+        )
+    }
+}
+
+impl Drop for SummaryManager {
+    fn drop(&mut self) {
+        stop_updaters(std::mem::take(&mut self.summaries));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::{ReadRecord, Record, WriteRecord};
+    use std::{fs::File, io::BufReader};
+
+    /// A source that sends a number of time iterations and then fails.
+    #[derive(Default)]
+    struct FailingSource {
+        n_steps: usize,
+        n_items: usize,
+    }
+
+    impl UpdateSummary for FailingSource {
+        fn update(&mut self, data_snd: Sender<Params>, _: Receiver<bool>) -> Result<()> {
+            for _ in 0..self.n_steps {
+                let _ = data_snd.send(Params::F32(vec![0.0; self.n_items]));
+            }
+            Err(EclairError::ReadRecordClosed)
+        }
+    }
+
+    impl InitializeSummary for FailingSource {
+        type Updater = Self;
+
+        fn init(mut self) -> Result<(Summary, Self::Updater)> {
+            let summary = Summary::open("../assets/SPE10")?;
+            self.n_items = summary.n_items();
+            Ok((summary, self))
+        }
+    }
+
+    #[test]
+    fn failed_source() {
+        let mut manager = SummaryManager::new();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+        manager.add("broken", FailingSource::default()).unwrap();
+        assert_eq!(manager.health(1), Some(SummarySourceStatus::Active));
+
+        let mut failure = None;
+        for _ in 0..200 {
+            let mut statuses = manager.refresh_status().unwrap();
+            assert!(matches!(statuses[0], RefreshStatus::NoData));
+            if let RefreshStatus::SourceFailed { .. } = statuses[1] {
+                failure = statuses.pop();
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(matches!(
+            failure,
+            Some(RefreshStatus::SourceFailed {
+                name,
+                error: EclairError::ReadRecordClosed,
+                new_steps: 0,
+            }) if name == "broken"
+        ));
+
+        let message = EclairError::ReadRecordClosed.to_string();
+        assert_eq!(
+            manager.health(1),
+            Some(SummarySourceStatus::Failed(message))
+        );
+        assert_eq!(manager.health(0), Some(SummarySourceStatus::Active));
+        assert!(matches!(
+            manager.refresh_status().unwrap()[1],
+            RefreshStatus::NoData
+        ));
+        manager.remove_by_name("broken").unwrap();
+    }
+
+    #[test]
+    fn data_before_failure() {
+        let mut manager = SummaryManager::new();
+        let source = FailingSource {
+            n_steps: 3,
+            ..FailingSource::default()
+        };
+        manager.add("broken", source).unwrap();
+        let n_steps = manager.timestamps(0).unwrap().len();
+
+        // The updater has sent everything once its error arrives.
+        while !manager.summaries[0].updater_thread.is_finished() {
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+        let statuses = manager.refresh_status().unwrap();
+        assert!(matches!(
+            statuses[0],
+            RefreshStatus::SourceFailed { new_steps: 3, .. }
+        ));
+        assert_eq!(manager.timestamps(0).unwrap().len(), n_steps + 3);
+        assert!(manager.refresh_deltas().unwrap().is_empty());
+    }
+
+    /// A source that sends a time iteration of the wrong length between two valid ones.
+    #[derive(Default)]
+    struct InvalidRowSource {
+        n_items: usize,
+    }
+
+    impl UpdateSummary for InvalidRowSource {
+        fn update(&mut self, data_snd: Sender<Params>, _: Receiver<bool>) -> Result<()> {
+            for n_items in [self.n_items, 1, self.n_items] {
+                let _ = data_snd.send(Params::F32(vec![0.0; n_items]));
+            }
+            Ok(())
+        }
+    }
+
+    impl InitializeSummary for InvalidRowSource {
+        type Updater = Self;
+
+        fn init(mut self) -> Result<(Summary, Self::Updater)> {
+            let summary = Summary::open("../assets/SPE10")?;
+            self.n_items = summary.n_items();
+            Ok((summary, self))
+        }
+    }
+
+    #[test]
+    fn invalid_row() {
+        let mut manager = SummaryManager::new();
+        manager.add("invalid", InvalidRowSource::default()).unwrap();
+        manager.add("finished", FinishedSource).unwrap();
+        let n_steps = manager.timestamps(0).unwrap().len();
+
+        for summary in &manager.summaries {
+            while !summary.updater_thread.is_finished() {
+                thread::sleep(std::time::Duration::from_millis(1));
+            }
+        }
+
+        // The valid time iteration is kept and the other summaries are still refreshed.
+        let statuses = manager.refresh_status().unwrap();
+        assert!(matches!(
+            &statuses[0],
+            RefreshStatus::SourceFailed {
+                error: EclairError::UnexpectedRecordDataLength { found: 1, .. },
+                new_steps: 1,
+                ..
+            }
+        ));
+        assert!(matches!(statuses[1], RefreshStatus::NoData));
+        assert_eq!(manager.timestamps(0).unwrap().len(), n_steps + 1);
+        assert!(matches!(
+            manager.health(0),
+            Some(SummarySourceStatus::Failed(_))
+        ));
+        assert_eq!(manager.health(1), Some(SummarySourceStatus::Finished));
+    }
+
+    /// A source that stops right away without an error.
+    struct FinishedSource;
+
+    impl UpdateSummary for FinishedSource {
+        fn update(&mut self, _: Sender<Params>, _: Receiver<bool>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl InitializeSummary for FinishedSource {
+        type Updater = Self;
+
+        fn init(self) -> Result<(Summary, Self::Updater)> {
+            Ok((Summary::open("../assets/SPE10")?, self))
+        }
+    }
+
+    #[test]
+    fn finished_source() {
+        let mut manager = SummaryManager::new();
+        manager.add("finished", FinishedSource).unwrap();
+
+        // The finished source is not waited for, so this returns instead of spinning.
+        assert!(!manager.refresh_blocking().unwrap());
+        assert_eq!(manager.health(0), Some(SummarySourceStatus::Finished));
+        assert!(!manager
+            .refresh_timeout(time::Duration::from_millis(10))
+            .unwrap());
+    }
+
+    /// A source that sends nothing and exposes its connection and staleness flags.
+    #[derive(Default)]
+    struct QuietSource {
+        connected: Arc<AtomicBool>,
+        stale: Arc<AtomicBool>,
+    }
+
+    impl UpdateSummary for QuietSource {
+        fn update(&mut self, _: Sender<Params>, term_rcv: Receiver<bool>) -> Result<()> {
+            let _ = term_rcv.recv();
+            Ok(())
+        }
+
+        fn connection_flag(&self) -> Option<Arc<AtomicBool>> {
+            Some(self.connected.clone())
+        }
+
+        fn stale_flag(&self) -> Option<Arc<AtomicBool>> {
+            Some(self.stale.clone())
+        }
+    }
+
+    impl InitializeSummary for QuietSource {
+        type Updater = Self;
+
+        fn init(self) -> Result<(Summary, Self::Updater)> {
+            Ok((Summary::open("../assets/SPE10")?, self))
+        }
+    }
+
+    #[test]
+    fn stale_source() {
+        let source = QuietSource::default();
+        let (connected, stale) = (source.connected.clone(), source.stale.clone());
+        connected.store(true, Ordering::Relaxed);
+
+        let mut manager = SummaryManager::new();
+        manager.add("quiet", source).unwrap();
+        assert_eq!(manager.health(0), Some(SummarySourceStatus::Active));
+
+        stale.store(true, Ordering::Relaxed);
+        assert_eq!(manager.health(0), Some(SummarySourceStatus::Stale));
+
+        // Losing the connection takes precedence over going quiet.
+        connected.store(false, Ordering::Relaxed);
+        assert_eq!(manager.health(0), Some(SummarySourceStatus::Disconnected));
+
+        connected.store(true, Ordering::Relaxed);
+        stale.store(false, Ordering::Relaxed);
+        assert_eq!(manager.health(0), Some(SummarySourceStatus::Active));
+    }
+
+    /// Create the LIVE case in the directory from the SPE10 SMSPEC and the first records of its
+    /// UNSMRY. Returns the open UNSMRY file and all SPE10 UNSMRY records.
+    fn live_case(dir: &std::path::Path, n_records: usize) -> (File, Vec<Record>) {
+        let records: Vec<Record> = BufReader::new(File::open("../assets/SPE10.UNSMRY").unwrap())
+            .records()
+            .map(|r| r.unwrap())
+            .collect();
+
+        std::fs::copy("../assets/SPE10.SMSPEC", dir.join("LIVE.SMSPEC")).unwrap();
+        let mut unsmry = File::create(dir.join("LIVE.UNSMRY")).unwrap();
+        for record in &records[..n_records] {
+            unsmry.write_record(record).unwrap();
+        }
+        (unsmry, records)
+    }
+
+    #[test]
+    fn refresh_deltas() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut unsmry, records) = live_case(dir.path(), 90);
+
+        let mut manager = SummaryManager::new();
+        manager
+            .add_from_files(dir.path().join("LIVE"), None)
+            .unwrap();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+        assert_eq!(manager.timestamps(0).unwrap().len(), 30);
+
+        for record in &records[90..96] {
+            unsmry.write_record(record).unwrap();
+        }
+
+        let mut new_steps = 0;
+        let start = std::time::Instant::now();
+        while new_steps < 2 && start.elapsed() < std::time::Duration::from_secs(10) {
+            for delta in manager.refresh_deltas().unwrap() {
+                assert_eq!(delta.summary_index, 0);
+                new_steps += delta.new_steps;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(new_steps, 2);
+        assert_eq!(manager.timestamps(0).unwrap().len(), 32);
+        assert!(!manager.refresh().unwrap());
+    }
+
+    #[test]
+    fn refresh_batch_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut unsmry, records) = live_case(dir.path(), 90);
+
+        let mut manager = SummaryManager::with_config(SummaryManagerConfig {
+            refresh_batch_size: Some(1),
+            ..SummaryManagerConfig::default()
+        });
+        manager
+            .add_from_files(dir.path().join("LIVE"), None)
+            .unwrap();
+        for record in &records[90..99] {
+            unsmry.write_record(record).unwrap();
+        }
+
+        let mut refreshes = 0;
+        let start = std::time::Instant::now();
+        while manager.timestamps(0).unwrap().len() < 33
+            && start.elapsed() < time::Duration::from_secs(10)
+        {
+            for delta in manager.refresh_deltas().unwrap() {
+                assert_eq!(delta.new_steps, 1);
+                refreshes += 1;
+            }
+            thread::sleep(time::Duration::from_millis(10));
+        }
+        assert_eq!(refreshes, 3);
+        assert_eq!(manager.timestamps(0).unwrap().len(), 33);
+    }
+
+    #[test]
+    fn refresh_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut unsmry, records) = live_case(dir.path(), 90);
+
+        let mut manager = SummaryManager::with_config(SummaryManagerConfig {
+            file_poll_interval: time::Duration::from_millis(10),
+            ..SummaryManagerConfig::default()
+        });
+        assert!(!manager.refresh_blocking().unwrap());
+        manager
+            .add_from_files(dir.path().join("LIVE"), None)
+            .unwrap();
+
+        let start = std::time::Instant::now();
+        assert!(!manager
+            .refresh_timeout(time::Duration::from_millis(50))
+            .unwrap());
+        assert!(start.elapsed() >= time::Duration::from_millis(50));
+
+        for record in &records[90..93] {
+            unsmry.write_record(record).unwrap();
+        }
+        assert!(manager.refresh_blocking().unwrap());
+        assert_eq!(manager.timestamps(0).unwrap().len(), 31);
+
+        manager.add("broken", FailingSource::default()).unwrap();
+        while manager.health(1) == Some(SummarySourceStatus::Active) {
+            manager
+                .refresh_timeout(time::Duration::from_secs(1))
+                .unwrap();
+        }
+        manager.remove(0).unwrap();
+        assert!(!manager.refresh_blocking().unwrap());
+    }
+
+    #[test]
+    fn drop_with_full_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut unsmry, records) = live_case(dir.path(), 90);
+
+        let mut manager = SummaryManager::with_config(SummaryManagerConfig {
+            channel_capacity: 1,
+            file_poll_interval: time::Duration::from_millis(10),
+            ..SummaryManagerConfig::default()
+        });
+        manager
+            .add_from_files(dir.path().join("LIVE"), None)
+            .unwrap();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+
+        // Let the updater fill the channel and block on sending the rest.
+        for record in &records[90..] {
+            unsmry.write_record(record).unwrap();
+        }
+        thread::sleep(time::Duration::from_millis(100));
+
+        let start = std::time::Instant::now();
+        drop(manager);
+        assert!(start.elapsed() < UPDATER_STOP_TIMEOUT);
+    }
+
+    #[test]
+    fn small_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut unsmry, records) = live_case(dir.path(), 90);
+
+        let config = SummaryManagerConfig {
+            channel_capacity: 1,
+            file_poll_interval: time::Duration::from_millis(10),
+            ..SummaryManagerConfig::default()
+        };
+        let mut manager = SummaryManager::with_config(config.clone());
+        assert_eq!(manager.config(), &config);
+        manager
+            .add_from_files(dir.path().join("LIVE"), None)
+            .unwrap();
+
+        // The updater has to wait for refreshes to send all new time iterations.
+        for record in &records[90..] {
+            unsmry.write_record(record).unwrap();
+        }
+        let start = std::time::Instant::now();
+        while manager.timestamps(0).unwrap().len() < 58
+            && start.elapsed() < time::Duration::from_secs(10)
+        {
+            manager.refresh().unwrap();
+            thread::sleep(time::Duration::from_millis(1));
+        }
+        assert_eq!(manager.timestamps(0).unwrap().len(), 58);
+    }
+
+    #[test]
+    fn item_from_all_summaries() {
+        let mut manager = SummaryManager::new();
+        manager
+            .add_from_files("../assets/SPE10", Some("first"))
+            .unwrap();
+        manager
+            .add_from_files("../assets/SPE10", Some("second"))
+            .unwrap();
+
+        let foip = ItemId {
+            name: FlexString::from_str("FOIP"),
+            qualifier: ItemQualifier::Field,
+        };
+        let values = manager.item_from_all(&foip);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["first"], manager.field_item(0, "FOIP"));
+        assert_eq!(values["second"].as_ref().map(|v| v.len()), Some(58));
+
+        let fopt = ItemId {
+            name: FlexString::from_str("FOPT"),
+            qualifier: ItemQualifier::Field,
+        };
+        assert!(manager.item_from_all(&fopt).values().all(Option::is_none));
+
+        manager.remove(1).unwrap();
+        manager.remove(0).unwrap();
+    }
+
+    #[test]
+    fn remove_summary_by_name() {
+        let mut manager = SummaryManager::new();
+        manager
+            .add_from_files("../assets/SPE10", Some("first"))
+            .unwrap();
+        manager
+            .add_from_files("../assets/SPE10", Some("second"))
+            .unwrap();
+
+        manager.remove_by_name("first").unwrap();
+        assert_eq!(manager.length(), 1);
+        assert_eq!(manager.name(0), "second");
+
+        assert!(matches!(
+            manager.remove_by_name("first"),
+            Err(EclairError::SummaryNotFound(_))
+        ));
+
+        manager.remove_by_name("second").unwrap();
+        assert_eq!(manager.length(), 0);
+    }
+
+    #[test]
+    fn unique_summary_names() {
+        let mut manager = SummaryManager::new();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+        assert!(matches!(
+            manager.add_from_files("../assets/SPE10", Some("SPE10")),
+            Err(EclairError::DuplicateSummaryName(_))
+        ));
+        manager
+            .add_from_files("../assets/SPE10", Some("copy"))
+            .unwrap();
+
+        assert_eq!(manager.index_of("copy"), Some(1));
+        assert_eq!(manager.index_of("missing"), None);
+
+        assert!(matches!(
+            manager.remove(2),
+            Err(EclairError::SummaryIndexOutOfRange {
+                index: 2,
+                length: 2
+            })
+        ));
+        manager.remove(0).unwrap();
+        assert_eq!(manager.index_of("copy"), Some(0));
+    }
+
+    #[test]
+    fn item_view() {
+        let mut manager = SummaryManager::new();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+
+        let wbhp = ItemId {
+            name: FlexString::from_str("WBHP"),
+            qualifier: ItemQualifier::Well {
+                wg_name: FlexString::from_str("P1"),
+            },
+        };
+        let view = manager.get(0, &wbhp).unwrap();
+        assert_eq!(view.summary_name, "SPE10");
+        assert_eq!(view.unit, "PSIA");
+        assert_eq!(Some(view.values), manager.well_item(0, "WBHP", "P1"));
+
+        manager.remove(0).unwrap();
+    }
+
+    #[test]
+    fn long_well_names() {
+        let mut manager = SummaryManager::new();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+        manager
+            .add_from_files("../assets/SPE10_LONG", None)
+            .unwrap();
+
+        let wbhp = manager.well_item(1, "WBHP", "P1_WELL_NAME");
+        assert!(wbhp.is_some());
+        assert_eq!(wbhp, manager.well_item(0, "WBHP", "P1"));
+        assert_eq!(manager.well_item(1, "WBHP", "P1_WELL_"), None);
+        assert_eq!(manager.query(1, "WBHP", "P?_WELL_NAME").len(), 4);
+    }
+
+    #[test]
+    fn query_items() {
+        let mut manager = SummaryManager::new();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+
+        assert_eq!(manager.query(0, "WOPR", "").len(), 5);
+        assert_eq!(manager.query(0, "wbhp", "P?").len(), 4);
+        assert_eq!(manager.query(0, "F*", "").len(), 3);
+
+        let bpr = manager.query(0, "BPR", "120910");
+        assert_eq!(bpr.len(), 1);
+        assert_eq!(
+            bpr[0].1[..],
+            manager.block_item(0, "BPR", 120910).unwrap()[..]
+        );
+
+        assert!(manager.query(0, "WOPR", "I2").is_empty());
+
+        manager.remove(0).unwrap();
+    }
+
+    #[test]
+    fn custom_classifier() {
+        // Treat the BRS items as region items, leaving the rest to the default classification.
+        let reader = SummaryFileReader::from_path("../assets/SPE10")
+            .unwrap()
+            .with_classifier(|name, _, index| match name {
+                "BRS" => Some(ItemQualifier::Region {
+                    wg_name: None,
+                    index,
+                }),
+                _ => None,
+            });
+        let mut manager = SummaryManager::new();
+        manager.add("SPE10_CUSTOM", reader).unwrap();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+
+        assert_eq!(manager.block_item(0, "BRS", 120910), None);
+        assert_eq!(
+            manager.region_item(0, "BRS", 120910),
+            manager.block_item(1, "BRS", 120910)
+        );
+        assert!(manager.region_item(0, "BRS", 120910).is_some());
+        assert_eq!(manager.query(0, "BRS", "197450").len(), 1);
+        assert_eq!(
+            manager.block_item(0, "BPR", 120910),
+            manager.block_item(1, "BPR", 120910)
+        );
+    }
+
+    #[test]
+    fn add_from_files_with_classifier() {
+        // Keywords unknown to the default classification can be given a qualifier.
+        let mut manager = SummaryManager::new();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+        manager
+            .add_from_files_with_classifier(
+                "../assets/SPE10",
+                Some("SPE10_WELLS"),
+                |name, _, index| match name {
+                    "BPR" => Some(ItemQualifier::Well {
+                        wg_name: FlexString::from(format!("CELL_{}", index).as_str()),
+                    }),
+                    _ => None,
+                },
+            )
+            .unwrap();
+        assert!(manager.well_item(1, "BPR", "CELL_120910").is_some());
+        assert_eq!(
+            manager.well_item(1, "BPR", "CELL_120910"),
+            manager.block_item(0, "BPR", 120910)
+        );
+    }
+
+    #[test]
+    fn item_units() {
+        let mut manager = SummaryManager::new();
+        manager.add_from_files("../assets/SPE10", None).unwrap();
+
+        assert_eq!(manager.well_item_unit(0, "WBHP", "P1"), Some("PSIA"));
+        assert_eq!(manager.well_item_unit(0, "WBHP", "P9"), None);
+        assert_eq!(manager.field_item_unit(0, "FOPT"), None);
+        assert_eq!(
+            manager.item_unit(
+                0,
+                &ItemId {
+                    name: FlexString::from_str("TIME"),
+                    qualifier: ItemQualifier::Time,
+                }
+            ),
+            Some("DAYS")
+        );
+
+        manager.remove(0).unwrap();
+    }
 }
@@ -0,0 +1,339 @@
+//! Export of summary data to CSV files in a self-describing layout that mirrors the SMSPEC
+//! metadata. The first column holds the row labels, followed by a column per item. The four
+//! header rows hold the item keywords, well or group names, integer indices and units, e.g.
+//!
+//! ```text
+//! KEYWORD,BPR,...,WBHP
+//! WGNAME,,...,P1
+//! NUM,120910,...,0
+//! UNIT,PSIA,...,PSIA
+//! 2005-03-01T00:00:00,2167.7368,...,0
+//! ```
+//!
+//! Alternatively, `CsvHeader::ItemNames` writes a single header row of full item names and units,
+//! e.g. `WBHP:P1 [PSIA]`, which is the layout of `Summary::write_csv`.
+//!
+//! All items are exported by default, sorted by `ItemId`. Full-field models have tens of thousands
+//! of items, so the export can be restricted to the items matching a list of `KEYWORD:QUALIFIER`
+//! patterns, e.g. `TIME`, `FOPR` and `WOPR:*`. Columns then follow the order of the patterns.
+//!
+//! Data rows are labelled with the datetime of their time iteration, either in ISO 8601 or as unix
+//! seconds. The datetime is the simulation start from STARTDAT plus the TIME item.
+
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use chrono::DateTime;
+
+use crate::{
+    query,
+    summary::{ItemId, ItemQualifier, ItemValues, Summary, UNKNOWN_WG_NAME},
+    Result,
+};
+
+/// How the datetimes of time iterations are written.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DateFormat {
+    /// E.g. `2005-03-01T00:00:00`
+    Iso8601,
+    /// Seconds since the unix epoch
+    UnixSeconds,
+}
+
+/// Layout of the header of a CSV export.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CsvHeader {
+    /// Four labelled rows of item keywords, well or group names, integer indices and units
+    Smspec,
+    /// A single row of full item names with the unit in brackets, e.g. `WBHP:P1 [PSIA]`
+    ItemNames,
+}
+
+/// CSV export settings.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub date_format: DateFormat,
+
+    pub header: CsvHeader,
+
+    /// Patterns of the exported items in the `KEYWORD:QUALIFIER` notation of
+    /// `query::matches_pattern`. All items are exported if empty.
+    pub columns: Vec<String>,
+
+    /// Skip the simulator performance items, e.g. `TCPU` or `NEWTON`.
+    pub exclude_performance: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            date_format: DateFormat::Iso8601,
+            header: CsvHeader::Smspec,
+            columns: vec![],
+            exclude_performance: false,
+        }
+    }
+}
+
+/// Outcome of a CSV export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvExport {
+    /// Number of data rows written
+    pub n_rows: usize,
+
+    /// Column patterns that matched no items
+    pub unmatched_columns: Vec<String>,
+}
+
+/// Items to export, in the column order, and the patterns that matched nothing.
+fn select_columns<'a>(
+    summary: &'a Summary,
+    opts: &CsvOptions,
+) -> (Vec<(&'a ItemId, usize)>, Vec<String>) {
+    let mut ids: Vec<(&ItemId, usize)> = summary
+        .columns()
+        .filter(|(id, _)| !(opts.exclude_performance && id.qualifier == ItemQualifier::Performance))
+        .collect();
+    ids.sort_unstable();
+
+    if opts.columns.is_empty() {
+        return (ids, vec![]);
+    }
+
+    let mut selected = Vec::new();
+    let mut unmatched = Vec::new();
+    let mut taken = vec![false; ids.len()];
+    for pattern in &opts.columns {
+        let mut matched = false;
+        for (i, &(id, index)) in ids.iter().enumerate() {
+            if query::matches_pattern(id, pattern) {
+                matched = true;
+                if !taken[i] {
+                    taken[i] = true;
+                    selected.push((id, index));
+                }
+            }
+        }
+        if !matched {
+            unmatched.push(pattern.clone());
+        }
+    }
+    (selected, unmatched)
+}
+
+/// Quote a CSV field if it contains separators or quotes.
+fn csv_field(field: String) -> String {
+    if field.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Write a data row per time iteration, labelled by its datetime. Values are written in their
+/// stored precision. Returns the number of rows written.
+fn write_csv_rows<W: Write>(
+    out: &mut W,
+    timestamps: &[i64],
+    date_format: DateFormat,
+    values: &[&ItemValues],
+) -> Result<usize> {
+    let doubles: Vec<Option<Vec<f64>>> = values
+        .iter()
+        .map(|values| {
+            if values.is_double_precision() {
+                Some(values.as_f64_vec())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    for (step, &ts) in timestamps.iter().enumerate() {
+        let mut row = vec![match date_format {
+            DateFormat::Iso8601 => DateTime::from_timestamp(ts, 0)
+                .expect("invalid or out-of-range datetime")
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string(),
+            DateFormat::UnixSeconds => ts.to_string(),
+        }];
+        for (values, double) in values.iter().zip(&doubles) {
+            row.push(match double {
+                Some(double) => double[step].to_string(),
+                None => values.as_f32_slice()[step].to_string(),
+            });
+        }
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(timestamps.len())
+}
+
+/// Write the four header rows with the SMSPEC description of the items.
+fn write_smspec_header<W: Write>(
+    summary: &Summary,
+    out: &mut W,
+    ids: &[(&ItemId, usize)],
+) -> Result<()> {
+    let mut keywords = vec!["KEYWORD".to_string()];
+    let mut wg_names = vec!["WGNAME".to_string()];
+    let mut nums = vec!["NUM".to_string()];
+    let mut units = vec!["UNIT".to_string()];
+    for (id, index) in ids {
+        let (wg_name, num) = id.smspec_fields();
+        keywords.push(csv_field(id.name.to_string()));
+        wg_names.push(if wg_name == UNKNOWN_WG_NAME {
+            String::new()
+        } else {
+            csv_field(wg_name.to_string())
+        });
+        nums.push(num.to_string());
+        units.push(csv_field(summary.items[*index].unit.to_string()));
+    }
+    for row in &[keywords, wg_names, nums, units] {
+        writeln!(out, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+/// Write a single header row of full item names and units.
+fn write_item_names_header<W: Write>(
+    summary: &Summary,
+    out: &mut W,
+    ids: &[(&ItemId, usize)],
+) -> Result<()> {
+    let mut header = vec!["DATE".to_string()];
+    for (id, index) in ids {
+        let unit = &summary.items[*index].unit;
+        header.push(if unit.is_empty() {
+            csv_field(id.to_string())
+        } else {
+            csv_field(format!("{} [{}]", id, unit))
+        });
+    }
+    writeln!(out, "{}", header.join(","))?;
+    Ok(())
+}
+
+/// Write the summary to CSV.
+pub fn to_csv_to_writer<W: Write>(
+    summary: &Summary,
+    mut out: W,
+    opts: &CsvOptions,
+) -> Result<CsvExport> {
+    let (ids, unmatched_columns) = select_columns(summary, opts);
+
+    match opts.header {
+        CsvHeader::Smspec => write_smspec_header(summary, &mut out, &ids)?,
+        CsvHeader::ItemNames => write_item_names_header(summary, &mut out, &ids)?,
+    }
+
+    let columns: Vec<Cow<'_, ItemValues>> = ids
+        .iter()
+        .map(|(_, index)| summary.item_column(*index))
+        .collect();
+    let values: Vec<&ItemValues> = columns.iter().map(|values| values.as_ref()).collect();
+    let n_rows = write_csv_rows(&mut out, &summary.timestamps, opts.date_format, &values)?;
+    Ok(CsvExport {
+        n_rows,
+        unmatched_columns,
+    })
+}
+
+/// Read the summary of a case and write it to a CSV file.
+pub fn to_csv_to_path<P, Q>(input_path: P, output_path: Q, opts: &CsvOptions) -> Result<CsvExport>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let summary = Summary::open(input_path)?;
+    let mut out = BufWriter::new(File::create(output_path)?);
+    let export = to_csv_to_writer(&summary, &mut out, opts)?;
+    out.flush()?;
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spe_10_to_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("SPE10.csv");
+        let export = to_csv_to_path("../assets/SPE10", &output_path, &CsvOptions::default());
+        assert_eq!(
+            export.unwrap(),
+            CsvExport {
+                n_rows: 58,
+                unmatched_columns: vec![],
+            }
+        );
+
+        let csv = std::fs::read_to_string(output_path).unwrap();
+        let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+        assert_eq!(rows.len(), 4 + 58);
+        assert!(rows.iter().all(|row| row.len() == 35));
+
+        assert_eq!(rows[0][..4], ["KEYWORD", "BPR", "BPR", "BRS"]);
+        assert_eq!(rows[1][..2], ["WGNAME", ""]);
+        assert_eq!(rows[2][..3], ["NUM", "120910", "197450"]);
+        assert_eq!(rows[3][..2], ["UNIT", "PSIA"]);
+
+        let wbhp = rows[0].iter().position(|&kw| kw == "WBHP").unwrap();
+        assert_eq!(rows[1][wbhp], "I1");
+        assert_eq!(
+            rows[4][..3],
+            ["2005-03-01T00:00:00", "2167.7368", "2179.2222"]
+        );
+    }
+
+    #[test]
+    fn unix_seconds() {
+        let summary = Summary::open("../assets/SPE10").unwrap();
+        let opts = CsvOptions {
+            date_format: DateFormat::UnixSeconds,
+            ..CsvOptions::default()
+        };
+        let mut csv = Vec::new();
+        to_csv_to_writer(&summary, &mut csv, &opts).unwrap();
+
+        let csv = String::from_utf8(csv).unwrap();
+        let dates: Vec<&str> = csv
+            .lines()
+            .skip(4)
+            .map(|line| line.split(',').next().unwrap())
+            .collect();
+        assert_eq!(dates[..2], ["1109635200", "1109721600"]);
+    }
+
+    #[test]
+    fn selected_columns() {
+        let summary = Summary::open("../assets/SPE10").unwrap();
+        let opts = CsvOptions {
+            columns: vec![
+                "TIME".to_string(),
+                "FOIP".to_string(),
+                "WOPR:P*".to_string(),
+                "WOPR:P1".to_string(),
+                "GOPR:*".to_string(),
+            ],
+            ..CsvOptions::default()
+        };
+        let mut csv = Vec::new();
+        let export = to_csv_to_writer(&summary, &mut csv, &opts).unwrap();
+        assert_eq!(export.n_rows, 58);
+        assert_eq!(export.unmatched_columns, ["GOPR:*"]);
+
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "KEYWORD,TIME,FOIP,WOPR,WOPR,WOPR,WOPR"
+        );
+        assert_eq!(lines.next().unwrap(), "WGNAME,,,P1,P2,P3,P4");
+    }
+}
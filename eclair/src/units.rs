@@ -0,0 +1,317 @@
+//! Conversion of summary values between the units of the Eclipse unit systems.
+//!
+//! Units are given by their Eclipse spelling, e.g. `BARSA`, `PSIA` or `STB/DAY`. A unit is either
+//! simple or a simple unit divided by one or more simple units, e.g. `SM3/DAY/BARS`. Pressures,
+//! surface and reservoir volumes, times, temperatures, lengths and masses are supported.
+//!
+//! Metric, PVT-M and lab units measure gas and liquid surface volumes the same way, while field
+//! units use `MSCF` for gas and `STB` for liquids. When converting to field units, the phase is
+//! taken from the item keyword, e.g. `FGPR` or `WGOR`.
+
+use std::collections::HashSet;
+
+use crate::{
+    summary::{ItemQualifier, Summary, UnitSystem},
+    FlexString,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Dimension {
+    Pressure,
+    PressureDifference,
+    SurfaceVolume,
+    ReservoirVolume,
+    Time,
+    Temperature,
+    Length,
+    Mass,
+}
+
+/// Simple units with their dimension, scale and offset relative to the base unit of the
+/// dimension, i.e. `base = value * scale + offset`.
+const UNITS: &[(&str, Dimension, f64, f64)] = &[
+    ("BARSA", Dimension::Pressure, 1e5, 0.0),
+    ("PSIA", Dimension::Pressure, 6894.757293168, 0.0),
+    ("ATMA", Dimension::Pressure, 101325.0, 0.0),
+    ("BARS", Dimension::PressureDifference, 1e5, 0.0),
+    ("BAR", Dimension::PressureDifference, 1e5, 0.0),
+    ("PSI", Dimension::PressureDifference, 6894.757293168, 0.0),
+    ("ATM", Dimension::PressureDifference, 101325.0, 0.0),
+    ("SM3", Dimension::SurfaceVolume, 1.0, 0.0),
+    ("STB", Dimension::SurfaceVolume, 0.158987294928, 0.0),
+    ("MSCF", Dimension::SurfaceVolume, 28.316846592, 0.0),
+    ("SCC", Dimension::SurfaceVolume, 1e-6, 0.0),
+    ("RM3", Dimension::ReservoirVolume, 1.0, 0.0),
+    ("RB", Dimension::ReservoirVolume, 0.158987294928, 0.0),
+    ("RCC", Dimension::ReservoirVolume, 1e-6, 0.0),
+    ("DAY", Dimension::Time, 1.0, 0.0),
+    ("DAYS", Dimension::Time, 1.0, 0.0),
+    ("HR", Dimension::Time, 1.0 / 24.0, 0.0),
+    ("HOURS", Dimension::Time, 1.0 / 24.0, 0.0),
+    ("YEARS", Dimension::Time, 365.25, 0.0),
+    ("K", Dimension::Temperature, 1.0, 0.0),
+    ("C", Dimension::Temperature, 1.0, 273.15),
+    ("F", Dimension::Temperature, 5.0 / 9.0, 459.67 * 5.0 / 9.0),
+    ("R", Dimension::Temperature, 5.0 / 9.0, 0.0),
+    ("M", Dimension::Length, 1.0, 0.0),
+    ("CM", Dimension::Length, 0.01, 0.0),
+    ("FT", Dimension::Length, 0.3048, 0.0),
+    ("KG", Dimension::Mass, 1.0, 0.0),
+    ("G", Dimension::Mass, 1e-3, 0.0),
+    ("LB", Dimension::Mass, 0.45359237, 0.0),
+];
+
+fn simple_unit(unit: &str) -> Option<(Dimension, f64, f64)> {
+    UNITS
+        .iter()
+        .find(|(name, ..)| *name == unit)
+        .map(|&(_, dimension, scale, offset)| (dimension, scale, offset))
+}
+
+/// Split a unit into its numerator and denominators, e.g. `SM3/DAY/BARS` into `SM3` and
+/// `[DAY, BARS]`. The unit is divided by all the denominators.
+fn split_unit(unit: &str) -> (&str, Vec<&str>) {
+    let mut parts = unit.split('/');
+    // A split always yields at least one part.
+    let numerator = parts.next().unwrap();
+    (numerator, parts.collect())
+}
+
+/// Scale and offset of the linear map from one simple unit to another.
+fn simple_map(from: &str, to: &str) -> Option<(f64, f64)> {
+    let (from_dim, from_scale, from_offset) = simple_unit(from)?;
+    let (to_dim, to_scale, to_offset) = simple_unit(to)?;
+    if from_dim != to_dim {
+        return None;
+    }
+    Some((from_scale / to_scale, (from_offset - to_offset) / to_scale))
+}
+
+/// Scale and offset of the linear map from one unit to another. Offsets only apply to simple
+/// units, since a ratio of temperatures is a ratio of differences.
+fn linear_map(from: &str, to: &str) -> Option<(f64, f64)> {
+    let ((from_num, from_dens), (to_num, to_dens)) = (split_unit(from), split_unit(to));
+    if from_dens.len() != to_dens.len() {
+        return None;
+    }
+    if from_dens.is_empty() {
+        return simple_map(from_num, to_num);
+    }
+
+    let (num_scale, _) = simple_map(from_num, to_num)?;
+    let den_scale = from_dens
+        .iter()
+        .zip(&to_dens)
+        .map(|(from, to)| simple_map(from, to).map(|(scale, _)| scale))
+        .product::<Option<f64>>()?;
+    Some((num_scale / den_scale, 0.0))
+}
+
+/// Convert a value from one unit to another, e.g. from `BARSA` to `PSIA` or from `SM3/DAY` to
+/// `STB/DAY`. None if either of the units is unknown or they measure different quantities.
+pub fn convert(value: f32, from: &str, to: &str) -> Option<f32> {
+    let (scale, offset) = linear_map(from, to)?;
+    Some((value as f64 * scale + offset) as f32)
+}
+
+/// Whether a surface volume in the unit of an item refers to gas, judging by the item keyword,
+/// e.g. FGPR or WGIT, and gas ratios, e.g. GOR or OGR.
+fn is_gas_volume(keyword: &str, numerator: bool) -> bool {
+    let quantity = keyword.get(1..).unwrap_or("");
+    if quantity.starts_with("GOR") || quantity.starts_with("GLR") {
+        numerator
+    } else if quantity.starts_with("OGR") || quantity.starts_with("WGR") {
+        !numerator
+    } else {
+        numerator && quantity.starts_with('G')
+    }
+}
+
+/// Simple unit of the target system measuring the same quantity.
+fn simple_unit_in(
+    unit: &str,
+    keyword: &str,
+    numerator: bool,
+    target: UnitSystem,
+) -> Option<&'static str> {
+    use UnitSystem::*;
+
+    let (dimension, ..) = simple_unit(unit)?;
+    Some(match (dimension, target) {
+        (Dimension::Pressure, Metric) => "BARSA",
+        (Dimension::Pressure, Field) => "PSIA",
+        (Dimension::Pressure, Lab) | (Dimension::Pressure, PvtM) => "ATMA",
+        (Dimension::PressureDifference, Metric) => "BARS",
+        (Dimension::PressureDifference, Field) => "PSI",
+        (Dimension::PressureDifference, Lab) | (Dimension::PressureDifference, PvtM) => "ATM",
+        (Dimension::SurfaceVolume, Metric) | (Dimension::SurfaceVolume, PvtM) => "SM3",
+        (Dimension::SurfaceVolume, Field) => {
+            let is_gas = match unit {
+                "MSCF" => true,
+                "STB" => false,
+                _ => is_gas_volume(keyword, numerator),
+            };
+            if is_gas {
+                "MSCF"
+            } else {
+                "STB"
+            }
+        }
+        (Dimension::SurfaceVolume, Lab) => "SCC",
+        (Dimension::ReservoirVolume, Metric) | (Dimension::ReservoirVolume, PvtM) => "RM3",
+        (Dimension::ReservoirVolume, Field) => "RB",
+        (Dimension::ReservoirVolume, Lab) => "RCC",
+        (Dimension::Time, Lab) => "HR",
+        (Dimension::Time, _) => "DAY",
+        (Dimension::Temperature, Field) => "F",
+        (Dimension::Temperature, _) => "C",
+        (Dimension::Length, Metric) | (Dimension::Length, PvtM) => "M",
+        (Dimension::Length, Field) => "FT",
+        (Dimension::Length, Lab) => "CM",
+        (Dimension::Mass, Metric) | (Dimension::Mass, PvtM) => "KG",
+        (Dimension::Mass, Field) => "LB",
+        (Dimension::Mass, Lab) => "G",
+    })
+}
+
+/// Unit of the target system measuring the same quantity as the unit of an item.
+fn unit_in(unit: &str, keyword: &str, target: UnitSystem) -> Option<String> {
+    let (num, dens) = split_unit(unit);
+    let mut unit = simple_unit_in(num, keyword, true, target)?.to_string();
+    for den in dens {
+        unit.push('/');
+        unit.push_str(simple_unit_in(den, keyword, false, target)?);
+    }
+    Some(unit)
+}
+
+impl Summary {
+    /// A copy of the summary with all item values converted to the target unit system. Time items
+    /// keep their units, since they define the timestamps. Items with unknown units are left
+    /// untouched.
+    pub fn to_unit_system(&self, target: UnitSystem) -> Summary {
+        let mut summary = self.clone();
+        let mut unknown_units = HashSet::new();
+
+        for (id, index) in self.columns() {
+            let item = &mut summary.items[index];
+            if id.qualifier == ItemQualifier::Time || item.unit.is_empty() {
+                continue;
+            }
+
+            let converted = unit_in(&item.unit, &id.name, target)
+                .and_then(|unit| Some((linear_map(&item.unit, &unit)?, unit)));
+            match converted {
+                Some(((scale, offset), unit)) => {
+                    item.unit = FlexString::from(unit.as_str());
+                    summary.map_item_values(index, |value| value * scale + offset);
+                }
+                None => {
+                    if unknown_units.insert(item.unit.clone()) {
+                        log::warn!(target: "Units", "Unknown unit {} is left unconverted", item.unit);
+                    }
+                }
+            }
+        }
+
+        summary.units_system = Some(target);
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::summary::ItemId;
+
+    fn assert_close(value: Option<f32>, expected: f32) {
+        let value = value.unwrap();
+        assert!(
+            (value - expected).abs() <= expected.abs() * 1e-5,
+            "{} != {}",
+            value,
+            expected
+        );
+    }
+
+    #[test]
+    fn convert_units() {
+        assert_close(convert(1.0, "BARSA", "PSIA"), 14.503774);
+        assert_close(convert(1.0, "SM3/DAY", "STB/DAY"), 6.2898108);
+        assert_close(convert(1.0, "MSCF/STB", "SM3/SM3"), 178.1076);
+        assert_close(convert(100.0, "C", "F"), 212.0);
+        assert_close(convert(32.0, "F", "K"), 273.15);
+        assert_close(convert(24.0, "SCC/HR", "SCC/DAY"), 576.0);
+        assert_close(convert(1.0, "SM3/DAY/BARS", "STB/DAY/PSI"), 0.4336672);
+
+        assert_eq!(convert(1.0, "BARSA", "BARS"), None);
+        assert_eq!(convert(1.0, "SM3", "RM3"), None);
+        assert_eq!(convert(1.0, "SM3/DAY", "STB"), None);
+        assert_eq!(convert(1.0, "SM3/DAY/BARS", "STB/DAY"), None);
+        assert_eq!(convert(1.0, "PSIA", "FURLONG"), None);
+    }
+
+    #[test]
+    fn target_units() {
+        use UnitSystem::*;
+
+        assert_eq!(unit_in("SM3/DAY", "FGPR", Field).unwrap(), "MSCF/DAY");
+        assert_eq!(unit_in("SM3/DAY", "WOPR", Field).unwrap(), "STB/DAY");
+        assert_eq!(unit_in("SM3/SM3", "WGOR", Field).unwrap(), "MSCF/STB");
+        assert_eq!(unit_in("SM3/SM3", "FWGR", Field).unwrap(), "STB/MSCF");
+        assert_eq!(unit_in("MSCF/STB", "WGOR", Lab).unwrap(), "SCC/SCC");
+        assert_eq!(unit_in("RB/DAY", "FVPR", Metric).unwrap(), "RM3/DAY");
+        assert_eq!(
+            unit_in("SM3/DAY/BARS", "WPI", Field).unwrap(),
+            "STB/DAY/PSI"
+        );
+        assert_eq!(unit_in("FURLONG", "FOPR", Metric), None);
+    }
+
+    #[test]
+    fn spe_10_to_metric() {
+        let summary = Summary::open("../assets/SPE10").unwrap();
+        let metric = summary.to_unit_system(UnitSystem::Metric);
+        assert_eq!(metric.units_system, Some(UnitSystem::Metric));
+        assert_eq!(metric.timestamps, summary.timestamps);
+
+        let item = |summary: &Summary, name: &str, qualifier: ItemQualifier| {
+            let id = ItemId {
+                name: FlexString::from(name),
+                qualifier,
+            };
+            summary.items[summary.item_ids[&id]].clone()
+        };
+        let well = || ItemQualifier::Well {
+            wg_name: FlexString::from("P1"),
+        };
+
+        let (wbhp, metric_wbhp) = (
+            item(&summary, "WBHP", well()),
+            item(&metric, "WBHP", well()),
+        );
+        assert_eq!(wbhp.unit, "PSIA");
+        assert_eq!(metric_wbhp.unit, "BARSA");
+        let wbhp_at_10 = |summary: &Summary| {
+            let id = ItemId {
+                name: FlexString::from("WBHP"),
+                qualifier: well(),
+            };
+            summary.item_values(summary.item_ids[&id])[10]
+        };
+        assert_close(
+            Some(wbhp_at_10(&metric)),
+            wbhp_at_10(&summary) * 0.068947573,
+        );
+
+        assert_eq!(item(&metric, "WGOR", well()).unit, "SM3/SM3");
+        assert_eq!(
+            item(&metric, "TIME", ItemQualifier::Time).unit,
+            item(&summary, "TIME", ItemQualifier::Time).unit
+        );
+
+        let field = metric.to_unit_system(UnitSystem::Field);
+        assert_eq!(item(&field, "WGOR", well()).unit, "MSCF/STB");
+        assert_eq!(item(&field, "WOPR", well()).unit, "STB/DAY");
+    }
+}
@@ -1,37 +1,141 @@
 use std::{
-    collections::HashMap,
-    convert::{From, TryFrom, TryInto},
-    thread::sleep,
-    time::Duration,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use crossbeam_channel::{Receiver, Sender};
-use serde::Deserialize;
 
 use crate::{
-    binary_parsing::{read_f32, read_i32},
+    binary_parsing::{read_f32, read_i32, ByteOrder},
     error::EclairError,
-    records::RecordData,
-    summary::{InitializeSummary, SmspecRecords, Summary, UpdateSummary},
-    FlexString, Result,
+    net::summary_from_smspec_json,
+    summary::{InitializeSummary, Params, Summary, UpdateSummary, DEFAULT_POLL_INTERVAL},
+    Result,
 };
 
+/// How a ZeroMQ connection recovers from a dropped connection. ZeroMQ reconnects by itself,
+/// waiting `interval` before the first attempt and doubling the wait up to `max_interval`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ReconnectPolicy {
+    pub interval: Duration,
+    pub max_interval: Duration,
+
+    /// Give up after this many failed attempts in a row. Retries forever if None.
+    pub max_attempts: Option<usize>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(5),
+            max_attempts: None,
+        }
+    }
+}
+
+/// The kind of the ZeroMQ socket to receive summary data with.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ZmqSocketKind {
+    /// A DEALER socket that requests the data from the server. The server sends the SMSPEC JSON in
+    /// reply to an empty message, followed by the time iterations.
+    Dealer,
+
+    /// A SUB socket receiving the data broadcast by a PUB server under the given topic. Every
+    /// message starts with a topic frame. The server is expected to retain the SMSPEC JSON and
+    /// send it to new subscribers first.
+    Sub { topic: String },
+}
+
+/// CurveZMQ keys for an encrypted and authenticated connection, all Z85-encoded (40 characters).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ZmqAuth {
+    pub server_public_key: String,
+    pub client_public_key: String,
+    pub client_secret_key: String,
+}
+
+impl ZmqAuth {
+    /// Make the socket a CURVE client. Keys that do not decode to 32 bytes are rejected upfront.
+    fn apply(&self, sock: &zmq::Socket) -> Result<()> {
+        if zmq::has("curve") != Some(true) {
+            return Err(EclairError::ZeroMqAuthFailed(
+                "libzmq is built without CURVE support".to_owned(),
+            ));
+        }
+
+        let decode = |key: &str, what: &str| match zmq::z85_decode(key) {
+            Ok(key) if key.len() == 32 => Ok(key),
+            _ => Err(EclairError::ZeroMqAuthFailed(format!(
+                "invalid {} key",
+                what
+            ))),
+        };
+        sock.set_curve_serverkey(&decode(&self.server_public_key, "server public")?)?;
+        sock.set_curve_publickey(&decode(&self.client_public_key, "client public")?)?;
+        sock.set_curve_secretkey(&decode(&self.client_secret_key, "client secret")?)?;
+        Ok(())
+    }
+}
+
+/// Map a failed handshake reported by the connection monitor to an error.
+fn handshake_error(event: zmq::SocketEvent) -> Option<EclairError> {
+    use zmq::SocketEvent::*;
+
+    let reason = match event {
+        HANDSHAKE_FAILED_AUTH => "the server rejected the client key",
+        HANDSHAKE_FAILED_PROTOCOL => "the security handshake failed",
+        HANDSHAKE_FAILED_NO_DETAIL => "the handshake failed",
+        _ => return None,
+    };
+    Some(EclairError::ZeroMqAuthFailed(reason.to_owned()))
+}
+
 /// Encapsulation of the ZeroMQ monitored connection. The field order is important, because member
 /// variables has custom Drop implementations.
 pub struct ZmqConnection {
     monitor: zmq::Socket,
     sock: zmq::Socket,
     ctx: zmq::Context,
+    poll_interval: Duration,
+    reconnect: ReconnectPolicy,
+    connected: Arc<AtomicBool>,
+    stale_after: Option<Duration>,
+    stale: Arc<AtomicBool>,
+    kind: ZmqSocketKind,
+    curve: bool,
 }
 
 impl ZmqConnection {
     /// Creates a new ZeroMQ-based connection to the server. Expects the server address, the port
-    /// number and the identity for the underlying socket. The socket type is fixed to be of the
-    /// DEALER type.
-    pub fn new(server: &str, port: i32, identity: &str) -> Result<Self> {
+    /// number, the identity for the underlying socket, the socket kind and, for a CURVE server,
+    /// the keys to authenticate with.
+    pub fn new(
+        server: &str,
+        port: i32,
+        identity: &str,
+        kind: ZmqSocketKind,
+        auth: Option<&ZmqAuth>,
+    ) -> Result<Self> {
         let ctx = zmq::Context::new();
-        let sock = ctx.socket(zmq::DEALER)?;
+        let sock = match &kind {
+            ZmqSocketKind::Dealer => ctx.socket(zmq::DEALER)?,
+            ZmqSocketKind::Sub { topic } => {
+                let sock = ctx.socket(zmq::SUB)?;
+                sock.set_subscribe(topic.as_bytes())?;
+                sock
+            }
+        };
         sock.set_identity(identity.as_bytes())?;
+        // Drop unsent messages on close, otherwise terminating the context blocks until they are
+        // delivered, which never happens once the server is gone.
+        sock.set_linger(0)?;
+        if let Some(auth) = auth {
+            auth.apply(&sock)?;
+        }
 
         // Connect to the server.
         let address = format!("tcp://{}:{}", server, port);
@@ -41,12 +145,96 @@ impl ZmqConnection {
         // Setup the connection monitor socket.
         sock.monitor(
             "inproc://monitor-client",
-            zmq::SocketEvent::DISCONNECTED as i32,
+            (zmq::SocketEvent::CONNECTED as i32)
+                | (zmq::SocketEvent::CONNECT_RETRIED as i32)
+                | (zmq::SocketEvent::DISCONNECTED as i32)
+                | (zmq::SocketEvent::HANDSHAKE_SUCCEEDED as i32)
+                | (zmq::SocketEvent::HANDSHAKE_FAILED_NO_DETAIL as i32)
+                | (zmq::SocketEvent::HANDSHAKE_FAILED_PROTOCOL as i32)
+                | (zmq::SocketEvent::HANDSHAKE_FAILED_AUTH as i32),
         )?;
         let monitor = ctx.socket(zmq::PAIR)?;
         monitor.connect("inproc://monitor-client")?;
 
-        Ok(ZmqConnection { monitor, sock, ctx })
+        ZmqConnection {
+            monitor,
+            sock,
+            ctx,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            reconnect: ReconnectPolicy::default(),
+            connected: Arc::new(AtomicBool::new(true)),
+            stale_after: None,
+            stale: Arc::new(AtomicBool::new(false)),
+            kind,
+            curve: auth.is_some(),
+        }
+        .with_reconnect_policy(ReconnectPolicy::default())
+    }
+
+    /// Set how the connection recovers after the server goes away.
+    pub fn with_reconnect_policy(self, reconnect: ReconnectPolicy) -> Result<Self> {
+        self.sock
+            .set_reconnect_ivl(reconnect.interval.as_millis() as i32)?;
+        self.sock
+            .set_reconnect_ivl_max(reconnect.max_interval.as_millis() as i32)?;
+        Ok(Self { reconnect, ..self })
+    }
+
+    /// Receive the next message without the topic frame of a SUB socket. None if the message is
+    /// for another topic that merely starts with ours.
+    fn recv_payload(&self) -> Result<Option<Vec<Vec<u8>>>> {
+        let mut msg = self.sock.recv_multipart(0)?;
+        match &self.kind {
+            ZmqSocketKind::Dealer => Ok(Some(msg)),
+            ZmqSocketKind::Sub { topic } => {
+                if msg.first().map(|frame| frame.as_slice()) != Some(topic.as_bytes()) {
+                    return Ok(None);
+                }
+                msg.remove(0);
+                Ok(Some(msg))
+            }
+        }
+    }
+
+    /// Receive the next event from the connection monitor.
+    fn recv_monitor_event(&self) -> Result<Option<zmq::SocketEvent>> {
+        use zmq::SocketEvent::*;
+
+        let msg = self.monitor.recv_multipart(0)?;
+        let event = match msg.first() {
+            Some(frame) if frame.len() >= 2 => u16::from_ne_bytes([frame[0], frame[1]]),
+            _ => return Ok(None),
+        };
+        Ok([
+            CONNECTED,
+            CONNECT_RETRIED,
+            DISCONNECTED,
+            HANDSHAKE_SUCCEEDED,
+            HANDSHAKE_FAILED_NO_DETAIL,
+            HANDSHAKE_FAILED_PROTOCOL,
+            HANDSHAKE_FAILED_AUTH,
+        ]
+        .iter()
+        .copied()
+        .find(|e| e.to_raw() == event))
+    }
+
+    /// Set how long the updater waits for new data before checking whether it should stop.
+    /// Defaults to `DEFAULT_POLL_INTERVAL`.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Consider the server stale once nothing has been received for the given time. Never if None,
+    /// which is the default.
+    pub fn with_stale_after(self, stale_after: Option<Duration>) -> Self {
+        Self {
+            stale_after,
+            ..self
+        }
     }
 
     pub fn send<T>(&self, data: T, flags: i32) -> Result<()>
@@ -75,33 +263,98 @@ pub struct ZmqUpdater {
 }
 
 impl UpdateSummary for ZmqUpdater {
-    fn update(&mut self, data_snd: Sender<Vec<f32>>, term_rcv: Receiver<bool>) -> Result<()> {
+    fn update(&mut self, data_snd: Sender<Params>, term_rcv: Receiver<bool>) -> Result<()> {
         let mut items = [
             self.conn.monitor.as_poll_item(zmq::POLLIN),
             self.conn.sock.as_poll_item(zmq::POLLIN),
         ];
 
-        let mut is_connected = true;
+        let mut failed_attempts = 0;
+        let mut last_received = Instant::now();
         loop {
-            // First check if we were instructed to stop.
-            if let Ok(_) = term_rcv.try_recv() {
+            // First check if we were instructed to stop. The sockets are closed when the updater
+            // is dropped along with its thread.
+            if term_rcv.try_recv().is_ok() {
+                log::debug!(target: "Updating Summary", "Stopping the ZeroMQ updater");
                 return Ok(());
             }
 
-            zmq::poll(&mut items, 0)?;
+            // Wait for the next message, but not past the next term check or the moment the
+            // server goes stale.
+            let mut timeout = self.conn.poll_interval;
+            if let Some(stale_after) = self.conn.stale_after {
+                let silence = last_received.elapsed();
+                if silence >= stale_after {
+                    if !self.conn.stale.swap(true, Ordering::Relaxed) {
+                        log::warn!(target: "Updating Summary", "Nothing received for {:?}", silence);
+                    }
+                } else {
+                    timeout = timeout.min(stale_after - silence);
+                }
+            }
+
+            zmq::poll(&mut items, timeout.as_millis().max(1) as i64)?;
 
-            if is_connected && items[0].is_readable() {
-                eprintln!("Detected ZeroMQ socket disconnect.");
-                is_connected = false;
-                //return Err(EclairError::ZeroMqSocketDisconnected);
+            if items[0].is_readable() {
+                match self.conn.recv_monitor_event()? {
+                    Some(zmq::SocketEvent::DISCONNECTED) => {
+                        log::warn!(target: "Updating Summary", "Detected ZeroMQ socket disconnect");
+                        self.conn.connected.store(false, Ordering::Relaxed);
+                    }
+                    Some(zmq::SocketEvent::CONNECT_RETRIED) => {
+                        failed_attempts += 1;
+                        if matches!(self.conn.reconnect.max_attempts, Some(max) if failed_attempts > max)
+                        {
+                            return Err(EclairError::ZeroMqSocketDisconnected);
+                        }
+                    }
+                    Some(zmq::SocketEvent::CONNECTED)
+                        if !self.conn.connected.load(Ordering::Relaxed) =>
+                    {
+                        // Tell the restarted server how many time iterations we already have. It
+                        // either resumes from there or resends everything. A SUB socket cannot
+                        // send, a publisher simply carries on with its broadcast.
+                        log::info!(target: "Updating Summary", "Reconnected at step {}", self.n_steps);
+                        failed_attempts = 0;
+                        if self.conn.kind == ZmqSocketKind::Dealer {
+                            self.conn
+                                .sock
+                                .send(&(self.n_steps as i32).to_be_bytes()[..], 0)?;
+                        }
+                        self.conn.connected.store(true, Ordering::Relaxed);
+                    }
+                    Some(event) => {
+                        if let Some(err) = handshake_error(event) {
+                            return Err(err);
+                        }
+                    }
+                    None => {}
+                }
             }
 
             if items[1].is_readable() {
-                is_connected = true;
-                let msg = self.conn.sock.recv_multipart(0)?;
+                last_received = Instant::now();
+                if self.conn.stale.swap(false, Ordering::Relaxed) {
+                    log::info!(target: "Updating Summary", "Receiving data again");
+                }
 
-                // Make sure the time iteration is correct.
-                let current_step = read_i32(msg[0].as_slice()) as usize;
+                // A server resending everything starts with the SMSPEC, which we already have.
+                let msg = match self.conn.recv_payload()? {
+                    Some(msg) if msg.len() >= 2 => msg,
+                    _ => continue,
+                };
+
+                // Make sure the time iteration is correct. Steps received before a reconnect are
+                // skipped.
+                let current_step = read_i32(msg[0].as_slice(), ByteOrder::BigEndian) as usize;
+                if current_step < self.n_steps {
+                    continue;
+                }
+                // A subscriber only gets the time iterations broadcast after it has joined.
+                if self.n_steps == 0 && self.conn.kind != ZmqSocketKind::Dealer {
+                    log::info!(target: "Updating Summary", "Joined the broadcast at step {}", current_step);
+                    self.n_steps = current_step;
+                }
                 if current_step != self.n_steps {
                     return Err(EclairError::InvalidMinistepValue {
                         expected: self.n_steps,
@@ -111,7 +364,7 @@ impl UpdateSummary for ZmqUpdater {
 
                 let params: Vec<f32> = msg[1]
                     .chunks_exact(std::mem::size_of::<f32>())
-                    .map(|chunk| read_f32(chunk))
+                    .map(|chunk| read_f32(chunk, ByteOrder::BigEndian))
                     .collect();
 
                 if params.len() != self.n_items {
@@ -124,51 +377,20 @@ impl UpdateSummary for ZmqUpdater {
 
                 self.n_steps += 1;
 
-                if data_snd.send(params).is_err() {
+                if data_snd.send(Params::F32(params)).is_err() {
                     log::debug!(target: "Updating Summary", "Error while sending params over a channel");
                     return Ok(());
                 }
             }
-
-            sleep(Duration::from_millis(100));
         }
     }
-}
-
-#[derive(Deserialize)]
-struct SmspecJson {
-    #[serde(rename = "DIMENS")]
-    dimens: Vec<i32>,
-
-    #[serde(rename = "KEYWORDS")]
-    keywords: Vec<FlexString>,
-
-    #[serde(rename = "NAMES")]
-    names: Vec<FlexString>,
 
-    #[serde(rename = "NUMS")]
-    nums: Vec<i32>,
-
-    #[serde(rename = "STARTDAT")]
-    start_date: Vec<i32>,
-
-    #[serde(rename = "UNITS")]
-    units: Vec<FlexString>,
-}
-
-impl From<SmspecJson> for SmspecRecords {
-    fn from(smspec_json: SmspecJson) -> Self {
-        use RecordData::*;
-
-        let mut records = HashMap::new();
-        records.insert("DIMENS", Some(Int(smspec_json.dimens)));
-        records.insert("STARTDAT", Some(Int(smspec_json.start_date)));
-        records.insert("KEYWORDS", Some(Chars(smspec_json.keywords)));
-        records.insert("WGNAMES", Some(Chars(smspec_json.names)));
-        records.insert("NUMS", Some(Int(smspec_json.nums)));
-        records.insert("UNITS", Some(Chars(smspec_json.units)));
+    fn connection_flag(&self) -> Option<Arc<AtomicBool>> {
+        Some(self.conn.connected.clone())
+    }
 
-        SmspecRecords::new(records)
+    fn stale_flag(&self) -> Option<Arc<AtomicBool>> {
+        self.conn.stale_after.map(|_| self.conn.stale.clone())
     }
 }
 
@@ -176,8 +398,10 @@ impl InitializeSummary for ZmqConnection {
     type Updater = ZmqUpdater;
 
     fn init(self) -> Result<(Summary, Self::Updater)> {
-        // Initial handshake.
-        self.sock.send("", 0)?;
+        // Initial handshake. A publisher sends the SMSPEC unasked.
+        if self.kind == ZmqSocketKind::Dealer {
+            self.sock.send("", 0)?;
+        }
 
         // receive SMSPEC first
         let mut items = [
@@ -185,24 +409,41 @@ impl InitializeSummary for ZmqConnection {
             self.sock.as_poll_item(zmq::POLLIN),
         ];
 
-        let smspec_json: SmspecJson = loop {
+        let mut handshake_succeeded = false;
+        let summary = loop {
             zmq::poll(&mut items, 0)?;
 
             if items[0].is_readable() {
-                return Err(EclairError::ZeroMqSocketDisconnected);
+                match self.recv_monitor_event()? {
+                    // A CURVE server drops clients that use the wrong server key without a reply.
+                    Some(zmq::SocketEvent::DISCONNECTED) if self.curve && !handshake_succeeded => {
+                        return Err(EclairError::ZeroMqAuthFailed(
+                            "the server closed the connection during the handshake".to_owned(),
+                        ));
+                    }
+                    Some(zmq::SocketEvent::DISCONNECTED) => {
+                        return Err(EclairError::ZeroMqSocketDisconnected);
+                    }
+                    Some(zmq::SocketEvent::HANDSHAKE_SUCCEEDED) => handshake_succeeded = true,
+                    Some(event) => {
+                        if let Some(err) = handshake_error(event) {
+                            return Err(err);
+                        }
+                    }
+                    None => {}
+                }
             }
 
             if items[1].is_readable() {
-                let json = self.sock.recv_msg(0)?;
-                match json.as_str() {
-                    None => return Err(EclairError::InvalidSmspecJson),
-                    Some(v) => break serde_json::from_str(v)?,
+                // A subscriber may see time iterations before the SMSPEC, which are skipped.
+                let json = match self.recv_payload()? {
+                    Some(mut msg) if msg.len() == 1 => msg.remove(0),
+                    _ => continue,
                 };
+                break summary_from_smspec_json(&json)?;
             }
         };
 
-        let smspec_records = SmspecRecords::from(smspec_json);
-        let summary = Summary::try_from(smspec_records)?;
         let n_items = summary.n_items();
 
         Ok((
@@ -215,3 +456,179 @@ impl InitializeSummary for ZmqConnection {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::SMSPEC_JSON;
+    use std::thread;
+
+    /// Bind a CURVE server socket on a random port. Returns the socket, its port and public key.
+    fn curve_server(ctx: &zmq::Context) -> (zmq::Socket, i32, String) {
+        let keys = zmq::CurveKeyPair::new().unwrap();
+        let sock = ctx.socket(zmq::ROUTER).unwrap();
+        sock.set_curve_server(true).unwrap();
+        sock.set_curve_secretkey(&keys.secret_key).unwrap();
+        sock.bind("tcp://127.0.0.1:*").unwrap();
+
+        let endpoint = sock.get_last_endpoint().unwrap().unwrap();
+        let port = endpoint.rsplit(':').next().unwrap().parse().unwrap();
+        (sock, port, zmq::z85_encode(&keys.public_key).unwrap())
+    }
+
+    fn client_auth(server_public_key: String) -> ZmqAuth {
+        let keys = zmq::CurveKeyPair::new().unwrap();
+        ZmqAuth {
+            server_public_key,
+            client_public_key: zmq::z85_encode(&keys.public_key).unwrap(),
+            client_secret_key: zmq::z85_encode(&keys.secret_key).unwrap(),
+        }
+    }
+
+    #[test]
+    fn curve_auth() {
+        let ctx = zmq::Context::new();
+        let (server, port, server_key) = curve_server(&ctx);
+
+        let auth = client_auth(server_key);
+        let conn = ZmqConnection::new(
+            "127.0.0.1",
+            port,
+            "client",
+            ZmqSocketKind::Dealer,
+            Some(&auth),
+        )
+        .unwrap();
+        let handle = thread::spawn(move || {
+            let msg = server.recv_multipart(0).unwrap();
+            server
+                .send_multipart([msg[0].as_slice(), SMSPEC_JSON.as_bytes()], 0)
+                .unwrap();
+        });
+        let (summary, _) = conn.init().unwrap();
+        assert_eq!(summary.n_items(), 2);
+        handle.join().unwrap();
+
+        // The server drops a client that expects another server key.
+        let (_server, port, _) = curve_server(&ctx);
+        let wrong_key = zmq::z85_encode(&zmq::CurveKeyPair::new().unwrap().public_key).unwrap();
+        let conn = ZmqConnection::new(
+            "127.0.0.1",
+            port,
+            "client",
+            ZmqSocketKind::Dealer,
+            Some(&client_auth(wrong_key)),
+        )
+        .unwrap();
+        assert!(matches!(conn.init(), Err(EclairError::ZeroMqAuthFailed(_))));
+
+        // Malformed keys are rejected before connecting.
+        let mut auth = client_auth(String::new());
+        auth.server_public_key = "not a key".to_owned();
+        assert!(matches!(
+            ZmqConnection::new(
+                "127.0.0.1",
+                port,
+                "client",
+                ZmqSocketKind::Dealer,
+                Some(&auth)
+            ),
+            Err(EclairError::ZeroMqAuthFailed(_))
+        ));
+    }
+
+    /// Bind a PUB server socket on the port, or a random one if None. Returns the socket and port.
+    fn pub_server(ctx: &zmq::Context, port: Option<i32>) -> (zmq::Socket, i32) {
+        let sock = ctx.socket(zmq::PUB).unwrap();
+        sock.set_linger(0).unwrap();
+        match port {
+            Some(port) => sock.bind(&format!("tcp://127.0.0.1:{}", port)).unwrap(),
+            None => sock.bind("tcp://127.0.0.1:*").unwrap(),
+        }
+
+        let endpoint = sock.get_last_endpoint().unwrap().unwrap();
+        let port = endpoint.rsplit(':').next().unwrap().parse().unwrap();
+        (sock, port)
+    }
+
+    /// Keep publishing the frames under the topic until told to stop, so that a subscriber that is
+    /// still joining gets them too.
+    fn publish_until_stopped(
+        sock: zmq::Socket,
+        frames: Vec<Vec<u8>>,
+        stop_rcv: Receiver<()>,
+    ) -> thread::JoinHandle<zmq::Socket> {
+        thread::spawn(move || {
+            while stop_rcv.try_recv().is_err() {
+                let mut msg = vec![b"case".to_vec()];
+                msg.extend(frames.iter().cloned());
+                sock.send_multipart(msg, 0).unwrap();
+                thread::sleep(Duration::from_millis(10));
+            }
+            sock
+        })
+    }
+
+    /// Wait for the flag to take the value. Returns whether it did in time.
+    fn wait_for_flag(flag: &AtomicBool, value: bool) -> bool {
+        let start = Instant::now();
+        while flag.load(Ordering::Relaxed) != value {
+            if start.elapsed() > Duration::from_secs(10) {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        true
+    }
+
+    #[test]
+    fn sub_reconnect() {
+        let ctx = zmq::Context::new();
+        let (server, port) = pub_server(&ctx, None);
+        let kind = ZmqSocketKind::Sub {
+            topic: "case".to_owned(),
+        };
+        let policy = ReconnectPolicy {
+            interval: Duration::from_millis(10),
+            max_interval: Duration::from_millis(10),
+            max_attempts: None,
+        };
+        let conn = ZmqConnection::new("127.0.0.1", port, "client", kind, None)
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(10))
+            .with_reconnect_policy(policy)
+            .unwrap();
+
+        let (stop_snd, stop_rcv) = crossbeam_channel::bounded(1);
+        let publisher =
+            publish_until_stopped(server, vec![SMSPEC_JSON.as_bytes().to_vec()], stop_rcv);
+        let (_, mut updater) = conn.init().unwrap();
+        stop_snd.send(()).unwrap();
+        drop(publisher.join().unwrap());
+
+        let connected = updater.connection_flag().unwrap();
+        let (data_snd, data_rcv) = crossbeam_channel::bounded(10);
+        let (term_snd, term_rcv) = crossbeam_channel::bounded(1);
+        let handle = thread::spawn(move || updater.update(data_snd, term_rcv));
+        assert!(wait_for_flag(&connected, false));
+
+        // The restarted server broadcasts the first time iteration.
+        let (server, _) = pub_server(&ctx, Some(port));
+        let params: Vec<u8> = [1.0f32, 100.0]
+            .iter()
+            .flat_map(|value| value.to_be_bytes().to_vec())
+            .collect();
+        let (stop_snd, stop_rcv) = crossbeam_channel::bounded(1);
+        let publisher =
+            publish_until_stopped(server, vec![0i32.to_be_bytes().to_vec(), params], stop_rcv);
+
+        assert!(wait_for_flag(&connected, true));
+        let received = data_rcv.recv_timeout(Duration::from_secs(10));
+        stop_snd.send(()).unwrap();
+        publisher.join().unwrap();
+        assert_eq!(received.unwrap(), Params::F32(vec![1.0, 100.0]));
+
+        term_snd.send(true).unwrap();
+        assert!(handle.join().unwrap().is_ok());
+    }
+}